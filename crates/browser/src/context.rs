@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use headless_chrome::browser::context::Context;
+use headless_chrome::Tab;
+use tracing::{debug, warn};
+
+use crate::{Browser, BrowserError};
+
+/// An isolated browsing context, like an incognito window: its tabs share
+/// cookies, storage and cache with each other but not with the default
+/// context or any other `BrowserContext`.
+///
+/// Dropping it closes its tabs. headless_chrome doesn't expose
+/// `Target.disposeBrowserContext`, so the empty context itself lives until
+/// the browser exits; nothing can reach its cookies after the drop.
+pub struct BrowserContext<'a> {
+    browser: &'a Browser,
+    context: Context<'a>,
+}
+
+impl<'a> BrowserContext<'a> {
+    pub(crate) fn new(browser: &'a Browser) -> Result<Self, BrowserError> {
+        let context = browser
+            .browser
+            .new_context()
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        debug!("Created browser context {}", context.get_id());
+        Ok(Self { browser, context })
+    }
+
+    /// CDP id of the context, as reported by `Target.getBrowserContexts`.
+    pub fn id(&self) -> &str {
+        self.context.get_id()
+    }
+
+    /// Open a tab in this context, set up like [`Browser::get_tab`].
+    pub fn get_tab(&self) -> Result<Arc<Tab>, BrowserError> {
+        let tab = self
+            .context
+            .new_tab()
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        self.browser.prepare_tab(&tab)?;
+        Ok(tab)
+    }
+
+    /// Tabs opened in this context so far.
+    pub fn tabs(&self) -> Result<Vec<Arc<Tab>>, BrowserError> {
+        self.context
+            .get_tabs()
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))
+    }
+}
+
+impl Drop for BrowserContext<'_> {
+    fn drop(&mut self) {
+        let tabs = match self.tabs() {
+            Ok(tabs) => tabs,
+            Err(e) => {
+                warn!("Failed to list tabs of browser context {}: {}", self.id(), e);
+                return;
+            }
+        };
+        for tab in tabs {
+            self.browser.forget_tab(&tab);
+            if let Err(e) = tab.close(false) {
+                warn!("Failed to close tab in browser context {}: {}", self.id(), e);
+            }
+        }
+        debug!("Closed browser context {}", self.id());
+    }
+}
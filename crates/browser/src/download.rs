@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use headless_chrome::browser::tab::EventListener;
+use headless_chrome::protocol::cdp::types::Event;
+use headless_chrome::protocol::cdp::Page;
+use headless_chrome::Tab;
+use serde::{Deserialize, Serialize};
+
+/// How long to wait for a download to be announced after a navigation
+/// that didn't produce a page.
+pub const DOWNLOAD_DETECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A file download started by navigating to a URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Download {
+    pub url: String,
+    pub suggested_filename: String,
+    /// Where the file is saved; `None` when downloads are denied.
+    pub path: Option<PathBuf>,
+}
+
+/// Applies the tab's download behaviour and collects the downloads it
+/// starts from `Page.downloadWillBegin` events.
+#[derive(Clone)]
+pub(crate) struct DownloadWatcher {
+    started: Arc<Mutex<Vec<Download>>>,
+}
+
+impl DownloadWatcher {
+    /// Save downloads into `dir`, or deny them when `None`.
+    pub(crate) fn start(tab: &Arc<Tab>, dir: Option<&Path>) -> anyhow::Result<Self> {
+        let behavior = match dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Page::SetDownloadBehaviorBehaviorOption::Allow
+            }
+            None => Page::SetDownloadBehaviorBehaviorOption::Deny,
+        };
+        tab.call_method(Page::SetDownloadBehavior {
+            behavior,
+            download_path: dir.map(|dir| dir.to_string_lossy().into_owned()),
+        })?;
+
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let listener_started = started.clone();
+        let dir = dir.map(Path::to_path_buf);
+        let listener: Arc<dyn EventListener<Event> + Send + Sync> = Arc::new(move |event: &Event| {
+            if let Event::PageDownloadWillBegin(e) = event {
+                let p = &e.params;
+                listener_started.lock().unwrap().push(Download {
+                    url: p.url.clone(),
+                    suggested_filename: p.suggested_filename.clone(),
+                    path: dir.as_ref().map(|dir| dir.join(&p.suggested_filename)),
+                });
+            }
+        });
+        tab.add_event_listener(listener)?;
+
+        Ok(Self { started })
+    }
+
+    /// Number of downloads seen so far, to pass to [`Self::wait_since`].
+    pub(crate) fn count(&self) -> usize {
+        self.started.lock().unwrap().len()
+    }
+
+    /// The first download started after `count` downloads had been seen,
+    /// waiting up to `timeout` for one to be announced.
+    pub(crate) fn wait_since(&self, count: usize, timeout: Duration) -> Option<Download> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(download) = self.started.lock().unwrap().get(count) {
+                return Some(download.clone());
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+
+use headless_chrome::browser::tab::EventListener;
+use headless_chrome::protocol::cdp::types::Event;
+use headless_chrome::protocol::cdp::Network;
+use headless_chrome::Tab;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Largest response body stored per entry; longer text bodies are truncated
+/// and larger binary bodies are dropped.
+pub const MAX_BODY_BYTES: usize = 256 * 1024;
+/// Total response body bytes kept for one capture.
+pub const MAX_TOTAL_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// A HAR 1.2 archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: serde_json::Value,
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u32,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+/// In-flight request state, keyed by CDP request id.
+#[derive(Default)]
+struct PendingEntry {
+    request: Option<Network::Request>,
+    wall_time: f64,
+    request_ts: f64,
+    response: Option<Network::Response>,
+    response_ts: Option<f64>,
+    finished_ts: Option<f64>,
+    encoded_length: f64,
+    error: Option<String>,
+    body: Option<HarContentBody>,
+}
+
+struct HarContentBody {
+    text: String,
+    base64: bool,
+    truncated: bool,
+}
+
+#[derive(Default)]
+struct CaptureState {
+    pending: HashMap<String, PendingEntry>,
+    /// Request ids in the order they were first seen.
+    order: Vec<String>,
+    /// Entries closed by a redirect, keyed by position in `order`.
+    redirects: Vec<(usize, PendingEntry)>,
+    body_bytes: usize,
+}
+
+/// A running HAR capture attached to one tab. Response bodies are fetched
+/// on a worker thread because CDP calls can't be made from the event
+/// handler thread.
+pub(crate) struct HarCapture {
+    state: Arc<Mutex<CaptureState>>,
+    listener: Weak<dyn EventListener<Event> + Send + Sync>,
+    bodies_tx: Sender<String>,
+    worker: JoinHandle<()>,
+}
+
+impl HarCapture {
+    pub(crate) fn start(tab: &Arc<Tab>) -> anyhow::Result<Self> {
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })?;
+
+        let state = Arc::new(Mutex::new(CaptureState::default()));
+        let (bodies_tx, bodies_rx) = channel::<String>();
+
+        let worker_state = state.clone();
+        let worker_tab = Arc::downgrade(tab);
+        let worker = std::thread::spawn(move || {
+            for request_id in bodies_rx {
+                let Some(tab) = worker_tab.upgrade() else { break };
+                match tab.call_method(Network::GetResponseBody { request_id: request_id.clone() }) {
+                    Ok(body) => store_body(&worker_state, &request_id, body.body, body.base_64_encoded),
+                    Err(e) => debug!("No body for request {}: {}", request_id, e),
+                }
+            }
+        });
+
+        let listener_state = state.clone();
+        let listener_tx = bodies_tx.clone();
+        let listener: Arc<dyn EventListener<Event> + Send + Sync> = Arc::new(move |event: &Event| {
+            let mut state = listener_state.lock().unwrap();
+            match event {
+                Event::NetworkRequestWillBeSent(e) => {
+                    let p = &e.params;
+                    if let Some(ref redirect) = p.redirect_response {
+                        // The same request id is reused across a redirect chain.
+                        if let Some(mut previous) = state.pending.remove(&p.request_id) {
+                            previous.response = Some(redirect.clone());
+                            previous.response_ts = Some(p.timestamp);
+                            previous.finished_ts = Some(p.timestamp);
+                            let position = state.order.iter().position(|id| id == &p.request_id).unwrap_or(0);
+                            state.redirects.push((position, previous));
+                        }
+                    } else {
+                        state.order.push(p.request_id.clone());
+                    }
+                    state.pending.insert(
+                        p.request_id.clone(),
+                        PendingEntry {
+                            request: Some(p.request.clone()),
+                            wall_time: p.wall_time,
+                            request_ts: p.timestamp,
+                            ..PendingEntry::default()
+                        },
+                    );
+                }
+                Event::NetworkResponseReceived(e) => {
+                    if let Some(entry) = state.pending.get_mut(&e.params.request_id) {
+                        entry.response = Some(e.params.response.clone());
+                        entry.response_ts = Some(e.params.timestamp);
+                    }
+                }
+                Event::NetworkLoadingFinished(e) => {
+                    if let Some(entry) = state.pending.get_mut(&e.params.request_id) {
+                        entry.finished_ts = Some(e.params.timestamp);
+                        entry.encoded_length = e.params.encoded_data_length;
+                        let _ = listener_tx.send(e.params.request_id.clone());
+                    }
+                }
+                Event::NetworkLoadingFailed(e) => {
+                    if let Some(entry) = state.pending.get_mut(&e.params.request_id) {
+                        entry.finished_ts = Some(e.params.timestamp);
+                        entry.error = Some(e.params.error_text.clone());
+                    }
+                }
+                _ => {}
+            }
+        });
+        let listener = tab.add_event_listener(listener)?;
+
+        Ok(Self { state, listener, bodies_tx, worker })
+    }
+
+    pub(crate) fn finish(self, tab: &Arc<Tab>) -> Har {
+        if let Err(e) = tab.remove_event_listener(&self.listener) {
+            warn!("Failed to remove HAR listener: {}", e);
+        }
+        // Let the worker drain queued body requests before building entries.
+        drop(self.bodies_tx);
+        if self.worker.join().is_err() {
+            warn!("HAR body worker panicked");
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let mut entries: Vec<(usize, PendingEntry)> = std::mem::take(&mut state.redirects);
+        let order = std::mem::take(&mut state.order);
+        for (position, id) in order.iter().enumerate() {
+            if let Some(entry) = state.pending.remove(id) {
+                entries.push((position, entry));
+            }
+        }
+        // Stable sort keeps redirect hops ahead of their final request.
+        entries.sort_by_key(|(position, _)| *position);
+
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: "SiteRecorder".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: entries.into_iter().filter_map(|(_, e)| to_har_entry(e)).collect(),
+            },
+        }
+    }
+}
+
+fn store_body(state: &Mutex<CaptureState>, request_id: &str, body: String, base64: bool) {
+    let mut state = state.lock().unwrap();
+    if state.body_bytes >= MAX_TOTAL_BODY_BYTES {
+        return;
+    }
+    let (text, truncated) = if body.len() <= MAX_BODY_BYTES {
+        (body, false)
+    } else if base64 {
+        // A cut base64 payload can't be decoded, so keep nothing.
+        return;
+    } else {
+        let mut end = MAX_BODY_BYTES;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        (body[..end].to_string(), true)
+    };
+    state.body_bytes += text.len();
+    if let Some(entry) = state.pending.get_mut(request_id) {
+        entry.body = Some(HarContentBody { text, base64, truncated });
+    }
+}
+
+fn headers_to_har(headers: &Network::Headers) -> Vec<HarNameValue> {
+    match headers.0 {
+        Some(serde_json::Value::Object(ref map)) => map
+            .iter()
+            .map(|(name, value)| HarNameValue {
+                name: name.clone(),
+                value: value.as_str().map(|s| s.to_string()).unwrap_or_else(|| value.to_string()),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    url::Url::parse(url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(name, value)| HarNameValue { name: name.into_owned(), value: value.into_owned() })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn header_value(headers: &[HarNameValue], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.clone())
+}
+
+fn to_har_entry(entry: PendingEntry) -> Option<HarEntry> {
+    let request = entry.request?;
+    let started = chrono::DateTime::<chrono::Utc>::from_timestamp_millis((entry.wall_time * 1000.0) as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    let request_headers = headers_to_har(&request.headers);
+    let post_data = request.post_data.clone().map(|text| HarPostData {
+        mime_type: header_value(&request_headers, "content-type").unwrap_or_default(),
+        text,
+    });
+
+    let response_ts = entry.response_ts.unwrap_or(entry.request_ts);
+    let finished_ts = entry.finished_ts.unwrap_or(response_ts);
+    let wait = ((response_ts - entry.request_ts) * 1000.0).max(0.0);
+    let receive = ((finished_ts - response_ts) * 1000.0).max(0.0);
+
+    let http_version = entry
+        .response
+        .as_ref()
+        .and_then(|r| r.protocol.clone())
+        .unwrap_or_else(|| "HTTP/1.1".to_string());
+
+    let response = match entry.response {
+        Some(ref r) => {
+            let headers = headers_to_har(&r.headers);
+            let (text, encoding, comment) = match entry.body {
+                Some(body) => (
+                    Some(body.text),
+                    body.base64.then(|| "base64".to_string()),
+                    body.truncated.then(|| format!("Body truncated to {} bytes", MAX_BODY_BYTES)),
+                ),
+                None => (None, None, None),
+            };
+            HarResponse {
+                status: r.status,
+                status_text: r.status_text.clone(),
+                http_version: http_version.clone(),
+                cookies: Vec::new(),
+                redirect_url: header_value(&headers, "location").unwrap_or_default(),
+                headers,
+                content: HarContent {
+                    size: entry.encoded_length as i64,
+                    mime_type: r.mime_type.clone(),
+                    text,
+                    encoding,
+                    comment,
+                },
+                headers_size: -1,
+                body_size: entry.encoded_length as i64,
+                error: entry.error,
+            }
+        }
+        None => HarResponse {
+            status: 0,
+            status_text: String::new(),
+            http_version: http_version.clone(),
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            content: HarContent {
+                size: 0,
+                mime_type: String::new(),
+                text: None,
+                encoding: None,
+                comment: None,
+            },
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: -1,
+            error: entry.error,
+        },
+    };
+
+    Some(HarEntry {
+        started_date_time: started,
+        time: wait + receive,
+        request: HarRequest {
+            method: request.method.clone(),
+            query_string: query_string(&request.url),
+            url: request.url,
+            http_version,
+            cookies: Vec::new(),
+            headers: request_headers,
+            body_size: post_data.as_ref().map(|p| p.text.len() as i64).unwrap_or(0),
+            post_data,
+            headers_size: -1,
+        },
+        response,
+        cache: serde_json::json!({}),
+        timings: HarTimings { send: 0.0, wait, receive },
+        server_ip_address: entry.response.and_then(|r| r.remote_ip_address),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_body_caps_size() {
+        let state = Mutex::new(CaptureState::default());
+        state.lock().unwrap().pending.insert("1".to_string(), PendingEntry::default());
+        state.lock().unwrap().pending.insert("2".to_string(), PendingEntry::default());
+
+        store_body(&state, "1", "a".repeat(MAX_BODY_BYTES + 10), false);
+        store_body(&state, "2", "b".repeat(MAX_BODY_BYTES + 10), true);
+
+        let state = state.lock().unwrap();
+        let body = state.pending["1"].body.as_ref().unwrap();
+        assert_eq!(body.text.len(), MAX_BODY_BYTES);
+        assert!(body.truncated);
+        assert!(state.pending["2"].body.is_none());
+        assert_eq!(state.body_bytes, MAX_BODY_BYTES);
+    }
+
+    #[test]
+    fn test_query_string() {
+        let pairs = query_string("https://example.com/search?q=rust&page=2");
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].name, "q");
+        assert_eq!(pairs[1].value, "2");
+    }
+}
@@ -1,11 +1,32 @@
 use anyhow::Result;
+use base64::Engine as _;
 use headless_chrome::Browser as ChromeBrowser;
+use headless_chrome::protocol::cdp::Browser::{GrantPermissions, PermissionType, WindowState as BrowserWindowState};
+use headless_chrome::protocol::cdp::Emulation;
+use headless_chrome::protocol::cdp::{Page, Runtime, Security};
+use headless_chrome::types::Bounds;
 use headless_chrome::{LaunchOptions, Tab};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, info, warn};
+
+pub mod context;
+pub use context::BrowserContext;
+pub mod download;
+pub use download::Download;
+pub mod har;
+pub use har::Har;
+pub mod response;
+pub use response::DocumentResponse;
+pub mod processor;
+pub use processor::{run_processors, MetaDescriptionExtractor, PageMeta, PageProcessor, TitleExtractor};
 
 #[derive(Debug, Error)]
 pub enum BrowserError {
@@ -15,15 +36,80 @@ pub enum BrowserError {
     NavigationError(String),
     #[error("Timeout error: {0}")]
     Timeout(String),
+    #[error("No element matches selector: {0}")]
+    ElementNotFound(String),
+    /// The URL is a file download rather than a page.
+    #[error("Navigation started a download of {}", .0.url)]
+    Download(Download),
+    /// The server answered 429 Too Many Requests.
+    #[error("{url} is rate limited (HTTP 429)")]
+    RateLimited {
+        url: String,
+        /// The `Retry-After` header: seconds or an HTTP date.
+        retry_after: Option<String>,
+    },
+    #[error("Navigation failed after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<BrowserError>,
+    },
     #[error("Browser error: {0}")]
     BrowserError(#[from] anyhow::Error),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationOptions {
+    /// How long a page may take to load before the load is stopped and
+    /// [`BrowserError::Timeout`] returned.
     pub timeout_ms: u64,
     pub wait_for_idle: bool,
     pub scroll_behavior: ScrollBehavior,
+    /// Treat the page as loaded only once its body text contains this, for
+    /// pages that render their content after the load event.
+    #[serde(default)]
+    pub wait_for_text: Option<String>,
+    /// JavaScript run at the start of every document, before the page's own
+    /// scripts (e.g. to stub out autoplay or add instrumentation).
+    #[serde(default)]
+    pub pre_nav_script: Option<String>,
+    /// JavaScript run once the page has loaded, before scrolling (e.g. to
+    /// hide sticky headers or cookie banners).
+    #[serde(default)]
+    pub post_nav_script: Option<String>,
+    /// Fail with [`BrowserError::RateLimited`] when the page is served
+    /// with status 429 instead of treating it as loaded.
+    #[serde(default = "default_detect_rate_limits")]
+    pub detect_rate_limits: bool,
+    /// The first time each host is loaded, click its cookie consent or age
+    /// gate "accept" button so the resulting cookie spares later pages.
+    #[serde(default)]
+    pub warm_up: bool,
+    /// Pause after the page loads, before it is processed, for late
+    /// layout and scripts to settle.
+    #[serde(default = "default_post_nav_settle_ms")]
+    pub post_nav_settle_ms: u64,
+    /// Pause after closing modal dialogs, for close animations to finish.
+    #[serde(default = "default_post_modal_settle_ms")]
+    pub post_modal_settle_ms: u64,
+}
+
+/// Default for [`NavigationOptions::post_nav_settle_ms`].
+pub const DEFAULT_POST_NAV_SETTLE_MS: u64 = 1000;
+
+/// Default for [`NavigationOptions::post_modal_settle_ms`].
+pub const DEFAULT_POST_MODAL_SETTLE_MS: u64 = 500;
+
+fn default_detect_rate_limits() -> bool {
+    true
+}
+
+fn default_post_nav_settle_ms() -> u64 {
+    DEFAULT_POST_NAV_SETTLE_MS
+}
+
+fn default_post_modal_settle_ms() -> u64 {
+    DEFAULT_POST_MODAL_SETTLE_MS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +117,37 @@ pub enum ScrollBehavior {
     None,
     ToBottom,
     Incremental { steps: u32, delay_ms: u64 },
+    /// Eased scroll to the bottom over `duration_ms`, for watchable
+    /// recordings. With `pause_at_sections` the page is scrolled one
+    /// viewport at a time, holding briefly at each.
+    Smooth { duration_ms: u64, pause_at_sections: bool },
+}
+
+/// Interval between scroll position updates in [`ScrollBehavior::Smooth`].
+const SMOOTH_SCROLL_STEP_MS: u64 = 40;
+
+/// How long [`ScrollBehavior::Smooth`] holds at each viewport boundary.
+const SECTION_PAUSE_MS: u64 = 800;
+
+/// Extra time the tab's own load wait gets beyond
+/// [`NavigationOptions::timeout_ms`], so a timed-out load is stopped
+/// before that wait gives up.
+const NAVIGATION_STOP_GRACE: Duration = Duration::from_secs(2);
+
+/// How often [`Browser::wait_for_text`] checks the page.
+const TEXT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Eased (ease-in-out cubic) scroll offsets from `from` to `to` in `steps`
+/// updates, ending exactly at `to`.
+fn eased_positions(from: f64, to: f64, steps: u32) -> Vec<f64> {
+    let steps = steps.max(1);
+    (1..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let eased = if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 };
+            from + (to - from) * eased
+        })
+        .collect()
 }
 
 impl Default for NavigationOptions {
@@ -42,66 +159,484 @@ impl Default for NavigationOptions {
                 steps: 5,
                 delay_ms: 500,
             },
+            wait_for_text: None,
+            pre_nav_script: None,
+            post_nav_script: None,
+            detect_rate_limits: true,
+            warm_up: false,
+            post_nav_settle_ms: DEFAULT_POST_NAV_SETTLE_MS,
+            post_modal_settle_ms: DEFAULT_POST_MODAL_SETTLE_MS,
+        }
+    }
+}
+
+/// Exponential backoff for [`Browser::navigate_with_retry`]. The delay before
+/// retry `n` is `base_delay_ms * 2^(n-1)`, capped at `max_delay_ms`; with
+/// `jitter` a random delay between half and all of that is used instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total navigation attempts, including the first.
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 1000,
+            max_delay_ms: 30000,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Upper bound of the delay before retry number `retry` (1-based).
+    pub fn max_delay_for(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        Duration::from_millis(self.base_delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+
+    /// Delay to sleep before retry number `retry` (1-based).
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let max = self.max_delay_for(retry).as_millis() as u64;
+        if self.jitter && max > 0 {
+            Duration::from_millis(fastrand::u64(max / 2..=max))
+        } else {
+            Duration::from_millis(max)
+        }
+    }
+}
+
+/// Request headers the browser sets itself. Chrome refuses or overrides
+/// extra values for them.
+const FORBIDDEN_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "expect",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Chrome flags that [`BrowserConfig`] sets itself or that would hand the
+/// browser to other processes or turn off its security checks.
+const REFUSED_CHROME_ARGS: &[&str] = &[
+    // Set from the config or by headless_chrome
+    "--headless",
+    "--user-data-dir",
+    "--remote-debugging-port",
+    "--remote-debugging-pipe",
+    "--remote-debugging-address",
+    "--window-size",
+    "--window-position",
+    // Run commands, expose the browser or drop its protections
+    "--remote-allow-origins",
+    "--renderer-cmd-prefix",
+    "--gpu-launcher",
+    "--utility-cmd-prefix",
+    "--browser-subprocess-path",
+    "--no-sandbox",
+    "--disable-web-security",
+];
+
+/// Check that `arg` is a single `--flag` or `--flag=value` Chrome switch
+/// that isn't modeled by [`BrowserConfig`] or unsafe to pass through.
+pub fn validate_chrome_arg(arg: &str) -> Result<(), String> {
+    let Some(name) = arg.strip_prefix("--") else {
+        return Err(format!("Chrome argument {:?} must start with --", arg));
+    };
+    let name = name.split('=').next().unwrap_or_default();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("{:?} is not a Chrome switch", arg));
+    }
+    if arg.chars().any(|c| c.is_control()) {
+        return Err(format!("Chrome argument {:?} contains a control character", arg));
+    }
+    if REFUSED_CHROME_ARGS.iter().any(|refused| refused[2..].eq_ignore_ascii_case(name)) {
+        return Err(format!("--{} is set by SiteRecorder or unsafe and can't be passed through", name));
+    }
+    Ok(())
+}
+
+/// Check that `name: value` is a well-formed HTTP request header.
+pub fn validate_header(name: &str, value: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Header name is empty".to_string());
+    }
+    // RFC 9110 token characters
+    if let Some(c) = name.chars().find(|c| !c.is_ascii_alphanumeric() && !"!#$%&'*+-.^_`|~".contains(*c)) {
+        return Err(format!("Header name {:?} contains invalid character {:?}", name, c));
+    }
+    if value.chars().any(|c| c == '\r' || c == '\n' || c == '\0') {
+        return Err(format!("Value of header {:?} contains a line break or NUL", name));
+    }
+    Ok(())
+}
+
+/// Whether Chrome controls header `name` itself, so an extra value for it
+/// would be refused or ignored.
+pub fn is_forbidden_header(name: &str) -> bool {
+    FORBIDDEN_HEADERS.iter().any(|forbidden| forbidden.eq_ignore_ascii_case(name))
+}
+
+/// Value emulated for the `prefers-color-scheme` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
         }
     }
 }
 
 pub struct Browser {
     browser: ChromeBrowser,
+    har: Mutex<Option<har::HarCapture>>,
+    /// Download watchers by tab target ID.
+    downloads: Mutex<HashMap<String, download::DownloadWatcher>>,
+    /// Main document responses by tab target ID, for rate limit detection.
+    responses: Mutex<HashMap<String, response::ResponseWatcher>>,
+    /// Init script source and its CDP identifier, by tab target ID.
+    init_scripts: Mutex<HashMap<String, (String, String)>>,
+    /// Hosts whose consent prompts [`Browser::warm_up`] already handled.
+    warmed_hosts: Mutex<HashSet<String>>,
+    ignore_cert_errors: bool,
+    minimized: bool,
+}
+
+/// Browser window size used when no viewport is given.
+pub const DEFAULT_WINDOW_SIZE: (u32, u32) = (1920, 1080);
+/// How long the browser may go without receiving a command before it is
+/// shut down.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Consecutive page delays the idle timeout should cover.
+const IDLE_TIMEOUT_DELAY_FACTOR: u32 = 4;
+
+/// Whether Chrome shows a window, and which headless implementation it
+/// uses when not. Rendering, screenshots and some JavaScript APIs differ
+/// between the legacy and new implementations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadlessMode {
+    /// A visible window.
+    #[default]
+    Disabled,
+    /// Chrome's original headless mode (`--headless`).
+    Legacy,
+    /// The headless mode built on the regular browser (`--headless=new`).
+    New,
+}
+
+impl HeadlessMode {
+    pub fn is_headless(self) -> bool {
+        self != HeadlessMode::Disabled
+    }
+
+    /// Launch arguments selecting this mode beyond what
+    /// `LaunchOptions::headless` sets.
+    fn launch_args(self) -> Option<&'static str> {
+        match self {
+            HeadlessMode::New => Some("--headless=new"),
+            HeadlessMode::Disabled | HeadlessMode::Legacy => None,
+        }
+    }
+}
+
+impl From<bool> for HeadlessMode {
+    fn from(headless: bool) -> Self {
+        if headless {
+            HeadlessMode::Legacy
+        } else {
+            HeadlessMode::Disabled
+        }
+    }
+}
+
+/// Launch settings for [`Browser::with_config`].
+#[derive(Debug, Clone)]
+pub struct BrowserConfig {
+    pub headless: HeadlessMode,
+    /// The browser window, not the screen area FFmpeg captures.
+    pub window_size: (u32, u32),
+    pub idle_timeout: Duration,
+    /// Load pages despite invalid TLS certificates (self-signed, expired,
+    /// wrong host). Only for sites you trust, such as staging servers.
+    pub ignore_cert_errors: bool,
+    /// Chrome profile directory to keep logins, cookies and cache between
+    /// runs. `None` uses a throwaway profile.
+    pub user_data_dir: Option<PathBuf>,
+    /// Screen position of the window's top-left corner. May be off-screen
+    /// (e.g. negative) to keep a visible browser out of the way; pages still
+    /// render for browser-mode screenshots, but a desktop screen grab won't
+    /// see them.
+    pub window_position: Option<(i32, i32)>,
+    /// Minimize the window in every tab opened. Like an off-screen
+    /// position, this hides pages from desktop screen grabs.
+    pub minimized: bool,
+    /// Chrome switches passed through as-is, for flags this config doesn't
+    /// model. Each must pass [`validate_chrome_arg`].
+    pub extra_chrome_args: Vec<String>,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            headless: HeadlessMode::Disabled,
+            window_size: DEFAULT_WINDOW_SIZE,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            ignore_cert_errors: false,
+            user_data_dir: None,
+            window_position: None,
+            minimized: false,
+            extra_chrome_args: Vec::new(),
+        }
+    }
+}
+
+impl BrowserConfig {
+    /// An idle timeout for a crawl that waits `delay_ms` between pages:
+    /// [`DEFAULT_IDLE_TIMEOUT`], or several delays if that's longer, so
+    /// slow, polite crawls don't lose the browser between pages.
+    pub fn idle_timeout_for_delay(delay_ms: u64) -> Duration {
+        DEFAULT_IDLE_TIMEOUT.max(Duration::from_millis(delay_ms) * IDLE_TIMEOUT_DELAY_FACTOR)
+    }
 }
 
 impl Browser {
     pub fn new() -> Result<Self, BrowserError> {
-        let launch_options = LaunchOptions::default_builder()
-            .headless(false)
-            .window_size(Some((1920, 1080)))
-            .idle_browser_timeout(Duration::from_secs(300))
-            .build()
-            .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
+        Self::launch(false, DEFAULT_WINDOW_SIZE)
+    }
 
-        let browser = ChromeBrowser::new(launch_options)
-            .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
+    pub fn new_headless() -> Result<Self, BrowserError> {
+        Self::launch(true, DEFAULT_WINDOW_SIZE)
+    }
 
-        info!("Browser launched successfully");
-        Ok(Self { browser })
+    /// Launch with an explicit window size. This is the browser window, not
+    /// the screen area FFmpeg captures.
+    pub fn launch(headless: bool, window_size: (u32, u32)) -> Result<Self, BrowserError> {
+        Self::with_config(&BrowserConfig {
+            headless: headless.into(),
+            window_size,
+            ..BrowserConfig::default()
+        })
     }
 
-    pub fn new_headless() -> Result<Self, BrowserError> {
+    pub fn with_config(config: &BrowserConfig) -> Result<Self, BrowserError> {
+        let headless = config.headless.is_headless();
+        if let Some(ref dir) = config.user_data_dir {
+            // Chrome refuses to share a profile between running instances
+            if dir.join("SingletonLock").symlink_metadata().is_ok() {
+                warn!("Chrome profile {:?} looks in use by another browser; launching may fail", dir);
+            }
+            info!("Using Chrome profile: {:?}", dir);
+        }
+        // Chrome accepts negative positions on the command line, unlike
+        // CDP's window bounds
+        let window_position = config.window_position.map(|(x, y)| format!("--window-position={},{}", x, y));
+        for arg in &config.extra_chrome_args {
+            validate_chrome_arg(arg).map_err(BrowserError::LaunchFailed)?;
+        }
+        if !config.extra_chrome_args.is_empty() {
+            info!("Extra Chrome arguments: {}", config.extra_chrome_args.join(" "));
+        }
+        let args = window_position
+            .iter()
+            .map(OsStr::new)
+            .chain(config.headless.launch_args().map(OsStr::new))
+            .chain(config.extra_chrome_args.iter().map(OsStr::new))
+            .collect();
+        // `headless(true)` adds the legacy `--headless` flag, which would
+        // override `--headless=new`
         let launch_options = LaunchOptions::default_builder()
-            .headless(true)
-            .window_size(Some((1920, 1080)))
-            .idle_browser_timeout(Duration::from_secs(300))
+            .headless(config.headless == HeadlessMode::Legacy)
+            .window_size(Some(config.window_size))
+            .idle_browser_timeout(config.idle_timeout)
+            .ignore_certificate_errors(config.ignore_cert_errors)
+            .user_data_dir(config.user_data_dir.clone())
+            .args(args)
             .build()
             .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
 
         let browser = ChromeBrowser::new(launch_options)
             .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
 
-        info!("Headless browser launched successfully");
-        Ok(Self { browser })
+        if headless {
+            info!("Headless browser launched successfully");
+        } else {
+            info!("Browser launched successfully");
+        }
+        Ok(Self {
+            browser,
+            har: Mutex::new(None),
+            downloads: Mutex::new(HashMap::new()),
+            responses: Mutex::new(HashMap::new()),
+            init_scripts: Mutex::new(HashMap::new()),
+            warmed_hosts: Mutex::new(HashSet::new()),
+            ignore_cert_errors: config.ignore_cert_errors,
+            minimized: config.minimized && !headless,
+        })
+    }
+
+    /// PID of the browser's main process, if this crate launched it.
+    pub fn process_id(&self) -> Option<u32> {
+        self.browser.get_process_id()
     }
 
     pub fn get_tab(&self) -> Result<Arc<Tab>, BrowserError> {
-        self.browser
+        let tab = self
+            .browser
             .new_tab()
-            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        self.prepare_tab(&tab)?;
+        Ok(tab)
+    }
+
+    /// Apply browser-wide settings to a newly opened tab.
+    fn prepare_tab(&self, tab: &Arc<Tab>) -> Result<(), BrowserError> {
+        if self.ignore_cert_errors {
+            tab.call_method(Security::SetIgnoreCertificateErrors { ignore: true })
+                .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        }
+        if self.minimized {
+            tab.set_bounds(Bounds::Minimized)
+                .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        }
+        Ok(())
+    }
+
+    /// Drop per-tab download, response and init script state for a tab
+    /// being closed.
+    fn forget_tab(&self, tab: &Arc<Tab>) {
+        self.downloads.lock().unwrap().remove(tab.get_target_id());
+        self.responses.lock().unwrap().remove(tab.get_target_id());
+        self.init_scripts.lock().unwrap().remove(tab.get_target_id());
+    }
+
+    /// The tab's document response watcher, started on first use.
+    fn response_watcher(&self, tab: &Arc<Tab>) -> Result<response::ResponseWatcher, BrowserError> {
+        let mut responses = self.responses.lock().unwrap();
+        if let Some(watcher) = responses.get(tab.get_target_id()) {
+            return Ok(watcher.clone());
+        }
+        let watcher = response::ResponseWatcher::start(tab)?;
+        responses.insert(tab.get_target_id().clone(), watcher.clone());
+        Ok(watcher)
+    }
+
+    /// Create an isolated context whose tabs don't share cookies, storage
+    /// or cache with other tabs, so several sessions can be crawled side by
+    /// side without seeing each other's logins.
+    pub fn new_incognito_context(&self) -> Result<BrowserContext<'_>, BrowserError> {
+        BrowserContext::new(self)
+    }
+
+    /// Open `n` new tabs. They share the browser's cookies and session, so
+    /// pages can be loaded in parallel as the same user.
+    pub fn get_tabs(&self, n: usize) -> Result<Vec<Arc<Tab>>, BrowserError> {
+        (0..n).map(|_| self.get_tab()).collect()
     }
 
     pub fn navigate(&self, tab: &Arc<Tab>, url: &str, options: &NavigationOptions) -> Result<(), BrowserError> {
         info!("Navigating to: {}", url);
-        
-        tab.navigate_to(url)
-            .map_err(|e| BrowserError::NavigationError(e.to_string()))?;
+        let watcher = self.downloads.lock().unwrap().get(tab.get_target_id()).cloned();
+        let downloads_before = watcher.as_ref().map(|w| w.count());
+        let response = if options.detect_rate_limits {
+            let response = self.response_watcher(tab)?;
+            response.reset();
+            Some(response)
+        } else {
+            None
+        };
+        self.set_init_script(tab, options.pre_nav_script.as_deref())?;
+
+        // Enforce the timeout from a watchdog thread: stopping the load makes
+        // a hung Page.navigate return. The tab's own wait runs a little
+        // longer so the watchdog fires first.
+        let timeout = Duration::from_millis(options.timeout_ms);
+        tab.set_default_timeout(timeout + NAVIGATION_STOP_GRACE);
+        let timed_out = AtomicBool::new(false);
+        let loaded = std::thread::scope(|scope| {
+            let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+            let timed_out = &timed_out;
+            scope.spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    warn!("Navigation to {} timed out after {:?}; stopping the load", url, timeout);
+                    if let Err(e) = tab.stop_loading() {
+                        warn!("Failed to stop loading {}: {}", url, e);
+                    }
+                }
+            });
+            let loaded = tab.navigate_to(url).and_then(|tab| {
+                if options.wait_for_idle {
+                    tab.wait_until_navigated()?;
+                }
+                Ok(())
+            });
+            drop(done_tx);
+            loaded
+        });
+        // A URL served as an attachment (even an HTML one) aborts the
+        // navigation instead of loading a page; report the download rather
+        // than a failure.
+        let detect_timeout = match loaded {
+            Ok(()) => Duration::ZERO,
+            Err(_) => download::DOWNLOAD_DETECT_TIMEOUT,
+        };
+        let download = watcher.zip(downloads_before).and_then(|(w, before)| w.wait_since(before, detect_timeout));
+        if let Some(download) = download {
+            info!("Download started: {}", download.suggested_filename);
+            return Err(BrowserError::Download(download));
+        }
+        if timed_out.load(Ordering::SeqCst) {
+            return Err(BrowserError::Timeout(format!("{} did not load within {:?}", url, timeout)));
+        }
+        loaded.map_err(|e| BrowserError::NavigationError(e.to_string()))?;
+        if let Some(response) = response.and_then(|response| response.last()) {
+            if response.status == 429 {
+                return Err(BrowserError::RateLimited {
+                    url: url.to_string(),
+                    retry_after: response.retry_after,
+                });
+            }
+        }
 
-        if options.wait_for_idle {
-            tab.wait_until_navigated()
-                .map_err(|e| BrowserError::NavigationError(e.to_string()))?;
+        std::thread::sleep(Duration::from_millis(options.post_nav_settle_ms));
+
+        if let Some(ref text) = options.wait_for_text {
+            self.wait_for_text(tab, text, Duration::from_millis(options.timeout_ms))?;
         }
 
-        std::thread::sleep(Duration::from_millis(1000));
+        if options.warm_up {
+            if let Err(e) = self.warm_up(tab, url) {
+                warn!("Warm-up failed on {}: {}", url, e);
+            }
+        }
 
         // Check for and close any modal dialogs
-        self.close_modals(tab)?;
+        self.close_modals_and_settle(tab, Duration::from_millis(options.post_modal_settle_ms));
+
+        if let Some(ref script) = options.post_nav_script {
+            debug!("Running post-navigation script");
+            if let Err(e) = tab.evaluate(script, true) {
+                warn!("Post-navigation script failed on {}: {}", url, e);
+            }
+        }
 
         match &options.scroll_behavior {
             ScrollBehavior::None => {}
@@ -111,13 +646,116 @@ impl Browser {
             ScrollBehavior::Incremental { steps, delay_ms } => {
                 self.scroll_incremental(tab, *steps, *delay_ms)?;
             }
+            ScrollBehavior::Smooth {
+                duration_ms,
+                pause_at_sections,
+            } => {
+                self.scroll_smooth(tab, *duration_ms, *pause_at_sections)?;
+            }
         }
 
         debug!("Navigation complete");
         Ok(())
     }
 
+    /// Poll until the page's body text contains `text`, failing with
+    /// [`BrowserError::Timeout`] if it hasn't appeared within `timeout`.
+    pub fn wait_for_text(&self, tab: &Arc<Tab>, text: &str, timeout: Duration) -> Result<(), BrowserError> {
+        debug!("Waiting for text: {:?}", text);
+        let script = format!(
+            "(document.body?.innerText || '').includes({})",
+            serde_json::Value::from(text)
+        );
+        let deadline = Instant::now() + timeout;
+        loop {
+            let found = tab
+                .evaluate(&script, false)
+                .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?
+                .value
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false);
+            if found {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(BrowserError::Timeout(format!(
+                    "text {:?} did not appear within {:?}",
+                    text, timeout
+                )));
+            }
+            std::thread::sleep(TEXT_POLL_INTERVAL);
+        }
+    }
+
+    /// [`navigate`](Self::navigate), retrying navigation errors and timeouts
+    /// with backoff per `policy`. Other errors are returned immediately.
+    pub fn navigate_with_retry(
+        &self,
+        tab: &Arc<Tab>,
+        url: &str,
+        options: &NavigationOptions,
+        policy: &RetryPolicy,
+    ) -> Result<(), BrowserError> {
+        let max_attempts = policy.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match self.navigate(tab, url, options) {
+                Ok(()) => return Ok(()),
+                Err(e @ (BrowserError::NavigationError(_) | BrowserError::Timeout(_))) => {
+                    if attempt >= max_attempts {
+                        return Err(BrowserError::RetriesExhausted {
+                            attempts: attempt,
+                            source: Box::new(e),
+                        });
+                    }
+                    let delay = policy.delay_for(attempt);
+                    warn!("Navigation to {} failed (attempt {}/{}): {}; retrying in {:?}", url, attempt, max_attempts, e, delay);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Accept the cookie consent or age gate on `url`'s host, once per host.
+    /// The consent cookie the site sets keeps the prompt off later pages,
+    /// which only get [`close_modals`](Self::close_modals). Returns whether
+    /// a button was clicked.
+    pub fn warm_up(&self, tab: &Arc<Tab>, url: &str) -> Result<bool, BrowserError> {
+        let Some(host) = url::Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)) else {
+            return Ok(false);
+        };
+        if !self.warmed_hosts.lock().unwrap().insert(host.clone()) {
+            return Ok(false);
+        }
+
+        let clicked = tab
+            .evaluate(CONSENT_SCRIPT, false)
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?
+            .value
+            .and_then(|value| value.as_str().map(str::to_string));
+        match clicked {
+            Some(label) => {
+                info!("Warm-up on {}: clicked {:?}", host, label);
+                // Consent scripts often reload or fade out the page
+                std::thread::sleep(Duration::from_millis(1000));
+                Ok(true)
+            }
+            None => {
+                debug!("Warm-up on {}: no consent prompt found", host);
+                Ok(false)
+            }
+        }
+    }
+
     pub fn close_modals(&self, tab: &Arc<Tab>) -> Result<(), BrowserError> {
+        self.close_modals_and_settle(tab, Duration::from_millis(DEFAULT_POST_MODAL_SETTLE_MS));
+        Ok(())
+    }
+
+    /// Close modal dialogs, waiting `settle` after closing any.
+    fn close_modals_and_settle(&self, tab: &Arc<Tab>, settle: Duration) {
         debug!("Checking for modal dialogs...");
         
         let modal_script = r#"
@@ -218,8 +856,7 @@ impl Browser {
                     if let Some(num) = count.as_i64() {
                         if num > 0 {
                             info!("Closed {} modal dialog(s)", num);
-                            // Wait a bit after closing modals
-                            std::thread::sleep(Duration::from_millis(500));
+                            std::thread::sleep(settle);
                         } else {
                             debug!("No modals found");
                         }
@@ -230,8 +867,6 @@ impl Browser {
                 debug!("Modal check failed (non-critical): {}", e);
             }
         }
-
-        Ok(())
     }
 
     pub fn scroll_to_bottom(&self, tab: &Arc<Tab>) -> Result<(), BrowserError> {
@@ -254,6 +889,45 @@ impl Browser {
         Ok(())
     }
 
+    pub fn scroll_smooth(&self, tab: &Arc<Tab>, duration_ms: u64, pause_at_sections: bool) -> Result<(), BrowserError> {
+        let number = |script: &str| -> Result<f64, BrowserError> {
+            let result = tab
+                .evaluate(script, false)
+                .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+            Ok(result.value.and_then(|v| v.as_f64()).unwrap_or(0.0))
+        };
+        let viewport = number("window.innerHeight")?.max(1.0);
+        let max_scroll = number("document.documentElement.scrollHeight - window.innerHeight")?;
+        let mut position = number("window.scrollY")?;
+        if max_scroll <= position {
+            return Ok(());
+        }
+
+        let sections = if pause_at_sections {
+            ((max_scroll - position) / viewport).ceil().max(1.0) as u64
+        } else {
+            1
+        };
+        let steps_per_section = (duration_ms / sections / SMOOTH_SCROLL_STEP_MS).max(1) as u32;
+        for section in 1..=sections {
+            let target = if section == sections {
+                max_scroll
+            } else {
+                position + viewport
+            };
+            for offset in eased_positions(position, target, steps_per_section) {
+                tab.evaluate(&format!("window.scrollTo(0, {});", offset.round()), false)
+                    .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+                std::thread::sleep(Duration::from_millis(SMOOTH_SCROLL_STEP_MS));
+            }
+            position = target;
+            if pause_at_sections {
+                std::thread::sleep(Duration::from_millis(SECTION_PAUSE_MS));
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_page_content(&self, tab: &Arc<Tab>) -> Result<String, BrowserError> {
         let content = tab
             .get_content()
@@ -261,6 +935,106 @@ impl Browser {
         Ok(content)
     }
 
+    /// HTML of the top document and each same-origin iframe, as
+    /// `(frame_url, html)` with the top document first. Cross-origin frames
+    /// can't be read and are skipped.
+    pub fn get_all_frame_content(&self, tab: &Arc<Tab>) -> Result<Vec<(String, String)>, BrowserError> {
+        let tree = tab
+            .call_method(Page::GetFrameTree(None))
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?
+            .frame_tree;
+        let top_origin = tree.frame.security_origin.clone();
+
+        let mut frames = vec![(tree.frame.url.clone(), self.get_page_content(tab)?)];
+        let mut pending = tree.child_frames.unwrap_or_default();
+        while !pending.is_empty() {
+            let node = pending.remove(0);
+            pending.extend(node.child_frames.unwrap_or_default());
+
+            let frame = node.frame;
+            if frame.security_origin != top_origin {
+                debug!("Skipping cross-origin frame {}", frame.url);
+                continue;
+            }
+            match frame_html(tab, &frame.id) {
+                Ok(html) => frames.push((frame.url, html)),
+                Err(e) => debug!("Could not read frame {}: {}", frame.url, e),
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Title, meta description and canonical URL of the loaded page.
+    pub fn get_page_meta(&self, tab: &Arc<Tab>) -> Result<PageMeta, BrowserError> {
+        let script = r#"
+            JSON.stringify({
+                title: document.title,
+                description: document.querySelector('meta[name="description" i]')?.content ?? null,
+                canonical: document.querySelector('link[rel~="canonical" i]')?.href ?? null,
+            })
+        "#;
+        let json = self.execute_script(tab, script)?;
+        let meta: PageMeta = serde_json::from_str(json.as_str().unwrap_or("{}"))
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e)))?;
+        Ok(meta.normalized())
+    }
+
+    /// PNG screenshot of the tab's viewport.
+    pub fn screenshot_png(&self, tab: &Arc<Tab>) -> Result<Vec<u8>, BrowserError> {
+        self.screenshot(tab, Page::CaptureScreenshotFormatOption::Png, None)
+    }
+
+    /// Screenshot of the tab's viewport in `format`. `quality` (0-100)
+    /// only applies to JPEG and WebP.
+    pub fn screenshot(
+        &self,
+        tab: &Arc<Tab>,
+        format: Page::CaptureScreenshotFormatOption,
+        quality: Option<u32>,
+    ) -> Result<Vec<u8>, BrowserError> {
+        tab.capture_screenshot(format, quality, None, true)
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!("Failed to capture screenshot: {}", e)))
+    }
+
+    /// PNG screenshot of the whole page, including what is scrolled out of
+    /// view, at the tab's current width.
+    pub fn capture_full_page_png(&self, tab: &Arc<Tab>) -> Result<Vec<u8>, BrowserError> {
+        let metrics = tab.call_method(Page::GetLayoutMetrics(None))?;
+        let size = metrics.css_content_size;
+        let data = tab
+            .call_method(Page::CaptureScreenshot {
+                format: Some(Page::CaptureScreenshotFormatOption::Png),
+                quality: None,
+                clip: Some(Page::Viewport {
+                    x: 0.0,
+                    y: 0.0,
+                    width: size.width,
+                    height: size.height,
+                    scale: 1.0,
+                }),
+                from_surface: Some(true),
+                capture_beyond_viewport: Some(true),
+                optimize_for_speed: None,
+            })
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!("Failed to capture screenshot: {}", e)))?
+            .data;
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!("Malformed screenshot data: {}", e)))
+    }
+
+    /// The main document response of the tab's last navigation. Only
+    /// recorded when [`NavigationOptions::detect_rate_limits`] is on.
+    pub fn last_document_response(&self, tab: &Arc<Tab>) -> Option<DocumentResponse> {
+        self.responses.lock().unwrap().get(tab.get_target_id()).and_then(|watcher| watcher.last())
+    }
+
+    /// Whether the tab still answers DevTools commands; `false` once Chrome
+    /// has crashed or the tab was closed or detached.
+    pub fn is_responsive(&self, tab: &Arc<Tab>) -> bool {
+        tab.evaluate("1", false).is_ok()
+    }
+
     pub fn get_current_url(&self, tab: &Arc<Tab>) -> Result<String, BrowserError> {
         let url = tab
             .get_url()
@@ -275,6 +1049,284 @@ impl Browser {
         Ok(result.value.unwrap_or(serde_json::Value::Null))
     }
 
+    /// Override the position reported by the Geolocation API and grant the
+    /// geolocation permission so pages don't prompt for it.
+    pub fn set_geolocation(&self, tab: &Arc<Tab>, latitude: f64, longitude: f64, accuracy: f64) -> Result<(), BrowserError> {
+        info!("Overriding geolocation: {}, {} (±{}m)", latitude, longitude, accuracy);
+
+        if let Err(e) = tab.call_method(GrantPermissions {
+            permissions: vec![PermissionType::Geolocation],
+            origin: None,
+            browser_context_id: None,
+        }) {
+            debug!("Failed to grant geolocation permission: {}", e);
+        }
+
+        tab.call_method(Emulation::SetGeolocationOverride {
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            accuracy: Some(accuracy),
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        Ok(())
+    }
+
+    /// Override the page timezone with an IANA id such as `Europe/Berlin`.
+    pub fn set_timezone(&self, tab: &Arc<Tab>, timezone: &str) -> Result<(), BrowserError> {
+        info!("Overriding timezone: {}", timezone);
+        tab.call_method(Emulation::SetTimezoneOverride {
+            timezone_id: timezone.to_string(),
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        Ok(())
+    }
+
+    /// Override the ICU locale used by `Intl` and date formatting, e.g. `de-DE`.
+    pub fn set_locale(&self, tab: &Arc<Tab>, locale: &str) -> Result<(), BrowserError> {
+        info!("Overriding locale: {}", locale);
+        tab.call_method(Emulation::SetLocaleOverride {
+            locale: Some(locale.to_string()),
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        Ok(())
+    }
+
+    /// Pin the page viewport to `width`x`height` CSS pixels so layout doesn't
+    /// depend on window decorations or the host screen.
+    pub fn set_viewport(&self, tab: &Arc<Tab>, width: u32, height: u32) -> Result<(), BrowserError> {
+        self.set_device_metrics(tab, Some((width, height)), None)
+    }
+
+    /// Override the viewport size in CSS pixels and the device pixel ratio
+    /// together, as one CDP call replaces the other's settings. `None`
+    /// leaves the window's size or the host display's ratio. Screenshots
+    /// are `viewport` times `device_scale_factor` pixels.
+    pub fn set_device_metrics(
+        &self,
+        tab: &Arc<Tab>,
+        viewport: Option<(u32, u32)>,
+        device_scale_factor: Option<f64>,
+    ) -> Result<(), BrowserError> {
+        if let Some((width, height)) = viewport {
+            info!("Setting viewport to {}x{}", width, height);
+        }
+        if let Some(scale) = device_scale_factor {
+            info!("Setting device pixel ratio to {}", scale);
+        }
+        // Zero turns each override off
+        let (width, height) = viewport.unwrap_or((0, 0));
+        tab.call_method(Emulation::SetDeviceMetricsOverride {
+            width,
+            height,
+            device_scale_factor: device_scale_factor.unwrap_or(0.0),
+            mobile: false,
+            scale: None,
+            screen_width: None,
+            screen_height: None,
+            position_x: None,
+            position_y: None,
+            dont_set_visible_size: None,
+            screen_orientation: None,
+            viewport: None,
+            display_feature: None,
+            device_posture: None,
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        Ok(())
+    }
+
+    /// Screen position and size of the window showing `tab`, as an
+    /// `(x, y, width, height)` region for screen recording.
+    pub fn get_window_region(&self, tab: &Arc<Tab>) -> Result<(i32, i32, i32, i32), BrowserError> {
+        let bounds = tab
+            .get_bounds()
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        if matches!(bounds.state, BrowserWindowState::Minimized) {
+            return Err(BrowserError::BrowserError(anyhow::anyhow!("browser window is minimized")));
+        }
+        window_region(bounds.left, bounds.top, bounds.width, bounds.height).ok_or_else(|| {
+            BrowserError::BrowserError(anyhow::anyhow!(
+                "browser window has no usable size ({}x{})",
+                bounds.width,
+                bounds.height
+            ))
+        })
+    }
+
+    /// Emulate `prefers-color-scheme` so sites render their light or dark
+    /// theme regardless of the OS setting. The override is verified with
+    /// `matchMedia` after it is applied.
+    pub fn set_color_scheme(&self, tab: &Arc<Tab>, scheme: ColorScheme) -> Result<(), BrowserError> {
+        info!("Emulating prefers-color-scheme: {}", scheme.as_str());
+        tab.call_method(Emulation::SetEmulatedMedia {
+            media: None,
+            features: Some(vec![Emulation::MediaFeature {
+                name: "prefers-color-scheme".to_string(),
+                value: scheme.as_str().to_string(),
+            }]),
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+
+        let query = format!("window.matchMedia('(prefers-color-scheme: {})').matches", scheme.as_str());
+        match self.execute_script(tab, &query)? {
+            serde_json::Value::Bool(true) => Ok(()),
+            other => Err(BrowserError::BrowserError(anyhow::anyhow!(
+                "prefers-color-scheme override not applied (matchMedia returned {})",
+                other
+            ))),
+        }
+    }
+
+    /// Send `headers` with every request the tab makes, replacing any set
+    /// before. Headers Chrome sets itself (`Host`, `Content-Length`, ...)
+    /// are skipped with a warning.
+    pub fn set_extra_headers(&self, tab: &Arc<Tab>, headers: &HashMap<String, String>) -> Result<(), BrowserError> {
+        let mut extra = HashMap::new();
+        for (name, value) in headers {
+            validate_header(name, value).map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e)))?;
+            if is_forbidden_header(name) {
+                warn!("Ignoring header {}: Chrome sets it itself", name);
+                continue;
+            }
+            extra.insert(name.as_str(), value.as_str());
+        }
+        info!("Sending {} extra request header(s)", extra.len());
+        tab.set_extra_http_headers(extra)
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+        Ok(())
+    }
+
+    /// Save files that navigations download into `dir`, or refuse them when
+    /// `None`. Either way [`navigate`](Self::navigate) then returns
+    /// [`BrowserError::Download`] for download URLs instead of waiting for
+    /// a page.
+    pub fn set_download_dir(&self, tab: &Arc<Tab>, dir: Option<&std::path::Path>) -> Result<(), BrowserError> {
+        let mut downloads = self.downloads.lock().unwrap();
+        if downloads.contains_key(tab.get_target_id()) {
+            return Err(BrowserError::BrowserError(anyhow::anyhow!("Download handling already set up for this tab")));
+        }
+        downloads.insert(tab.get_target_id().clone(), download::DownloadWatcher::start(tab, dir)?);
+        match dir {
+            Some(dir) => info!("Saving downloads to {:?}", dir),
+            None => debug!("Downloads disabled"),
+        }
+        Ok(())
+    }
+
+    /// Run `source` at the start of every document the tab loads, replacing
+    /// any script set before; `None` removes it.
+    pub fn set_init_script(&self, tab: &Arc<Tab>, source: Option<&str>) -> Result<(), BrowserError> {
+        let mut init_scripts = self.init_scripts.lock().unwrap();
+        let current = init_scripts.get(tab.get_target_id());
+        if current.map(|(current, _)| current.as_str()) == source {
+            return Ok(());
+        }
+        if let Some((_, identifier)) = current {
+            tab.call_method(Page::RemoveScriptToEvaluateOnNewDocument {
+                identifier: identifier.clone(),
+            })
+            .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+            init_scripts.remove(tab.get_target_id());
+        }
+        if let Some(source) = source {
+            let added = tab
+                .call_method(Page::AddScriptToEvaluateOnNewDocument {
+                    source: source.to_string(),
+                    world_name: None,
+                    include_command_line_api: None,
+                    run_immediately: None,
+                })
+                .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+            debug!("Added init script {}", added.identifier);
+            init_scripts.insert(tab.get_target_id().clone(), (source.to_string(), added.identifier));
+        }
+        Ok(())
+    }
+
+    /// Start recording the tab's network traffic for a HAR archive.
+    pub fn start_har_capture(&self, tab: &Arc<Tab>) -> Result<(), BrowserError> {
+        let mut har = self.har.lock().unwrap();
+        if har.is_some() {
+            return Err(BrowserError::BrowserError(anyhow::anyhow!("HAR capture already running")));
+        }
+        *har = Some(har::HarCapture::start(tab)?);
+        info!("HAR capture started");
+        Ok(())
+    }
+
+    /// Stop the running HAR capture and assemble the archive. Response
+    /// bodies are capped at [`har::MAX_BODY_BYTES`] each.
+    pub fn stop_har_capture(&self, tab: &Arc<Tab>) -> Result<Har, BrowserError> {
+        let capture = self
+            .har
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| BrowserError::BrowserError(anyhow::anyhow!("HAR capture not running")))?;
+        let har = capture.finish(tab);
+        info!("HAR capture stopped with {} entries", har.log.entries.len());
+        Ok(har)
+    }
+
+    /// Type each value into the first element matching its selector. A
+    /// selector may list comma-separated fallbacks, tried in order.
+    pub fn fill_form(&self, tab: &Arc<Tab>, fields: &[(&str, &str)]) -> Result<(), BrowserError> {
+        for (i, (selector, value)) in fields.iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(Duration::from_millis(FORM_FIELD_DELAY_MS));
+            }
+            let filled = selector_candidates(selector).any(|candidate| {
+                let typed = tab
+                    .find_element(candidate)
+                    .and_then(|element| element.type_into(value).map(|_| ()))
+                    .is_ok();
+                if typed {
+                    debug!("Filled field using selector: {}", candidate);
+                }
+                typed
+            });
+            if !filled {
+                return Err(BrowserError::ElementNotFound(selector.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Click the first element matching one of the comma-separated selectors.
+    pub fn submit(&self, tab: &Arc<Tab>, selector: &str) -> Result<(), BrowserError> {
+        let clicked = selector_candidates(selector).any(|candidate| {
+            let clicked = tab
+                .find_element(candidate)
+                .and_then(|element| element.click().map(|_| ()))
+                .is_ok();
+            if clicked {
+                info!("Submit button clicked using selector: {}", candidate);
+            }
+            clicked
+        });
+        if clicked {
+            Ok(())
+        } else {
+            Err(BrowserError::ElementNotFound(selector.to_string()))
+        }
+    }
+
+    /// Current non-empty value of the first matching input, if any.
+    pub fn field_value(&self, tab: &Arc<Tab>, selector: &str) -> Option<String> {
+        selector_candidates(selector).find_map(|candidate| {
+            let script = format!(
+                "document.querySelector({})?.value || ''",
+                serde_json::Value::from(candidate)
+            );
+            tab.evaluate(&script, false)
+                .ok()?
+                .value?
+                .as_str()
+                .filter(|value| !value.trim().is_empty())
+                .map(str::to_string)
+        })
+    }
+
     pub fn go_back(&self, tab: &Arc<Tab>) -> Result<(), BrowserError> {
         tab.evaluate("window.history.back();", false)
             .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
@@ -290,6 +1342,104 @@ impl Browser {
     }
 }
 
+/// Pause between form fields so pages with per-field validation keep up.
+const FORM_FIELD_DELAY_MS: u64 = 500;
+
+/// Clicks the first visible consent or age-gate accept button and returns
+/// its label, or `null`. Known consent-manager buttons are tried before
+/// matching on button text.
+const CONSENT_SCRIPT: &str = r#"
+(function() {
+    const visible = el => el && el.offsetParent !== null && !el.disabled;
+    const known = [
+        '#onetrust-accept-btn-handler',
+        '#CybotCookiebotDialogBodyLevelButtonLevelOptinAllowAll',
+        '#CybotCookiebotDialogBodyButtonAccept',
+        '#didomi-notice-agree-button',
+        '#L2AGLb',
+        '.fc-cta-consent',
+        '.cc-allow',
+        '.cc-accept',
+        '[data-testid="uc-accept-all-button"]',
+        '[data-cookiebanner="accept_button"]',
+        '.js-accept-cookies',
+        '#accept-cookies',
+        '#cookie-accept'
+    ];
+    for (const selector of known) {
+        const el = document.querySelector(selector);
+        if (visible(el)) {
+            el.click();
+            return selector;
+        }
+    }
+    const labels = [
+        'accept', 'accept all', 'accept all cookies', 'accept cookies',
+        'allow all', 'allow all cookies', 'allow cookies', 'i accept',
+        'i agree', 'agree', 'agree and continue', 'got it', 'ok',
+        'i am over 18', "i'm over 18", 'yes, i am over 18', 'i am 18 or older',
+        'enter', 'enter site', 'yes'
+    ];
+    const candidates = document.querySelectorAll('button, a[role="button"], [role="button"], input[type="submit"], input[type="button"]');
+    for (const el of candidates) {
+        const text = (el.innerText || el.value || '').trim().toLowerCase().replace(/\s+/g, ' ');
+        if (labels.includes(text) && visible(el)) {
+            el.click();
+            return text;
+        }
+    }
+    return null;
+})()
+"#;
+
+/// Evaluate `outerHTML` in an isolated world attached to the frame, so page
+/// scripts can't interfere with the read.
+fn frame_html(tab: &Arc<Tab>, frame_id: &str) -> Result<String, BrowserError> {
+    let context = tab
+        .call_method(Page::CreateIsolatedWorld {
+            frame_id: frame_id.to_string(),
+            world_name: Some("site-recorder".to_string()),
+            grant_univeral_access: None,
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+    let result = tab
+        .call_method(Runtime::Evaluate {
+            expression: "document.documentElement ? document.documentElement.outerHTML : ''".to_string(),
+            object_group: None,
+            include_command_line_api: None,
+            silent: Some(true),
+            context_id: Some(context.execution_context_id),
+            return_by_value: Some(true),
+            generate_preview: None,
+            user_gesture: None,
+            await_promise: None,
+            throw_on_side_effect: None,
+            timeout: None,
+            disable_breaks: None,
+            repl_mode: None,
+            allow_unsafe_eval_blocked_by_csp: None,
+            unique_context_id: None,
+            serialization_options: None,
+        })
+        .map_err(|e| BrowserError::BrowserError(anyhow::anyhow!(e.to_string())))?;
+    match result.result.value {
+        Some(serde_json::Value::String(html)) => Ok(html),
+        _ => Err(BrowserError::BrowserError(anyhow::anyhow!("frame returned no document"))),
+    }
+}
+
+/// Capture region for a window, with width and height rounded down to even
+/// numbers as H.264 requires. `None` if the window is too small to record.
+fn window_region(left: u32, top: u32, width: f64, height: f64) -> Option<(i32, i32, i32, i32)> {
+    let width = (width as i32) & !1;
+    let height = (height as i32) & !1;
+    (width > 0 && height > 0).then_some((left as i32, top as i32, width, height))
+}
+
+fn selector_candidates(selector: &str) -> impl Iterator<Item = &str> {
+    selector.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
 impl Default for Browser {
     fn default() -> Self {
         Self::new().expect("Failed to create default browser")
@@ -300,10 +1450,102 @@ impl Default for Browser {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_eased_positions() {
+        let positions = eased_positions(100.0, 900.0, 10);
+        assert_eq!(positions.len(), 10);
+        assert_eq!(*positions.last().unwrap(), 900.0);
+        assert!(positions.windows(2).all(|w| w[0] <= w[1]));
+        // Slow start: the first step covers less than an even share.
+        assert!(positions[0] - 100.0 < 80.0);
+        assert_eq!(eased_positions(0.0, 50.0, 0), vec![50.0]);
+    }
+
+    #[test]
+    fn test_validate_header() {
+        assert!(validate_header("X-Api-Key", "abc: 123").is_ok());
+        assert!(validate_header("", "x").is_err());
+        assert!(validate_header("X Api", "x").is_err());
+        assert!(validate_header("X-Api", "a\r\nHost: evil").is_err());
+        assert!(is_forbidden_header("Host"));
+        assert!(is_forbidden_header("content-length"));
+        assert!(!is_forbidden_header("X-Forwarded-For"));
+    }
+
+    #[test]
+    fn test_idle_timeout_for_delay() {
+        assert_eq!(BrowserConfig::idle_timeout_for_delay(1000), DEFAULT_IDLE_TIMEOUT);
+        assert_eq!(BrowserConfig::idle_timeout_for_delay(120_000), Duration::from_secs(480));
+    }
+
+    #[test]
+    fn test_validate_chrome_arg() {
+        assert!(validate_chrome_arg("--disable-gpu").is_ok());
+        assert!(validate_chrome_arg("--lang=de-DE").is_ok());
+        assert!(validate_chrome_arg("--force-color-profile=srgb").is_ok());
+        assert!(validate_chrome_arg("--enable-features=A,B").is_ok());
+        assert!(validate_chrome_arg("disable-gpu").is_err());
+        assert!(validate_chrome_arg("--").is_err());
+        assert!(validate_chrome_arg("--lang=de\n--no-sandbox").is_err());
+        assert!(validate_chrome_arg("--no-sandbox").is_err());
+        assert!(validate_chrome_arg("--Remote-Debugging-Port=9222").is_err());
+        assert!(validate_chrome_arg("--headless=new").is_err());
+    }
+
+    #[test]
+    fn test_headless_mode() {
+        assert_eq!(HeadlessMode::from(true), HeadlessMode::Legacy);
+        assert!(!HeadlessMode::from(false).is_headless());
+        assert_eq!(HeadlessMode::New.launch_args(), Some("--headless=new"));
+        assert_eq!(HeadlessMode::Legacy.launch_args(), None);
+        assert_eq!(serde_json::to_string(&HeadlessMode::New).unwrap(), "\"new\"");
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay_ms: 500,
+            max_delay_ms: 3000,
+            jitter: false,
+        };
+        assert_eq!(policy.delay_for(1), Duration::from_millis(500));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(1000));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(2000));
+        assert_eq!(policy.delay_for(4), Duration::from_millis(3000));
+        assert_eq!(policy.delay_for(40), Duration::from_millis(3000));
+
+        let jittered = RetryPolicy { jitter: true, ..policy };
+        for _ in 0..20 {
+            let delay = jittered.delay_for(3);
+            assert!(delay >= Duration::from_millis(1000) && delay <= Duration::from_millis(2000));
+        }
+    }
+
+    #[test]
+    fn test_window_region() {
+        assert_eq!(window_region(10, 20, 1281.0, 721.5), Some((10, 20, 1280, 720)));
+        assert_eq!(window_region(0, 0, 1.0, 600.0), None);
+    }
+
     #[test]
     fn test_navigation_options_default() {
         let options = NavigationOptions::default();
         assert_eq!(options.timeout_ms, 30000);
         assert!(options.wait_for_idle);
+        assert!(options.wait_for_text.is_none());
+    }
+
+    #[test]
+    fn test_selector_candidates() {
+        let candidates: Vec<&str> = selector_candidates("#email, input[name='user'],, ").collect();
+        assert_eq!(candidates, vec!["#email", "input[name='user']"]);
+    }
+
+    #[test]
+    fn test_color_scheme_serde() {
+        assert_eq!(serde_json::to_string(&ColorScheme::Dark).unwrap(), "\"dark\"");
+        let scheme: ColorScheme = serde_json::from_str("\"light\"").unwrap();
+        assert_eq!(scheme, ColorScheme::Light);
     }
 }
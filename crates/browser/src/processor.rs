@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use exporter::RecordingData;
+use headless_chrome::Tab;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Custom per-page logic run by the crawl loop after each navigation.
+///
+/// Returned records are merged into the session's data export. Processors
+/// may leave `session_id` empty; [`run_processors`] fills it in. `tab` is
+/// `None` when the page was fetched over HTTP without a browser.
+pub trait PageProcessor: Send + Sync {
+    fn process(&self, url: &str, html: &str, tab: Option<&Arc<Tab>>) -> Vec<RecordingData>;
+}
+
+/// Run every processor against a page and stamp the results with `session_id`.
+pub fn run_processors(
+    processors: &[Box<dyn PageProcessor>],
+    session_id: &str,
+    url: &str,
+    html: &str,
+    tab: Option<&Arc<Tab>>,
+) -> Vec<RecordingData> {
+    processors
+        .iter()
+        .flat_map(|p| p.process(url, html, tab))
+        .map(|mut record| {
+            if record.session_id.is_empty() {
+                record.session_id = session_id.to_string();
+            }
+            record
+        })
+        .collect()
+}
+
+/// Records the page `<title>`.
+pub struct TitleExtractor;
+
+impl TitleExtractor {
+    fn extract(html: &str) -> Option<String> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse("title").ok()?;
+        let title = document.select(&selector).next()?.text().collect::<String>();
+        let title = title.trim();
+        (!title.is_empty()).then(|| title.to_string())
+    }
+}
+
+impl PageProcessor for TitleExtractor {
+    fn process(&self, url: &str, html: &str, _tab: Option<&Arc<Tab>>) -> Vec<RecordingData> {
+        Self::extract(html)
+            .map(|title| record(url, "extract_title", serde_json::json!({ "title": title })))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Records the `<meta name="description">` content.
+pub struct MetaDescriptionExtractor;
+
+impl MetaDescriptionExtractor {
+    fn extract(html: &str) -> Option<String> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(r#"meta[name="description" i]"#).ok()?;
+        let content = document.select(&selector).next()?.value().attr("content")?.trim();
+        (!content.is_empty()).then(|| content.to_string())
+    }
+}
+
+impl PageProcessor for MetaDescriptionExtractor {
+    fn process(&self, url: &str, html: &str, _tab: Option<&Arc<Tab>>) -> Vec<RecordingData> {
+        Self::extract(html)
+            .map(|description| record(url, "extract_meta_description", serde_json::json!({ "description": description })))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Title, meta description and canonical URL of a page. Missing or empty
+/// tags are `None` and left out when serialized.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<String>,
+}
+
+impl PageMeta {
+    /// Read the fields from raw HTML, for pages fetched without a browser.
+    /// A relative canonical link is resolved against `url`.
+    pub fn from_html(html: &str, url: &str) -> Self {
+        let canonical = Selector::parse(r#"link[rel~="canonical" i][href]"#).ok().and_then(|selector| {
+            let document = Html::parse_document(html);
+            let href = document.select(&selector).next()?.value().attr("href")?.trim().to_string();
+            let resolved = url::Url::parse(url).and_then(|base| base.join(&href)).map(String::from);
+            Some(resolved.unwrap_or(href))
+        });
+        Self {
+            title: TitleExtractor::extract(html),
+            description: MetaDescriptionExtractor::extract(html),
+            canonical: canonical.filter(|href| !href.is_empty()),
+        }
+    }
+
+    /// Blank values read from the live DOM become `None`.
+    pub(crate) fn normalized(self) -> Self {
+        let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            title: clean(self.title),
+            description: clean(self.description),
+            canonical: clean(self.canonical),
+        }
+    }
+}
+
+fn record(url: &str, action: &str, metadata: serde_json::Value) -> RecordingData {
+    RecordingData {
+        session_id: String::new(),
+        timestamp: chrono::Utc::now(),
+        url: url.to_string(),
+        action: action.to_string(),
+        metadata,
+        success: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head>
+        <title> Example Domain </title>
+        <meta name="Description" content="An example page">
+        </head><body></body></html>"#;
+
+    #[test]
+    fn test_title_extractor() {
+        assert_eq!(TitleExtractor::extract(PAGE), Some("Example Domain".to_string()));
+        assert_eq!(TitleExtractor::extract("<html><head></head></html>"), None);
+    }
+
+    #[test]
+    fn test_run_processors_without_tab() {
+        let processors: Vec<Box<dyn PageProcessor>> = vec![Box::new(TitleExtractor)];
+        let records = run_processors(&processors, "session-1", "https://example.com/", PAGE, None);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].session_id, "session-1");
+        assert_eq!(records[0].metadata["title"], "Example Domain");
+    }
+
+    #[test]
+    fn test_page_meta_from_html() {
+        let html = r#"<head><title>Docs</title><link rel="canonical" href="/docs/"></head>"#;
+        let meta = PageMeta::from_html(html, "https://example.com/docs/?ref=nav");
+        assert_eq!(
+            meta,
+            PageMeta {
+                title: Some("Docs".to_string()),
+                description: None,
+                canonical: Some("https://example.com/docs/".to_string()),
+            }
+        );
+        assert_eq!(serde_json::to_value(&meta).unwrap(), serde_json::json!({
+            "title": "Docs",
+            "canonical": "https://example.com/docs/",
+        }));
+        assert_eq!(PageMeta::from_html("<html></html>", "https://example.com/"), PageMeta::default());
+    }
+
+    #[test]
+    fn test_meta_description_extractor() {
+        assert_eq!(MetaDescriptionExtractor::extract(PAGE), Some("An example page".to_string()));
+        assert_eq!(MetaDescriptionExtractor::extract("<html></html>"), None);
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::{Arc, Mutex};
+
+use headless_chrome::browser::tab::EventListener;
+use headless_chrome::protocol::cdp::types::Event;
+use headless_chrome::protocol::cdp::Network;
+use headless_chrome::Tab;
+
+/// Status and headers of the main document a navigation loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentResponse {
+    pub url: String,
+    pub status: u16,
+    /// The `Retry-After` header, if the server sent one.
+    pub retry_after: Option<String>,
+}
+
+/// Records the tab's latest top-level document response from
+/// `Network.responseReceived` events.
+#[derive(Clone)]
+pub(crate) struct ResponseWatcher {
+    last: Arc<Mutex<Option<DocumentResponse>>>,
+}
+
+impl ResponseWatcher {
+    pub(crate) fn start(tab: &Arc<Tab>) -> anyhow::Result<Self> {
+        tab.call_method(Network::Enable {
+            max_total_buffer_size: None,
+            max_resource_buffer_size: None,
+            max_post_data_size: None,
+        })?;
+
+        let last = Arc::new(Mutex::new(None));
+        let listener_last = last.clone();
+        // The main frame of a page target shares the target's id
+        let main_frame = tab.get_target_id().clone();
+        let listener: Arc<dyn EventListener<Event> + Send + Sync> = Arc::new(move |event: &Event| {
+            if let Event::NetworkResponseReceived(e) = event {
+                let p = &e.params;
+                if matches!(p.Type, Network::ResourceType::Document) && p.frame_id.as_ref() == Some(&main_frame) {
+                    *listener_last.lock().unwrap() = Some(DocumentResponse {
+                        url: p.response.url.clone(),
+                        status: p.response.status as u16,
+                        retry_after: header(&p.response.headers, "retry-after"),
+                    });
+                }
+            }
+        });
+        tab.add_event_listener(listener)?;
+
+        Ok(Self { last })
+    }
+
+    /// Forget the previous response before a new navigation.
+    pub(crate) fn reset(&self) {
+        self.last.lock().unwrap().take();
+    }
+
+    pub(crate) fn last(&self) -> Option<DocumentResponse> {
+        self.last.lock().unwrap().clone()
+    }
+}
+
+fn header(headers: &Network::Headers, name: &str) -> Option<String> {
+    match headers.0 {
+        Some(serde_json::Value::Object(ref map)) => map
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_str())
+            .map(|value| value.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use browser::{Browser, NavigationOptions};
+use headless_chrome::Tab;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::{AuthConfig, EngineError};
+
+/// Used when a TOTP secret is set without a field selector.
+pub const DEFAULT_TOTP_SELECTOR: &str =
+    "input[autocomplete='one-time-code'], input[name*='otp' i], input[name*='totp' i], input[name*='code' i]";
+
+/// How [`log_in`] will authenticate with a given [`AuthConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginMethod {
+    Script,
+    Form,
+}
+
+impl AuthConfig {
+    /// `None` when neither a login script nor a complete set of form
+    /// credentials and selectors is configured.
+    pub fn method(&self) -> Option<LoginMethod> {
+        if self.login_script.is_some() {
+            return Some(LoginMethod::Script);
+        }
+        let form = [
+            &self.username,
+            &self.password,
+            &self.username_selector,
+            &self.password_selector,
+            &self.submit_selector,
+        ];
+        form.iter().all(|field| field.is_some()).then_some(LoginMethod::Form)
+    }
+}
+
+/// Open the login page and sign in, waiting for the configured
+/// post-login condition.
+pub async fn log_in(
+    browser: &Browser,
+    tab: &Arc<Tab>,
+    auth: &AuthConfig,
+    nav_options: &NavigationOptions,
+) -> Result<(), EngineError> {
+    info!("Navigating to login page: {}", auth.login_url);
+    browser.navigate(tab, &auth.login_url, nav_options)?;
+    let login_page_url = tab.get_url();
+    let login_timeout = Duration::from_millis(nav_options.timeout_ms);
+
+    match auth.method() {
+        Some(LoginMethod::Script) => {
+            let setup = format!(
+                "window.__SR_USER = {}; window.__SR_PASS = {};",
+                js_quote(auth.username.as_deref().unwrap_or("")),
+                js_quote(auth.password.as_deref().unwrap_or("")),
+            );
+            if let Err(e) = browser.execute_script(tab, &setup) {
+                warn!("Failed to inject credentials for login script: {}", e);
+            }
+            browser.execute_script(tab, auth.login_script.as_deref().unwrap_or_default())?;
+            info!("Custom login script executed");
+        }
+        Some(LoginMethod::Form) => {
+            let field = |value: &Option<String>| value.as_deref().unwrap_or_default().to_string();
+            perform_login(
+                browser,
+                tab,
+                &field(&auth.username),
+                &field(&auth.password),
+                &field(&auth.username_selector),
+                &field(&auth.password_selector),
+                &field(&auth.submit_selector),
+            )?;
+            if let Some(ref secret) = auth.totp_secret {
+                enter_totp_code(tab, secret, auth.totp_field_selector.as_deref().unwrap_or(DEFAULT_TOTP_SELECTOR))?;
+            }
+        }
+        None => {
+            warn!("No login script or complete form credentials configured, skipping login");
+            return Ok(());
+        }
+    }
+
+    wait_after_login(tab, &auth.post_login_wait, &login_page_url, login_timeout).await
+}
+
+fn js_quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Wait for the configured post-login condition, failing if it isn't met
+/// within `timeout`.
+async fn wait_after_login(
+    tab: &Arc<Tab>,
+    wait: &session::PostLoginWait,
+    login_page_url: &str,
+    timeout: Duration,
+) -> Result<(), EngineError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let poll = Duration::from_millis(250);
+    match wait {
+        session::PostLoginWait::FixedMs(ms) => {
+            sleep(Duration::from_millis(*ms)).await;
+            Ok(())
+        }
+        session::PostLoginWait::ForSelector(selector) => {
+            info!("Waiting for post-login element: {}", selector);
+            while tokio::time::Instant::now() < deadline {
+                if tab.find_element(selector).is_ok() {
+                    return Ok(());
+                }
+                sleep(poll).await;
+            }
+            Err(EngineError::Login(format!(
+                "post-login element '{}' did not appear within {:?}",
+                selector, timeout
+            )))
+        }
+        session::PostLoginWait::ForUrlChange => {
+            info!("Waiting for navigation away from {}", login_page_url);
+            while tokio::time::Instant::now() < deadline {
+                if tab.get_url() != login_page_url {
+                    return Ok(());
+                }
+                sleep(poll).await;
+            }
+            Err(EngineError::Login(format!(
+                "URL did not change from {} within {:?}",
+                login_page_url, timeout
+            )))
+        }
+    }
+}
+
+fn perform_login(
+    browser: &Browser,
+    tab: &Arc<Tab>,
+    username: &str,
+    password: &str,
+    username_selector: &str,
+    password_selector: &str,
+    submit_selector: &str,
+) -> Result<(), EngineError> {
+    // Check if we're on localhost - if so, check for pre-filled fields
+    let current_url = tab.get_url();
+    let is_localhost = current_url.contains("localhost") || current_url.contains("127.0.0.1");
+    let mut prefilled = false;
+
+    if is_localhost {
+        info!("Detected localhost domain, checking for pre-filled form fields...");
+
+        let username_value = browser.field_value(tab, username_selector);
+        if let Some(value) = &username_value {
+            info!("Username field already contains: '{}'", value);
+        }
+        let password_prefilled = browser.field_value(tab, password_selector).is_some();
+        if password_prefilled {
+            info!("Password field already contains data");
+        }
+
+        prefilled = username_value.is_some() && password_prefilled;
+        if prefilled {
+            info!("Both username and password fields are pre-filled on localhost, skipping form filling...");
+        } else {
+            info!("Fields not pre-filled, proceeding with normal form filling...");
+        }
+    }
+
+    if !prefilled {
+        info!("Filling login form...");
+        browser.fill_form(tab, &[(username_selector, username), (password_selector, password)])?;
+    }
+
+    std::thread::sleep(Duration::from_millis(500));
+
+    info!("Clicking submit button...");
+    browser.submit(tab, submit_selector)?;
+
+    info!("Login form submitted");
+    Ok(())
+}
+
+/// Wait for the 2FA field, then type and submit the current TOTP code.
+fn enter_totp_code(tab: &Arc<Tab>, secret: &str, selector: &str) -> Result<(), EngineError> {
+    info!("Waiting for one-time code field...");
+    let element = tab
+        .wait_for_element_with_custom_timeout(selector, Duration::from_secs(15))
+        .map_err(|e| EngineError::Login(format!("could not find one-time code field: {}", e)))?;
+
+    // Don't submit a code that expires before the server checks it.
+    let now = chrono::Utc::now().timestamp() as u64;
+    let remaining = session::totp_seconds_remaining(now);
+    if remaining < 5 {
+        info!("TOTP code expires in {}s, waiting for the next one", remaining);
+        std::thread::sleep(Duration::from_secs(remaining));
+    }
+    let code = session::generate_totp(secret, chrono::Utc::now().timestamp() as u64)?;
+
+    element.click()?;
+    element.type_into(&code)?;
+    tab.press_key("Enter")?;
+    info!("One-time code submitted");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_method() {
+        let mut auth = AuthConfig {
+            login_url: "https://example.com/login".to_string(),
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+            username_selector: Some("#user".to_string()),
+            password_selector: Some("#pass".to_string()),
+            ..AuthConfig::default()
+        };
+        assert_eq!(auth.method(), None);
+
+        auth.submit_selector = Some("button".to_string());
+        assert_eq!(auth.method(), Some(LoginMethod::Form));
+
+        auth.login_script = Some("document.forms[0].submit()".to_string());
+        assert_eq!(auth.method(), Some(LoginMethod::Script));
+    }
+}
@@ -0,0 +1,741 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use browser::{Browser, BrowserConfig, BrowserError, ColorScheme, HeadlessMode, NavigationOptions, RetryPolicy, ScrollBehavior};
+use crawler::TrapLimits;
+use exporter::ExportOptions;
+use headless_chrome::Tab;
+use notifier::NotificationConfig;
+use recorder::{AudioCodec, ContactSheetConfig, IntroCard, RecordingConfig, RecordingMode, RecordingOutput, ScreenshotFormat, VideoFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::EngineError;
+
+/// How the crawl loads each page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchBackend {
+    /// Load pages in Chrome, running scripts so they can be recorded.
+    #[default]
+    Browser,
+    /// Plain HTTP GET for link discovery only. Much faster, but sees only
+    /// server-rendered HTML, can't record, and skips page processors.
+    Http,
+}
+
+/// Everything a crawl-and-record run needs. Construct with
+/// [`EngineConfig::new`] and override fields as required.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EngineConfig {
+    pub url: String,
+    pub max_pages: usize,
+    pub delay_ms: u64,
+    /// Vary each delay by up to this many milliseconds either way.
+    #[serde(default)]
+    pub delay_jitter_ms: u64,
+    /// Seed for the delay jitter, for reproducible runs; random when `None`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Pause the recording while waiting between pages, so a browser video
+    /// only shows page activity. A screen video holds its last frame
+    /// through the waits instead.
+    #[serde(default)]
+    pub pause_during_delay: bool,
+    pub headless: bool,
+    /// Use Chrome's new headless implementation (`--headless=new`) instead
+    /// of the legacy one when `headless` is set.
+    #[serde(default)]
+    pub new_headless: bool,
+    /// Seconds the browser may sit idle before it is shut down; derived
+    /// from `delay_ms` when `None`.
+    #[serde(default)]
+    pub browser_idle_timeout_secs: Option<u64>,
+    /// Ignore TLS certificate errors in the browser and HTTP requests.
+    #[serde(default)]
+    pub insecure: bool,
+    /// Headers sent with every browser and HTTP request, e.g. an API key.
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+    /// Wait out HTTP 429 responses (honoring `Retry-After`) and retry the
+    /// page instead of recording it.
+    #[serde(default = "default_respect_rate_limits")]
+    pub respect_rate_limits: bool,
+    /// Accept cookie consent and age gates once per host before recording.
+    #[serde(default)]
+    pub warm_up: bool,
+    /// Persistent Chrome profile directory; see [`BrowserConfig::user_data_dir`].
+    #[serde(default)]
+    pub profile_dir: Option<PathBuf>,
+    pub output_dir: PathBuf,
+    pub fps: u32,
+    pub recording_mode: RecordingMode,
+    #[serde(default)]
+    pub fetch_backend: FetchBackend,
+    pub audio_enabled: bool,
+    /// Codec for recorded audio; checked against the container by
+    /// [`EngineConfig::validate`].
+    #[serde(default)]
+    pub audio_codec: AudioCodec,
+    /// Audio bitrate; the encoder's default when `None`.
+    #[serde(default)]
+    pub audio_bitrate_kbps: Option<u32>,
+    pub screen_width: Option<u32>,
+    pub screen_height: Option<u32>,
+    pub screen_region: Option<(i32, i32, i32, i32)>,
+    /// Stream the screen recording to stdout or a named pipe instead of
+    /// saving it in the session directory.
+    #[serde(default)]
+    pub recording_output: Option<RecordingOutput>,
+    /// End the crawl once the recording takes up this many megabytes.
+    #[serde(default)]
+    pub max_recording_size_mb: Option<u64>,
+    /// Capture browser frames less often while the crawl, counting FFmpeg
+    /// and the browser, uses more than this many megabytes of memory.
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+    /// Stop recording after this many pages and crawl the rest without video.
+    #[serde(default)]
+    pub record_first_n_pages: Option<usize>,
+    /// Title card prepended to the finished videos.
+    #[serde(default)]
+    pub intro_card: Option<IntroCard>,
+    /// Tile a screenshot of each page into `<session>_contactsheet.jpg`.
+    #[serde(default)]
+    pub contact_sheet: Option<ContactSheetConfig>,
+    /// Save a full-page PNG of every visited page here, named after its
+    /// URL, whatever the recording mode.
+    #[serde(default)]
+    pub screenshots_dir: Option<PathBuf>,
+    /// Screen-record only the browser window instead of the whole display.
+    #[serde(default)]
+    pub capture_window: bool,
+    pub organize_by_session: bool,
+    /// Name the session this instead of generating an ID.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Start generated session IDs with this instead of `session`.
+    #[serde(default)]
+    pub session_prefix: Option<String>,
+    pub finalize_timeout_secs: u64,
+    pub screenshot_format: ScreenshotFormat,
+    /// Browser frames per batch directory and encoded segment.
+    #[serde(default = "default_frame_batch_size")]
+    pub frame_batch_size: u64,
+    /// Probe finished videos with `ffprobe` and report unplayable ones as
+    /// capture warnings.
+    #[serde(default)]
+    pub verify_video: bool,
+    /// How each page is scrolled after it loads.
+    pub scroll_behavior: ScrollBehavior,
+    /// Wait for this text to appear on each page before processing it.
+    #[serde(default)]
+    pub wait_for_text: Option<String>,
+    /// JavaScript run before each page's own scripts.
+    #[serde(default)]
+    pub pre_nav_script: Option<String>,
+    /// JavaScript run after each page loads, before it is scrolled.
+    #[serde(default)]
+    pub post_nav_script: Option<String>,
+    /// Pause after each page loads; see [`NavigationOptions::post_nav_settle_ms`].
+    #[serde(default = "default_post_nav_settle_ms")]
+    pub post_nav_settle_ms: u64,
+    /// Pause after closing modals; see [`NavigationOptions::post_modal_settle_ms`].
+    #[serde(default = "default_post_modal_settle_ms")]
+    pub post_modal_settle_ms: u64,
+    pub viewport: Option<(u32, u32)>,
+    /// Device pixel ratio pages render and browser frames are captured at,
+    /// e.g. 1 for small files or 2 for sharp HiDPI video; the host
+    /// display's when `None`.
+    #[serde(default)]
+    pub device_scale_factor: Option<f64>,
+    /// Place a visible browser window here, possibly off-screen.
+    #[serde(default)]
+    pub window_position: Option<(i32, i32)>,
+    /// Keep a visible browser window minimized.
+    #[serde(default)]
+    pub minimized: bool,
+    /// Chrome switches passed through at launch; see
+    /// [`BrowserConfig::extra_chrome_args`].
+    #[serde(default)]
+    pub chrome_args: Vec<String>,
+    pub proxy: Option<String>,
+    pub sitemap: Option<String>,
+    /// Limit on each HTTP request the crawler makes, the sitemap and
+    /// robots.txt fetches included.
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+    /// Retries of a sitemap or robots.txt fetch that failed transiently.
+    /// When all fail the crawl goes on without it.
+    #[serde(default = "default_aux_retries")]
+    pub aux_retries: u32,
+    /// URLs queued after the start URL, one per entry; see
+    /// [`Crawler::seed_from_list`](crawler::Crawler::seed_from_list).
+    #[serde(default)]
+    pub seed_urls: Vec<String>,
+    /// Other domains the crawl may follow links into. A leading `.` also
+    /// matches subdomains.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Caps on URLs queued per query-string pattern, to escape crawl traps.
+    #[serde(default)]
+    pub trap_limits: TrapLimits,
+    pub concurrency: usize,
+    /// Tabs loading pages in parallel within the one browser. Only for
+    /// runs that don't record or capture HAR.
+    #[serde(default = "default_tabs")]
+    pub tabs: usize,
+    /// Times a crashed or unresponsive browser is replaced with a new one
+    /// before the crawl ends with [`StopReason::BrowserCrashed`]. 0 ends it
+    /// at the first crash.
+    ///
+    /// [`StopReason::BrowserCrashed`]: crate::StopReason::BrowserCrashed
+    #[serde(default = "default_max_browser_relaunches")]
+    pub max_browser_relaunches: u32,
+    pub geo: Option<(f64, f64, f64)>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub color_scheme: Option<ColorScheme>,
+    pub save_har: bool,
+    /// Save files that pages download here; downloads are refused when `None`.
+    #[serde(default)]
+    pub download_dir: Option<PathBuf>,
+    /// Skip link extraction on pages whose content matches an earlier page.
+    pub dedup_content: bool,
+    /// Skip link extraction on pages whose `rel=canonical` link names
+    /// another crawlable URL, crawling that URL instead.
+    #[serde(default)]
+    pub respect_canonical: bool,
+    /// Visit only the start URL, sitemap entries and `seed_urls`, in that
+    /// order, without following links. `max_pages` still caps the run.
+    #[serde(default)]
+    pub no_discover: bool,
+    /// Load only the start URL and keep recording it as it updates in place,
+    /// until `max_runtime_secs` passes or the run is stopped. For dashboards
+    /// and status pages.
+    #[serde(default)]
+    pub kiosk: bool,
+    /// Record each page's images, stylesheets and CSS `url()` references.
+    #[serde(default)]
+    pub discover_assets: bool,
+    pub max_runtime_secs: Option<u64>,
+    /// Retry failed page loads with backoff; `None` tries each page once.
+    pub retry: Option<RetryPolicy>,
+    pub scan_url: Option<String>,
+    /// Write a sitemap of the visited URLs to this path after the crawl.
+    pub emit_sitemap: Option<PathBuf>,
+    /// Which records go into the session's data export.
+    #[serde(default)]
+    pub export: ExportOptions,
+    pub auth: Option<AuthConfig>,
+    pub notifications: NotificationConfig,
+}
+
+impl EngineConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            max_pages: 100,
+            delay_ms: 1000,
+            delay_jitter_ms: 0,
+            pause_during_delay: false,
+            seed: None,
+            headless: false,
+            new_headless: false,
+            browser_idle_timeout_secs: None,
+            insecure: false,
+            extra_headers: HashMap::new(),
+            profile_dir: None,
+            respect_rate_limits: true,
+            warm_up: false,
+            output_dir: PathBuf::from("./recordings"),
+            fps: 30,
+            recording_mode: RecordingMode::Both,
+            fetch_backend: FetchBackend::Browser,
+            audio_enabled: false,
+            screen_width: Some(1920),
+            screen_height: Some(1080),
+            screen_region: None,
+            recording_output: None,
+            max_recording_size_mb: None,
+            memory_limit_mb: None,
+            record_first_n_pages: None,
+            intro_card: None,
+            contact_sheet: None,
+            screenshots_dir: None,
+            capture_window: false,
+            organize_by_session: false,
+            session_id: None,
+            session_prefix: None,
+            finalize_timeout_secs: recorder::DEFAULT_FINALIZE_TIMEOUT_SECS,
+            frame_batch_size: recorder::DEFAULT_FRAME_BATCH_SIZE,
+            verify_video: false,
+            audio_codec: AudioCodec::default(),
+            audio_bitrate_kbps: None,
+            screenshot_format: ScreenshotFormat::default(),
+            scroll_behavior: NavigationOptions::default().scroll_behavior,
+            wait_for_text: None,
+            pre_nav_script: None,
+            post_nav_script: None,
+            post_nav_settle_ms: browser::DEFAULT_POST_NAV_SETTLE_MS,
+            post_modal_settle_ms: browser::DEFAULT_POST_MODAL_SETTLE_MS,
+            viewport: None,
+            device_scale_factor: None,
+            window_position: None,
+            minimized: false,
+            chrome_args: Vec::new(),
+            proxy: None,
+            sitemap: None,
+            http_timeout_secs: default_http_timeout_secs(),
+            aux_retries: crawler::DEFAULT_AUX_RETRIES,
+            seed_urls: Vec::new(),
+            allowed_domains: Vec::new(),
+            trap_limits: TrapLimits::default(),
+            concurrency: 1,
+            tabs: 1,
+            max_browser_relaunches: DEFAULT_MAX_BROWSER_RELAUNCHES,
+            geo: None,
+            timezone: None,
+            locale: None,
+            color_scheme: None,
+            save_har: false,
+            download_dir: None,
+            dedup_content: false,
+            respect_canonical: false,
+            no_discover: false,
+            kiosk: false,
+            discover_assets: false,
+            max_runtime_secs: None,
+            retry: None,
+            scan_url: None,
+            emit_sitemap: None,
+            export: ExportOptions::default(),
+            auth: None,
+            notifications: NotificationConfig::default(),
+        }
+    }
+
+    /// Reject combinations the engine can't run.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        if self.fetch_backend == FetchBackend::Http {
+            if self.recording_mode.records() {
+                return Err(EngineError::InvalidConfig(
+                    "the HTTP fetch backend can't record; use recording mode none".to_string(),
+                ));
+            }
+            if self.save_har {
+                return Err(EngineError::InvalidConfig(
+                    "HAR capture needs the browser fetch backend".to_string(),
+                ));
+            }
+            if self.contact_sheet.is_some() {
+                return Err(EngineError::InvalidConfig(
+                    "the contact sheet needs the browser fetch backend".to_string(),
+                ));
+            }
+            if self.screenshots_dir.is_some() {
+                return Err(EngineError::InvalidConfig(
+                    "page screenshots need the browser fetch backend".to_string(),
+                ));
+            }
+        }
+        if self.kiosk && !self.recording_mode.records() {
+            return Err(EngineError::InvalidConfig(
+                "kiosk mode needs a recording mode that records".to_string(),
+            ));
+        }
+        if self.kiosk && (self.sitemap.is_some() || !self.seed_urls.is_empty()) {
+            return Err(EngineError::InvalidConfig(
+                "kiosk mode records the start URL only; drop the sitemap and seed URLs".to_string(),
+            ));
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(EngineError::InvalidConfig(
+                "the memory limit must be at least 1 MB".to_string(),
+            ));
+        }
+        if self.export.formats.is_empty() {
+            return Err(EngineError::InvalidConfig(
+                "choose at least one data export format".to_string(),
+            ));
+        }
+        if self.record_first_n_pages == Some(0) {
+            return Err(EngineError::InvalidConfig(
+                "record at least one page, or use recording mode none".to_string(),
+            ));
+        }
+        if self.screenshot_format.quality().is_some_and(|quality| !(1..=100).contains(&quality)) {
+            return Err(EngineError::InvalidConfig(
+                "screenshot quality must be between 1 and 100".to_string(),
+            ));
+        }
+        if self.contact_sheet.is_some_and(|sheet| sheet.columns == 0 || sheet.rows == 0 || sheet.tile_width < 16) {
+            return Err(EngineError::InvalidConfig(
+                "the contact sheet needs at least one column and row and tiles at least 16 pixels wide".to_string(),
+            ));
+        }
+        for name in self.session_id.iter().chain(&self.session_prefix) {
+            if !is_valid_session_name(name) {
+                return Err(EngineError::InvalidConfig(format!(
+                    "session ID or prefix {:?} may only contain letters, digits, '-', '_' and '.'",
+                    name
+                )));
+            }
+        }
+        if let Err(e) = self.recording_config().audio_args() {
+            return Err(EngineError::InvalidConfig(e.to_string()));
+        }
+        for arg in &self.chrome_args {
+            browser::validate_chrome_arg(arg).map_err(EngineError::InvalidConfig)?;
+        }
+        if self.device_scale_factor.is_some_and(|scale| !DEVICE_SCALE_FACTORS.contains(&scale)) {
+            return Err(EngineError::InvalidConfig(format!(
+                "the device pixel ratio must be between {} and {}",
+                DEVICE_SCALE_FACTORS.start(),
+                DEVICE_SCALE_FACTORS.end()
+            )));
+        }
+        if self.http_timeout_secs == 0 {
+            return Err(EngineError::InvalidConfig(
+                "the HTTP timeout must be at least one second".to_string(),
+            ));
+        }
+        if self.frame_batch_size == 0 {
+            return Err(EngineError::InvalidConfig(
+                "the frame batch size must be at least one frame".to_string(),
+            ));
+        }
+        if self.intro_card.as_ref().is_some_and(|card| card.duration_secs == 0) {
+            return Err(EngineError::InvalidConfig(
+                "the intro card needs a duration of at least one second".to_string(),
+            ));
+        }
+        if self.window_position.is_some() || self.minimized {
+            if self.headless {
+                return Err(EngineError::InvalidConfig(
+                    "window position and minimizing only apply to a visible browser".to_string(),
+                ));
+            }
+            if self.recording_mode.records_screen() {
+                return Err(EngineError::InvalidConfig(
+                    "a moved or minimized window can't be screen recorded; use browser recording mode".to_string(),
+                ));
+            }
+        }
+        if let Err(e) = self.export.timestamps() {
+            return Err(EngineError::InvalidConfig(e.to_string()));
+        }
+        if self.recording_output.is_some() && !self.recording_mode.records_screen() {
+            return Err(EngineError::InvalidConfig(
+                "streaming the recording needs screen or both recording mode".to_string(),
+            ));
+        }
+        if self.tabs > 1 {
+            if self.recording_mode.records() {
+                return Err(EngineError::InvalidConfig(
+                    "crawling with several tabs needs recording mode none".to_string(),
+                ));
+            }
+            if self.fetch_backend == FetchBackend::Http {
+                return Err(EngineError::InvalidConfig(
+                    "tabs only apply to the browser fetch backend".to_string(),
+                ));
+            }
+            if self.save_har {
+                return Err(EngineError::InvalidConfig(
+                    "HAR capture follows a single tab; use one tab".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured session ID, or a new `<prefix>_<timestamp>_<suffix>`
+    /// one. The random hex suffix keeps runs started in the same second
+    /// apart.
+    pub fn new_session_id(&self) -> String {
+        if let Some(ref id) = self.session_id {
+            return id.clone();
+        }
+        format!(
+            "{}_{}_{:04x}",
+            self.session_prefix.as_deref().unwrap_or("session"),
+            chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+            fastrand::u16(..)
+        )
+    }
+
+    pub fn recording_config(&self) -> RecordingConfig {
+        RecordingConfig {
+            output_dir: self.output_dir.clone(),
+            format: VideoFormat::Mp4,
+            fps: self.fps,
+            quality: 80,
+            audio_enabled: self.audio_enabled,
+            mode: self.recording_mode.clone(),
+            screen_width: self.screen_width,
+            screen_height: self.screen_height,
+            screen_region: self.screen_region,
+            organize_by_session: self.organize_by_session,
+            finalize_timeout_secs: self.finalize_timeout_secs,
+            screenshot_format: self.screenshot_format,
+            output: self.recording_output.clone(),
+            max_file_size_mb: self.max_recording_size_mb,
+            memory_limit_mb: self.memory_limit_mb,
+            intro_card: self.intro_card.clone(),
+            frame_batch_size: self.frame_batch_size,
+            verify_output: self.verify_video,
+            audio_codec: self.audio_codec,
+            audio_bitrate_kbps: self.audio_bitrate_kbps,
+            frame_size: self.frame_size(),
+        }
+    }
+
+    /// Pixel size of full-page browser frames, when the viewport and pixel
+    /// ratio are both pinned.
+    fn frame_size(&self) -> Option<(u32, u32)> {
+        if !matches!(self.recording_mode, RecordingMode::Browser | RecordingMode::Both) {
+            return None;
+        }
+        let ((width, height), scale) = (self.viewport?, self.device_scale_factor?);
+        Some(((width as f64 * scale).round() as u32, (height as f64 * scale).round() as u32))
+    }
+
+    pub fn navigation_options(&self) -> NavigationOptions {
+        NavigationOptions {
+            scroll_behavior: self.scroll_behavior.clone(),
+            wait_for_text: self.wait_for_text.clone(),
+            pre_nav_script: self.pre_nav_script.clone(),
+            post_nav_script: self.post_nav_script.clone(),
+            detect_rate_limits: self.respect_rate_limits,
+            warm_up: self.warm_up,
+            post_nav_settle_ms: self.post_nav_settle_ms,
+            post_modal_settle_ms: self.post_modal_settle_ms,
+            ..NavigationOptions::default()
+        }
+    }
+
+    pub fn headless_mode(&self) -> HeadlessMode {
+        match (self.headless, self.new_headless) {
+            (false, _) => HeadlessMode::Disabled,
+            (true, false) => HeadlessMode::Legacy,
+            (true, true) => HeadlessMode::New,
+        }
+    }
+
+    /// Launch a browser with the configured headless mode and window size.
+    pub fn browser_config(&self) -> BrowserConfig {
+        BrowserConfig {
+            headless: self.headless_mode(),
+            window_size: self.viewport.unwrap_or(browser::DEFAULT_WINDOW_SIZE),
+            idle_timeout: match self.browser_idle_timeout_secs {
+                Some(secs) => Duration::from_secs(secs),
+                None => BrowserConfig::idle_timeout_for_delay(self.delay_ms),
+            },
+            ignore_cert_errors: self.insecure,
+            user_data_dir: self.profile_dir.clone(),
+            window_position: self.window_position,
+            minimized: self.minimized,
+            extra_chrome_args: self.chrome_args.clone(),
+        }
+    }
+
+    pub fn launch_browser(&self) -> Result<Browser, EngineError> {
+        Ok(Browser::with_config(&self.browser_config())?)
+    }
+
+    /// Navigate to a crawled page, applying the retry policy if one is set.
+    pub fn navigate(
+        &self,
+        browser: &Browser,
+        tab: &Arc<Tab>,
+        url: &str,
+        options: &NavigationOptions,
+    ) -> Result<(), BrowserError> {
+        match self.retry {
+            Some(ref policy) => browser.navigate_with_retry(tab, url, options, policy),
+            None => browser.navigate(tab, url, options),
+        }
+    }
+
+    /// Apply extra headers and viewport, geolocation, timezone, locale and
+    /// color-scheme overrides before the first navigation.
+    pub fn apply_emulation(&self, browser: &Browser, tab: &Arc<Tab>) -> Result<(), EngineError> {
+        if !self.extra_headers.is_empty() {
+            browser.set_extra_headers(tab, &self.extra_headers)?;
+        }
+        if self.viewport.is_some() || self.device_scale_factor.is_some() {
+            browser.set_device_metrics(tab, self.viewport, self.device_scale_factor)?;
+        }
+        if let Some((latitude, longitude, accuracy)) = self.geo {
+            browser.set_geolocation(tab, latitude, longitude, accuracy)?;
+        }
+        if let Some(ref timezone) = self.timezone {
+            browser.set_timezone(tab, timezone)?;
+        }
+        if let Some(ref locale) = self.locale {
+            browser.set_locale(tab, locale)?;
+        }
+        if let Some(scheme) = self.color_scheme {
+            browser.set_color_scheme(tab, scheme)?;
+        }
+        Ok(())
+    }
+}
+
+/// Default for `EngineConfig::max_browser_relaunches`.
+pub const DEFAULT_MAX_BROWSER_RELAUNCHES: u32 = 3;
+
+/// Device pixel ratios accepted for `EngineConfig::device_scale_factor`.
+pub const DEVICE_SCALE_FACTORS: std::ops::RangeInclusive<f64> = 0.5..=4.0;
+
+/// Whether `name` is safe as a session directory and file name prefix.
+fn is_valid_session_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn default_tabs() -> usize {
+    1
+}
+
+fn default_max_browser_relaunches() -> u32 {
+    DEFAULT_MAX_BROWSER_RELAUNCHES
+}
+
+fn default_respect_rate_limits() -> bool {
+    true
+}
+
+fn default_http_timeout_secs() -> u64 {
+    crawler::DEFAULT_REQUEST_TIMEOUT.as_secs()
+}
+
+fn default_aux_retries() -> u32 {
+    crawler::DEFAULT_AUX_RETRIES
+}
+
+fn default_frame_batch_size() -> u64 {
+    recorder::DEFAULT_FRAME_BATCH_SIZE
+}
+
+fn default_post_nav_settle_ms() -> u64 {
+    browser::DEFAULT_POST_NAV_SETTLE_MS
+}
+
+fn default_post_modal_settle_ms() -> u64 {
+    browser::DEFAULT_POST_MODAL_SETTLE_MS
+}
+
+/// Login performed once, before the crawl starts.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    pub login_url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub username_selector: Option<String>,
+    pub password_selector: Option<String>,
+    pub submit_selector: Option<String>,
+    /// Custom JavaScript run instead of filling the form. Credentials are
+    /// exposed to it as `window.__SR_USER` and `window.__SR_PASS`.
+    pub login_script: Option<String>,
+    pub totp_secret: Option<String>,
+    pub totp_field_selector: Option<String>,
+    #[serde(default)]
+    pub post_login_wait: session::PostLoginWait,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_backend_rejects_recording() {
+        let mut config = EngineConfig::new("https://example.com");
+        config.fetch_backend = FetchBackend::Http;
+        assert!(matches!(config.validate(), Err(EngineError::InvalidConfig(_))));
+
+        config.recording_mode = RecordingMode::None;
+        assert!(config.validate().is_ok());
+
+        config.save_har = true;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_tabs_require_recording_free_browser_crawl() {
+        let mut config = EngineConfig::new("https://example.com");
+        config.tabs = 4;
+        assert!(matches!(config.validate(), Err(EngineError::InvalidConfig(_))));
+
+        config.recording_mode = RecordingMode::None;
+        assert!(config.validate().is_ok());
+
+        config.fetch_backend = FetchBackend::Http;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_hidden_window_rejects_screen_recording() {
+        let mut config = EngineConfig::new("https://example.com");
+        config.minimized = true;
+        assert!(matches!(config.validate(), Err(EngineError::InvalidConfig(_))));
+
+        config.recording_mode = RecordingMode::Browser;
+        assert!(config.validate().is_ok());
+
+        config.headless = true;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_device_scale_factor_frame_size() {
+        let mut config = EngineConfig::new("https://example.com");
+        config.viewport = Some((1280, 800));
+        config.device_scale_factor = Some(2.0);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.recording_config().frame_size, Some((2560, 1600)));
+
+        config.recording_mode = RecordingMode::Screen;
+        assert_eq!(config.recording_config().frame_size, None);
+
+        config.device_scale_factor = Some(0.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_kiosk_needs_recording_and_single_url() {
+        let mut config = EngineConfig::new("https://example.com/status");
+        config.kiosk = true;
+        assert!(config.validate().is_ok());
+
+        config.seed_urls = vec!["https://example.com/other".to_string()];
+        assert!(config.validate().is_err());
+
+        config.seed_urls.clear();
+        config.recording_mode = RecordingMode::None;
+        assert!(matches!(config.validate(), Err(EngineError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_session_ids() {
+        let mut config = EngineConfig::new("https://example.com");
+        config.session_prefix = Some("docs".to_string());
+        let id = config.new_session_id();
+        assert!(id.starts_with("docs_"));
+        assert_eq!(id.len(), "docs_20240101_120000_abcd".len());
+        assert_ne!(
+            (0..8).map(|_| config.new_session_id()).collect::<std::collections::HashSet<_>>().len(),
+            1
+        );
+
+        config.session_id = Some("release-2.1".to_string());
+        assert_eq!(config.new_session_id(), "release-2.1");
+        assert!(config.validate().is_ok());
+
+        config.session_id = Some("../escape".to_string());
+        assert!(config.validate().is_err());
+    }
+}
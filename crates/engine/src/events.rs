@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use recorder::RecorderEvent;
+use serde::Serialize;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Structured progress events emitted by the crawl loop.
+///
+/// Front-ends (the TUI dashboard, the GUI, metrics) consume these instead of
+/// parsing log output. The GUI receives them as Tauri events named by
+/// [`CrawlEvent::channel`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum CrawlEvent {
+    PageStarted {
+        url: String,
+        page_number: usize,
+    },
+    PageVisited {
+        url: String,
+        pages_visited: usize,
+        pages_discovered: usize,
+        pages_remaining: usize,
+    },
+    LinksFound {
+        url: String,
+        count: usize,
+    },
+    NavigationFailed {
+        url: String,
+        error: String,
+    },
+    /// A concurrent link-discovery worker started fetching `url`.
+    WorkerFetching {
+        worker: usize,
+        url: String,
+    },
+    /// A link-discovery worker found nothing queued.
+    WorkerIdle {
+        worker: usize,
+    },
+    Recorder(RecorderEvent),
+    /// Recorder totals sampled after each page, and every few seconds while
+    /// the crawl waits.
+    RecorderStats {
+        duration_secs: u64,
+        frames_captured: u64,
+    },
+    Error {
+        message: String,
+    },
+    Finished {
+        pages_visited: usize,
+    },
+}
+
+impl CrawlEvent {
+    /// Event name this event is published under by the GUI.
+    pub fn channel(&self) -> &'static str {
+        match self {
+            CrawlEvent::PageStarted { .. } | CrawlEvent::PageVisited { .. } => "crawl://page",
+            CrawlEvent::LinksFound { .. } => "crawl://links",
+            CrawlEvent::WorkerFetching { .. } | CrawlEvent::WorkerIdle { .. } => "crawl://workers",
+            CrawlEvent::Recorder(_) => "crawl://recording",
+            CrawlEvent::RecorderStats { .. } => "crawl://stats",
+            CrawlEvent::NavigationFailed { .. } | CrawlEvent::Error { .. } => "crawl://error",
+            CrawlEvent::Finished { .. } => "crawl://finished",
+        }
+    }
+}
+
+type Handler = Arc<dyn Fn(&CrawlEvent) + Send + Sync>;
+
+/// Cheap, cloneable handle the crawl loop uses to publish [`CrawlEvent`]s
+/// into a channel and/or to registered handlers. A disabled sink drops
+/// every event.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    tx: Option<UnboundedSender<CrawlEvent>>,
+    handlers: Vec<Handler>,
+}
+
+impl EventSink {
+    pub fn channel() -> (Self, UnboundedReceiver<CrawlEvent>) {
+        let (tx, rx) = unbounded_channel();
+        (Self { tx: Some(tx), ..Self::default() }, rx)
+    }
+
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Also call `handler` for every event, before it is sent to the channel.
+    pub fn with_handler(mut self, handler: impl Fn(&CrawlEvent) + Send + Sync + 'static) -> Self {
+        self.handlers.push(Arc::new(handler));
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.tx.is_some() || !self.handlers.is_empty()
+    }
+
+    pub fn emit(&self, event: CrawlEvent) {
+        for handler in &self.handlers {
+            handler(&event);
+        }
+        if let Some(ref tx) = self.tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    /// Forward recorder lifecycle events into this sink until the
+    /// recorder is dropped.
+    pub fn forward_recorder(&self, mut events: tokio::sync::broadcast::Receiver<RecorderEvent>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let sink = self.clone();
+        tokio::spawn(async move {
+            use tokio::sync::broadcast::error::RecvError;
+            loop {
+                match events.recv().await {
+                    Ok(event) => sink.emit(CrawlEvent::Recorder(event)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_sink_reaches_handlers_and_channel() {
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = seen.clone();
+        let (sink, mut rx) = EventSink::channel();
+        let sink = sink.with_handler(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        sink.emit(CrawlEvent::Finished { pages_visited: 3 });
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+        assert!(matches!(rx.recv().await, Some(CrawlEvent::Finished { pages_visited: 3 })));
+        assert!(!EventSink::disabled().is_enabled());
+    }
+}
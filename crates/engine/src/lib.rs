@@ -0,0 +1,1166 @@
+//! Crawl-and-record pipeline as a library: wires the browser, crawler,
+//! recorder, notifier, exporter and scanner crates together behind
+//! [`Engine`], so front-ends only have to build an [`EngineConfig`].
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use browser::{run_processors, Browser, BrowserError, Har, PageMeta, PageProcessor};
+use crawler::{CrawlConfig, Crawler, PageLinks};
+use exporter::{Exporter, FailureRecord, RecordingData};
+use headless_chrome::protocol::cdp::Network::Cookie;
+use headless_chrome::Tab;
+use notifier::{NotificationLevel, Notifier, RunSummary};
+use recorder::{ContactSheet, Recorder};
+use scanner::{ScanConfig, ScanReport, VulnerabilityScanner};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+pub mod auth;
+pub use auth::{log_in, LoginMethod, DEFAULT_TOTP_SELECTOR};
+pub mod config;
+pub use config::{AuthConfig, EngineConfig, FetchBackend};
+pub mod events;
+pub use events::{CrawlEvent, EventSink};
+pub mod resume;
+pub use resume::{saved_crawl_path, SavedCrawl};
+pub mod screenshots;
+pub use screenshots::save_page_screenshot;
+pub mod stats;
+pub use stats::{HostBreakdown, HostStats};
+pub mod throttle;
+pub use throttle::{delay_rng, jittered_delay, RateLimiter};
+
+#[derive(Debug, Error)]
+pub enum EngineError {
+    #[error(transparent)]
+    Browser(#[from] browser::BrowserError),
+    #[error(transparent)]
+    Crawler(#[from] crawler::CrawlerError),
+    #[error(transparent)]
+    Recorder(#[from] recorder::RecorderError),
+    #[error(transparent)]
+    Export(#[from] exporter::ExportError),
+    #[error(transparent)]
+    Scan(#[from] scanner::ScanError),
+    #[error(transparent)]
+    Notifier(#[from] notifier::NotifierError),
+    #[error(transparent)]
+    Session(#[from] session::SessionError),
+    #[error("Login failed: {0}")]
+    Login(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl EngineError {
+    /// HTTP status of the response behind this error, if there was one.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            EngineError::Crawler(crawler::CrawlerError::NetworkError(e)) => e.status().map(|status| status.as_u16()),
+            _ if self.rate_limit().is_some() => Some(429),
+            _ => None,
+        }
+    }
+
+    /// For a 429 response, `Some` with its `Retry-After` header, if any.
+    pub fn rate_limit(&self) -> Option<Option<&str>> {
+        match self {
+            EngineError::Browser(BrowserError::RateLimited { retry_after, .. })
+            | EngineError::Crawler(crawler::CrawlerError::RateLimited { retry_after, .. }) => Some(retry_after.as_deref()),
+            _ => None,
+        }
+    }
+}
+
+/// Why a crawl ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    MaxPages,
+    MaxRuntime,
+    SizeLimit,
+    Stopped,
+    Exhausted,
+    /// The browser crashed more often than it may be relaunched.
+    BrowserCrashed,
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StopReason::MaxPages => "page limit reached",
+            StopReason::MaxRuntime => "time limit reached",
+            StopReason::SizeLimit => "recording size limit reached",
+            StopReason::Stopped => "stop requested",
+            StopReason::Exhausted => "no more URLs to crawl",
+            StopReason::BrowserCrashed => "browser crashed",
+        })
+    }
+}
+
+/// Outcome of a successful [`Engine::run`].
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub session_id: String,
+    pub pages_visited: usize,
+    pub pages_failed: usize,
+    /// Why each failed page failed, in crawl order.
+    pub failures: Vec<FailureRecord>,
+    /// Pages whose content matched an earlier page, so their links were skipped.
+    pub pages_duplicate: usize,
+    /// URLs the crawler found, visited or not.
+    pub pages_discovered: usize,
+    /// Pages, failures and load time per host, most pages first.
+    pub hosts: Vec<(String, HostStats)>,
+    pub stop_reason: StopReason,
+    pub duration_secs: u64,
+    /// `None` when recording was disabled.
+    pub video_path: Option<PathBuf>,
+    /// Why the video looks broken, if it does. See [`recorder::check_capture`].
+    pub capture_warnings: Vec<String>,
+    /// The data exports, one per format in `ExportOptions::formats`.
+    pub export_paths: Vec<PathBuf>,
+    pub har_path: Option<PathBuf>,
+    /// The sitemap, or sitemap index when it had to be split.
+    pub sitemap_path: Option<PathBuf>,
+    /// One labeled thumbnail per page, when a contact sheet was requested.
+    pub contact_sheet_path: Option<PathBuf>,
+    pub scan_path: Option<PathBuf>,
+    pub scan: Option<ScanReport>,
+}
+
+/// Runs one crawl: launches the browser, logs in, records while visiting
+/// pages, then exports the collected data and optionally scans the site.
+pub struct Engine {
+    config: EngineConfig,
+    events: EventSink,
+    processors: Vec<Box<dyn PageProcessor>>,
+    stop_flags: Vec<Arc<AtomicBool>>,
+    pause_flag: Option<Arc<AtomicBool>>,
+}
+
+impl Engine {
+    pub fn new(config: EngineConfig) -> Self {
+        Self {
+            config,
+            events: EventSink::disabled(),
+            processors: Vec::new(),
+            stop_flags: Vec::new(),
+            pause_flag: None,
+        }
+    }
+
+    /// Publish progress to `events`.
+    pub fn with_events(mut self, events: EventSink) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Run `processor` on every page, after those added before it.
+    pub fn with_processor(mut self, processor: Box<dyn PageProcessor>) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Stop the crawl gracefully once `flag` is set. May be called more
+    /// than once; any flag stops the run.
+    pub fn with_stop_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.stop_flags.push(flag);
+        self
+    }
+
+    /// Hold the crawl between pages, with the recording paused, while
+    /// `flag` is set.
+    pub fn with_pause_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.pause_flag = Some(flag);
+        self
+    }
+
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// Run the crawl to completion. Failures are reported through the
+    /// configured notification channels before being returned, and
+    /// pending remote deliveries are flushed either way.
+    pub async fn run(&self) -> Result<RunReport, EngineError> {
+        let notifier = Arc::new(Notifier::new(self.config.notifications.clone()));
+        let result = self.crawl(&notifier).await;
+        if let Err(ref e) = result {
+            let _ = notifier.notify_error("Crawl Failed", &e.to_string());
+        }
+        let flush = notifier.clone();
+        let _ = tokio::task::spawn_blocking(move || flush.wait_for_deliveries()).await;
+        result
+    }
+
+    fn stop_requested(&self) -> bool {
+        self.stop_flags.iter().any(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    fn pause_requested(&self) -> bool {
+        self.pause_flag.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    async fn crawl(&self, notifier: &Notifier) -> Result<RunReport, EngineError> {
+        let settings = &self.config;
+        let events = &self.events;
+        settings.validate()?;
+        let session_id = settings.new_session_id();
+        let http_only = settings.fetch_backend == FetchBackend::Http;
+        if settings.export.legacy_json {
+            warn!("The bare-array JSON export is deprecated; consumers should read the versioned export");
+        }
+        if settings.pause_during_delay && settings.recording_mode.records_screen() {
+            warn!("Pausing a screen capture only freezes its frame; the waits between pages stay in the video");
+        }
+        if settings.insecure {
+            warn!("!!! TLS certificate errors are IGNORED for this crawl (--insecure); only use this with sites you trust !!!");
+        }
+
+        // The HTTP backend only needs a browser to log in, then carries the
+        // session over in its own requests.
+        let http_session = match settings.auth {
+            Some(ref auth) if http_only => match http_session(settings, auth).await {
+                Ok(session) => {
+                    info!("Login successful!");
+                    Some(session)
+                }
+                Err(e) => {
+                    warn!("Login failed: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let browser = if http_only {
+            info!("Fetching pages over HTTP, without a browser");
+            None
+        } else {
+            info!("Initializing browser...");
+            Some(settings.launch_browser()?)
+        };
+
+        info!("Setting up crawler...");
+        let crawl_config = CrawlConfig::new(&settings.url)?;
+        let crawl_config = match http_session {
+            Some((_, ref user_agent)) => crawl_config.with_user_agent(user_agent),
+            None => crawl_config,
+        };
+        let crawl_config = if let Some(ref proxy) = settings.proxy {
+            crawl_config.with_proxy(proxy)
+        } else {
+            crawl_config
+        };
+        let crawl_config = if let Some(ref sitemap) = settings.sitemap {
+            crawl_config.with_sitemap(sitemap)
+        } else {
+            crawl_config
+        };
+        let crawl_config = crawl_config.with_concurrency(settings.concurrency);
+        let crawl_config = if settings.allowed_domains.is_empty() {
+            crawl_config
+        } else {
+            crawl_config.with_allowed_domains(settings.allowed_domains.clone())
+        };
+        let crawl_config = if settings.discover_assets {
+            crawl_config.with_asset_discovery()
+        } else {
+            crawl_config
+        };
+        let crawl_config = if settings.dedup_content {
+            crawl_config.with_content_dedup()
+        } else {
+            crawl_config
+        };
+        let crawl_config = if settings.respect_canonical {
+            crawl_config.with_canonical_dedup()
+        } else {
+            crawl_config
+        };
+        let crawl_config = if settings.no_discover || settings.kiosk {
+            crawl_config.with_no_discover()
+        } else {
+            crawl_config
+        };
+        let crawl_config = crawl_config
+            .with_trap_limits(settings.trap_limits)
+            .with_extra_headers(settings.extra_headers.clone())
+            .with_request_timeout(Duration::from_secs(settings.http_timeout_secs))
+            .with_aux_retries(settings.aux_retries);
+        let crawl_config = if settings.insecure {
+            crawl_config.with_invalid_certs_accepted()
+        } else {
+            crawl_config
+        };
+        let mut crawler = Crawler::new(crawl_config);
+        if !settings.seed_urls.is_empty() {
+            crawler.seed_from_list(&settings.seed_urls);
+        }
+        if let Some((ref cookies, _)) = http_session {
+            for cookie in cookies {
+                if let Some((set_cookie, url)) = set_cookie_header(cookie) {
+                    crawler.add_cookie(&set_cookie, &url);
+                }
+            }
+        }
+        // A session run again after an interruption carries on from there
+        let saved_path = saved_crawl_path(&settings.recording_config().session_dir(&session_id), &session_id);
+        let resumed_pages = match SavedCrawl::load(&saved_path) {
+            Some(saved) => {
+                info!("Continuing session {} after {} pages", session_id, saved.pages_visited);
+                crawler.restore_state(&saved.state);
+                saved.pages_visited
+            }
+            None => 0,
+        };
+        let crawler = Arc::new(Mutex::new(crawler));
+
+        // Ingest sitemap if provided
+        if settings.sitemap.is_some() {
+            if let Ok(count) = crawler.lock().await.ingest_sitemap().await {
+                info!("Ingested {} URLs from sitemap", count);
+            }
+        }
+
+        // Spawn concurrent prefetch workers
+        let concurrency = settings.concurrency.max(1);
+        let prefetch_active = Arc::new(AtomicBool::new(true));
+        let mut worker_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+        if concurrency > 1 {
+            for worker in 0..concurrency {
+                let crawler_clone = crawler.clone();
+                let active = prefetch_active.clone();
+                let events = events.clone();
+                worker_handles.push(tokio::spawn(async move {
+                    let mut idle = true;
+                    loop {
+                        let url = { crawler_clone.lock().await.next_prefetch_url() };
+                        match url {
+                            Some(u) => {
+                                idle = false;
+                                events.emit(CrawlEvent::WorkerFetching { worker, url: u.clone() });
+                                let links = crawler_clone.lock().await.prefetch_links(&u).await;
+                                crawler_clone.lock().await.add_discovered_links(links);
+                            }
+                            None => {
+                                if !idle {
+                                    idle = true;
+                                    events.emit(CrawlEvent::WorkerIdle { worker });
+                                }
+                                if !active.load(Ordering::SeqCst) {
+                                    break;
+                                }
+                                tokio::time::sleep(Duration::from_millis(150)).await;
+                            }
+                        }
+                    }
+                }));
+            }
+            info!("Started {} concurrent crawl workers", concurrency);
+        }
+
+        let page = match browser {
+            Some(browser) => {
+                let tab = browser.get_tab()?;
+                Some((browser, tab))
+            }
+            None => None,
+        };
+
+        info!("Configuring recorder...");
+        let mut recording_config = settings.recording_config();
+        if settings.capture_window && settings.recording_mode.records_screen() {
+            match page {
+                Some((ref browser, ref tab)) => match browser.get_window_region(tab) {
+                    Ok(region) => {
+                        info!("Recording the browser window at {:?}", region);
+                        recording_config.screen_region = Some(region);
+                    }
+                    Err(e) => warn!("Could not find the browser window ({}); recording the full screen", e),
+                },
+                None => warn!("No browser window to capture; recording the full screen"),
+            }
+        }
+        let session_dir = recording_config.session_dir(&session_id);
+        let recorder = Recorder::new(recording_config);
+        events.forward_recorder(recorder.subscribe());
+
+        let mut page = match page {
+            Some((browser, tab)) => {
+                settings.apply_emulation(&browser, &tab)?;
+                browser.set_download_dir(&tab, settings.download_dir.as_deref())?;
+                if settings.save_har {
+                    browser.start_har_capture(&tab)?;
+                }
+                recorder.set_browser_tab(tab.clone()).await;
+                recorder.set_browser_pid(browser.process_id()).await;
+                Some((browser, tab))
+            }
+            None => None,
+        };
+
+        let mut extra_tabs = match page {
+            Some((ref browser, _)) if settings.tabs > 1 => {
+                let tabs = browser.get_tabs(settings.tabs - 1)?;
+                for tab in &tabs {
+                    settings.apply_emulation(browser, tab)?;
+                    browser.set_download_dir(tab, settings.download_dir.as_deref())?;
+                }
+                info!("Crawling with {} tabs", settings.tabs);
+                tabs
+            }
+            _ => Vec::new(),
+        };
+        let nav_options = &settings.navigation_options();
+
+        let mut contact_sheet = match settings.contact_sheet {
+            Some(config) => Some(ContactSheet::new(
+                session_dir.join(format!("{}_thumbnails", session_id)),
+                config,
+            )?),
+            None => None,
+        };
+
+        info!("Starting recording...");
+        recorder.start_recording(session_id.clone(), Some(settings.url.clone())).await?;
+
+        if let (Some(ref auth), Some((ref browser, ref tab))) = (&settings.auth, &page) {
+            match log_in(browser, tab, auth, nav_options).await {
+                Ok(_) => info!("Login successful!"),
+                Err(e) => warn!("Login failed: {}", e),
+            }
+        }
+
+        info!("Beginning crawl...");
+        if let Err(e) = notifier.send_with_fields(
+            "Crawl Started",
+            &format!("Started crawling {}", settings.url),
+            NotificationLevel::Info,
+            vec![("Session ID", session_id.clone())],
+        ) {
+            let _ = recorder.stop_recording().await;
+            return Err(e.into());
+        }
+        let mut pages_visited = 0;
+        let mut pages_failed = 0;
+        let mut pages_duplicate = 0;
+        let mut hosts = HostBreakdown::default();
+        let mut rate_limiter = RateLimiter::default();
+        let mut rng = delay_rng(settings.seed);
+        let mut recording_data = Vec::new();
+        let mut browser_relaunches = 0;
+        // Requests captured by browsers that crashed, kept for the HAR
+        let mut har = None;
+
+        let crawl_started = Instant::now();
+        let mut last_heartbeat = crawl_started;
+        let max_runtime = settings.max_runtime_secs.map(Duration::from_secs);
+        // Any error from here on must stop the recorder, or FFmpeg and the
+        // capture task outlive the crawl
+        let crawled = async {
+            Ok::<_, EngineError>(loop {
+                if pages_visited >= settings.max_pages && !settings.kiosk {
+                    break StopReason::MaxPages;
+                }
+                if max_runtime.is_some_and(|limit| crawl_started.elapsed() >= limit) {
+                    break StopReason::MaxRuntime;
+                }
+                if recorder.size_limit_reached() {
+                    break StopReason::SizeLimit;
+                }
+                if self.stop_requested() {
+                    info!("Shutdown signal received, stopping crawl gracefully");
+                    break StopReason::Stopped;
+                }
+                if self.pause_requested() {
+                    if recorder.is_recording() && !recorder.is_paused() {
+                        info!("Crawl paused");
+                        recorder.pause_recording().await?;
+                    }
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                    continue;
+                }
+                if recorder.is_paused() {
+                    info!("Crawl resumed");
+                    recorder.resume_recording().await?;
+                }
+                // In kiosk mode the one page stays up, recording, until a limit
+                // above ends the run
+                if settings.kiosk && pages_visited > 0 {
+                    if last_heartbeat.elapsed() >= WAIT_HEARTBEAT {
+                        emit_recorder_stats(events, &recorder).await;
+                        last_heartbeat = Instant::now();
+                    }
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                    continue;
+                }
+
+                let tabs: Vec<&Arc<Tab>> = page.iter().map(|(_, tab)| tab).chain(&extra_tabs).collect();
+                let batch = {
+                    let mut crawler = crawler.lock().await;
+                    let size = tabs.len().max(1).min(settings.max_pages - pages_visited);
+                    std::iter::from_fn(|| crawler.get_next_url()).take(size).collect::<Vec<_>>()
+                };
+                if batch.is_empty() {
+                    info!("No more URLs to crawl");
+                    break StopReason::Exhausted;
+                }
+                for (i, url) in batch.iter().enumerate() {
+                    info!("[{}/{}] Crawling: {}", pages_visited + i + 1, settings.max_pages, url);
+                    events.emit(CrawlEvent::PageStarted {
+                        url: url.clone(),
+                        page_number: pages_visited + i + 1,
+                    });
+                }
+
+                // Load the batch with one page per tab in parallel, then handle
+                // the results in frontier order.
+                let loads: Vec<PageLoad> = match page {
+                    Some((ref browser, _)) => std::thread::scope(|scope| {
+                        let handles: Vec<_> = batch
+                            .into_iter()
+                            .zip(tabs.iter().copied())
+                            .map(|(url, tab)| {
+                                scope.spawn(move || {
+                                    let started = Instant::now();
+                                    let fetched = settings
+                                        .navigate(browser, tab, &url, nav_options)
+                                        .map(|_| browser.get_all_frame_content(tab).ok())
+                                        .map_err(EngineError::from);
+                                    (url, Some(tab), started.elapsed(), fetched)
+                                })
+                            })
+                            .collect();
+                        handles
+                            .into_iter()
+                            .map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                            .collect()
+                    }),
+                    None => {
+                        let mut loads = Vec::new();
+                        for url in batch {
+                            let started = Instant::now();
+                            let fetched = crawler
+                                .lock()
+                                .await
+                                .fetch_page(&url)
+                                .await
+                                .map(|html| Some(vec![(url.clone(), html)]))
+                                .map_err(EngineError::from);
+                            loads.push((url, None, started.elapsed(), fetched));
+                        }
+                        loads
+                    }
+                };
+
+                let mut any_loaded = false;
+                let mut browser_crashed = false;
+                for (url, tab, elapsed, fetched) in loads {
+                    match fetched {
+                        Ok(frames) => {
+                            hosts.record(&url, true, elapsed);
+                            rate_limiter.succeeded(&url);
+                            // Element recording starts on a blank tab, so the
+                            // selector can only be checked once a page is up
+                            if pages_visited == 0 {
+                                recorder.check_element().await?;
+                            }
+                            let meta = match (&page, tab, &frames) {
+                                (Some((browser, _)), Some(tab), _) => browser.get_page_meta(tab).unwrap_or_else(|e| {
+                                    debug!("  Could not read page metadata: {}", e);
+                                    PageMeta::default()
+                                }),
+                                (_, _, Some(frames)) => PageMeta::from_html(&frames[0].1, &url),
+                                _ => PageMeta::default(),
+                            };
+                            let mut metadata = serde_json::to_value(&meta)?;
+                            metadata["page_number"] = serde_json::json!(pages_visited + 1);
+                            if settings.recording_mode.records() {
+                                note_video_offset(&recorder, &mut metadata).await;
+                            }
+                            if let (Some((browser, _)), Some(tab)) = (&page, tab) {
+                                if let Some(response) = browser.last_document_response(tab) {
+                                    metadata["status"] = serde_json::json!(response.status);
+                                }
+                                if let Some(ref dir) = settings.screenshots_dir {
+                                    match save_page_screenshot(browser, tab, dir, &url) {
+                                        Ok(path) => metadata["screenshot"] = serde_json::json!(path),
+                                        Err(e) => warn!("  Could not save a page screenshot: {}", e),
+                                    }
+                                }
+                                if let Ok(landed) = browser.get_current_url(tab) {
+                                    note_redirect(&mut *crawler.lock().await, &url, &landed, &mut metadata);
+                                }
+                            }
+                            recording_data.push(RecordingData {
+                                session_id: session_id.clone(),
+                                timestamp: chrono::Utc::now(),
+                                url: url.clone(),
+                                action: "navigate".to_string(),
+                                metadata,
+                                success: true,
+                            });
+
+                            // Run page processors and discover links in every readable frame
+                            if let Some(frames) = frames {
+                                let duplicate_of = {
+                                    let mut crawler = crawler.lock().await;
+                                    match crawler.check_canonical(&url, meta.canonical.as_deref()) {
+                                        Some(canonical) => Some((canonical, "canonical")),
+                                        None => crawler.check_duplicate(&url, &frames[0].1).map(|first| (first, "content")),
+                                    }
+                                };
+                                if let Some((first, reason)) = duplicate_of {
+                                    info!("  Duplicate of {} ({}), skipping links", first, reason);
+                                    pages_duplicate += 1;
+                                    recording_data.push(RecordingData {
+                                        session_id: session_id.clone(),
+                                        timestamp: chrono::Utc::now(),
+                                        url: url.clone(),
+                                        action: "duplicate".to_string(),
+                                        metadata: serde_json::json!({
+                                            "duplicate_of": first,
+                                            "reason": reason,
+                                        }),
+                                        success: true,
+                                    });
+                                } else {
+                                    recording_data.extend(run_processors(&self.processors, &session_id, &url, &frames[0].1, tab));
+                                    let PageLinks { links, assets } = frame_links(&*crawler.lock().await, &frames);
+                                    info!("  Found {} links", links.len());
+                                    events.emit(CrawlEvent::LinksFound {
+                                        url: url.clone(),
+                                        count: links.len(),
+                                    });
+                                    if !assets.is_empty() {
+                                        info!("  Found {} assets", assets.len());
+                                        recording_data.push(RecordingData {
+                                            session_id: session_id.clone(),
+                                            timestamp: chrono::Utc::now(),
+                                            url: url.clone(),
+                                            action: "assets".to_string(),
+                                            metadata: serde_json::json!({
+                                                "assets": assets,
+                                            }),
+                                            success: true,
+                                        });
+                                    }
+                                    let mut crawler = crawler.lock().await;
+                                    crawler.add_discovered_links(links);
+                                    crawler.add_discovered_assets(assets);
+                                }
+                            }
+
+                            if let (Some(sheet), Some(tab)) = (contact_sheet.as_mut(), tab) {
+                                if let Err(e) = sheet.add_page(tab, &url) {
+                                    warn!("  Could not capture a contact sheet thumbnail: {}", e);
+                                }
+                            }
+
+                            crawler.lock().await.mark_visited(&url);
+                            pages_visited += 1;
+                            if settings.record_first_n_pages == Some(pages_visited) && recorder.is_recording() {
+                                info!("Recorded {} pages; crawling on without recording", pages_visited);
+                                recorder.stop_recording_after_pages(pages_visited).await?;
+                            }
+                            {
+                                let crawler_guard = crawler.lock().await;
+                                events.emit(CrawlEvent::PageVisited {
+                                    url: url.clone(),
+                                    pages_visited,
+                                    pages_discovered: crawler_guard.get_discovered_count(),
+                                    pages_remaining: crawler_guard.get_remaining_count(),
+                                });
+                            }
+                            emit_recorder_stats(events, &recorder).await;
+                            any_loaded = true;
+                        }
+                        Err(EngineError::Browser(BrowserError::Download(download))) => {
+                            info!("  Downloaded {} instead of a page", download.suggested_filename);
+                            hosts.record(&url, true, elapsed);
+                            recording_data.push(RecordingData {
+                                session_id: session_id.clone(),
+                                timestamp: chrono::Utc::now(),
+                                url: url.clone(),
+                                action: "download".to_string(),
+                                metadata: serde_json::json!({
+                                    "filename": download.suggested_filename,
+                                    "path": download.path,
+                                }),
+                                success: true,
+                            });
+                            crawler.lock().await.mark_visited(&url);
+                        }
+                        Err(e) => {
+                            if let (Some((browser, _)), Some(tab)) = (&page, tab) {
+                                if !browser.is_responsive(tab) {
+                                    warn!("  The browser stopped responding while loading {}: {}", url, e);
+                                    crawler.lock().await.requeue(&url);
+                                    browser_crashed = true;
+                                    continue;
+                                }
+                            }
+                            if let Some(retry_after) = e.rate_limit().filter(|_| settings.respect_rate_limits) {
+                                match rate_limiter.wait_for(&url, retry_after) {
+                                    Some(wait) => {
+                                        warn!("  Rate limited; retrying in {:?}", wait);
+                                        recording_data.push(RecordingData {
+                                            session_id: session_id.clone(),
+                                            timestamp: chrono::Utc::now(),
+                                            url: url.clone(),
+                                            action: "rate_limited".to_string(),
+                                            metadata: serde_json::json!({
+                                                "status": 429,
+                                                "retry_after": retry_after,
+                                                "wait_secs": wait.as_secs(),
+                                            }),
+                                            success: true,
+                                        });
+                                        wait_with_heartbeat(events, &recorder, wait).await;
+                                        crawler.lock().await.requeue(&url);
+                                        continue;
+                                    }
+                                    None => warn!(
+                                        "  Rate limit wait budget of {:?} used up; giving up on {}",
+                                        throttle::RATE_LIMIT_MAX_TOTAL_WAIT,
+                                        url
+                                    ),
+                                }
+                            }
+                            warn!("  Failed to navigate: {}", e);
+                            hosts.record(&url, false, elapsed);
+                            pages_failed += 1;
+                            let mut metadata = serde_json::json!({
+                                "error": e.to_string(),
+                            });
+                            if let Some(status) = e.http_status() {
+                                metadata["status"] = serde_json::json!(status);
+                            }
+                            recording_data.push(RecordingData {
+                                session_id: session_id.clone(),
+                                timestamp: chrono::Utc::now(),
+                                url: url.clone(),
+                                action: "navigate".to_string(),
+                                metadata,
+                                success: false,
+                            });
+                            events.emit(CrawlEvent::NavigationFailed {
+                                url: url.clone(),
+                                error: e.to_string(),
+                            });
+                            crawler.lock().await.mark_visited(&url);
+                        }
+                    }
+                }
+
+                // Saved once the whole batch is handled, when every URL it
+                // took is either visited or back in the frontier
+                let saved = SavedCrawl {
+                    pages_visited: resumed_pages + pages_visited,
+                    state: crawler.lock().await.state(),
+                };
+                if let Err(e) = saved.save(&saved_path) {
+                    warn!("Could not save the crawl state: {}", e);
+                }
+
+                if browser_crashed {
+                    if browser_relaunches >= settings.max_browser_relaunches {
+                        error!(
+                            "The browser crashed and has already been relaunched {} times; giving up",
+                            browser_relaunches
+                        );
+                        break StopReason::BrowserCrashed;
+                    }
+                    browser_relaunches += 1;
+                    warn!(
+                        "Relaunching the browser ({} of {})",
+                        browser_relaunches, settings.max_browser_relaunches
+                    );
+                    // Keep the requests captured so far, then close what is left
+                    // of the old browser
+                    if let (true, Some((ref browser, ref tab))) = (settings.save_har, &page) {
+                        if let Err(e) = collect_har(browser, tab, &mut har) {
+                            warn!("Could not keep the crashed browser's HAR entries: {}", e);
+                        }
+                    }
+                    extra_tabs.clear();
+                    page = None;
+                    match relaunch_browser(settings, &recorder).await {
+                        Ok((browser, tab, tabs)) => {
+                            recording_data.push(RecordingData {
+                                session_id: session_id.clone(),
+                                timestamp: chrono::Utc::now(),
+                                url: settings.url.clone(),
+                                action: "browser_relaunched".to_string(),
+                                metadata: serde_json::json!({
+                                    "attempt": browser_relaunches,
+                                }),
+                                success: true,
+                            });
+                            page = Some((browser, tab));
+                            extra_tabs = tabs;
+                        }
+                        Err(e) => {
+                            error!("Could not relaunch the browser: {}", e);
+                            break StopReason::BrowserCrashed;
+                        }
+                    }
+                    continue;
+                }
+
+                // Delay between pages
+                if any_loaded && settings.kiosk {
+                    match max_runtime {
+                        Some(limit) => info!("Kiosk mode: recording {} for {:?}", settings.url, limit),
+                        None => info!("Kiosk mode: recording {} until stopped", settings.url),
+                    }
+                } else if any_loaded {
+                    let delay = jittered_delay(settings.delay_ms, settings.delay_jitter_ms, &mut rng);
+                    wait_between_pages(events, &recorder, delay, settings.pause_during_delay).await;
+                }
+            })
+        }
+        .await;
+        let stop_reason = match crawled {
+            Ok(stop_reason) => stop_reason,
+            Err(e) => {
+                prefetch_active.store(false, Ordering::SeqCst);
+                let _ = recorder.stop_recording().await;
+                return Err(e);
+            }
+        };
+
+        prefetch_active.store(false, Ordering::SeqCst);
+        for handle in worker_handles {
+            let _ = handle.await;
+        }
+
+        let duration_secs = crawl_started.elapsed().as_secs();
+        info!("Crawl stopped: {} after {}s", stop_reason, duration_secs);
+        // Only a crawl that was cut short is carried on by the next run
+        if !matches!(stop_reason, StopReason::Stopped | StopReason::BrowserCrashed) {
+            let _ = std::fs::remove_file(&saved_path);
+        }
+
+        let video_path = if recorder.is_recording() {
+            info!("Stopping recording...");
+            recorder.stop_recording().await?
+        } else {
+            // Already stopped by record_first_n_pages
+            recorder.get_metadata().await.and_then(|metadata| metadata.file_path)
+        };
+        let capture_warnings = recorder
+            .get_metadata()
+            .await
+            .map(|metadata| metadata.capture_warnings)
+            .unwrap_or_default();
+
+        let hosts = hosts.sorted();
+        recording_data.push(RecordingData {
+            session_id: session_id.clone(),
+            timestamp: chrono::Utc::now(),
+            url: settings.url.clone(),
+            action: "host_stats".to_string(),
+            metadata: serde_json::json!({
+                "hosts": hosts
+                    .iter()
+                    .map(|(host, stats)| serde_json::json!({
+                        "host": host,
+                        "pages": stats.pages,
+                        "failures": stats.failures,
+                        "total_nav_ms": stats.total_nav_ms,
+                        "average_nav_ms": stats.average_nav_ms(),
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+            success: true,
+        });
+        let failures = exporter::failures(&recording_data);
+        if settings.export.include_failures {
+            recording_data.push(RecordingData {
+                session_id: session_id.clone(),
+                timestamp: chrono::Utc::now(),
+                url: settings.url.clone(),
+                action: "failures".to_string(),
+                metadata: serde_json::json!({
+                    "count": failures.len(),
+                    "failures": failures,
+                }),
+                success: true,
+            });
+        }
+
+        let export_paths = Exporter::new().export_all(
+            &recording_data,
+            session_dir.join(format!("{}_data", session_id)),
+            &settings.export,
+        )?;
+        if let (true, Some((ref browser, ref tab))) = (settings.save_har, &page) {
+            collect_har(browser, tab, &mut har)?;
+        }
+        let har_path = match har {
+            Some(ref har) => Some(save_har(har, &session_dir, &session_id)?),
+            None => None,
+        };
+        let sitemap_path = match settings.emit_sitemap {
+            Some(ref path) => {
+                let files = crawler.lock().await.write_sitemap(path)?;
+                info!("Sitemap written to: {:?} ({} file(s))", path, files.len());
+                Some(path.clone())
+            }
+            None => None,
+        };
+        let contact_sheet_path = match contact_sheet {
+            Some(sheet) => {
+                let path = session_dir.join(format!("{}_contactsheet.jpg", session_id));
+                match sheet.render(&path) {
+                    Ok(true) => Some(path),
+                    Ok(false) => None,
+                    Err(e) => {
+                        warn!("Failed to build the contact sheet: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        if let Some(ref video_path) = video_path {
+            info!("Recording saved to: {:?}", video_path);
+        }
+        for path in &export_paths {
+            info!("Data exported to: {:?}", path);
+        }
+        info!("Total pages visited: {}", pages_visited);
+        if pages_failed > 0 {
+            info!("Pages failed: {} (see the \"failures\" record in the export)", pages_failed);
+        }
+        if settings.dedup_content || settings.respect_canonical {
+            info!("Duplicate pages skipped: {}", pages_duplicate);
+        }
+        if settings.discover_assets {
+            info!("Assets discovered: {}", crawler.lock().await.get_discovered_assets().len());
+        }
+        let pages_discovered = crawler.lock().await.get_discovered_count();
+        events.emit(CrawlEvent::Finished { pages_visited });
+        notifier.notify_run_summary(&RunSummary {
+            session_id: session_id.clone(),
+            url: settings.url.clone(),
+            pages_visited,
+            pages_failed,
+            pages_duplicate,
+            duration_secs,
+            stopped_by: Some(stop_reason.to_string()),
+            capture_warnings: capture_warnings.clone(),
+        });
+
+        // Run vulnerability scan if requested
+        let mut scan = None;
+        let mut scan_path = None;
+        if let Some(ref scan_url) = settings.scan_url {
+            info!("Running vulnerability scan on: {}", scan_url);
+            let mut scanner = VulnerabilityScanner::new(ScanConfig::new(scan_url)?)?;
+            match scanner.run_full_scan().await {
+                Ok(report) => {
+                    let path = session_dir.join(format!("{}_scan.json", session_id));
+                    std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+                    info!("Vulnerability scan completed. Report saved to: {:?}", path);
+                    scan = Some(report);
+                    scan_path = Some(path);
+                }
+                Err(e) => warn!("Vulnerability scan failed: {}", e),
+            }
+        }
+
+        Ok(RunReport {
+            session_id,
+            pages_visited,
+            pages_failed,
+            failures,
+            pages_duplicate,
+            pages_discovered,
+            hosts,
+            stop_reason,
+            duration_secs,
+            video_path,
+            capture_warnings,
+            export_paths,
+            har_path,
+            sitemap_path,
+            contact_sheet_path,
+            scan_path,
+            scan,
+        })
+    }
+}
+
+/// A page loaded by one crawl step: its URL, the tab it was loaded in
+/// (`None` over HTTP), how long the load took, and the frames it produced.
+type PageLoad<'a> = (String, Option<&'a Arc<Tab>>, Duration, Result<Option<Vec<(String, String)>>, EngineError>);
+
+/// Replace a crashed browser with a new one: set its tabs up like the first
+/// ones, point the recorder at the new main tab and log in again, so the
+/// crawl carries on from its frontier with the same session.
+async fn relaunch_browser(settings: &EngineConfig, recorder: &Recorder) -> Result<(Browser, Arc<Tab>, Vec<Arc<Tab>>), EngineError> {
+    let browser = settings.launch_browser()?;
+    let tab = browser.get_tab()?;
+    let extra_tabs = browser.get_tabs(settings.tabs.saturating_sub(1))?;
+    for tab in std::iter::once(&tab).chain(&extra_tabs) {
+        settings.apply_emulation(&browser, tab)?;
+        browser.set_download_dir(tab, settings.download_dir.as_deref())?;
+    }
+    if settings.save_har {
+        browser.start_har_capture(&tab)?;
+    }
+    recorder.set_browser_tab(tab.clone()).await;
+    recorder.set_browser_pid(browser.process_id()).await;
+    if let Some(ref auth) = settings.auth {
+        match log_in(&browser, &tab, auth, &settings.navigation_options()).await {
+            Ok(_) => info!("Logged in again after relaunching the browser"),
+            Err(e) => warn!("Login after relaunching the browser failed: {}", e),
+        }
+    }
+    Ok((browser, tab, extra_tabs))
+}
+
+/// Log in with a short-lived browser and return its cookies and user agent,
+/// so HTTP requests are seen as the same session.
+async fn http_session(settings: &EngineConfig, auth: &AuthConfig) -> Result<(Vec<Cookie>, String), EngineError> {
+    let browser = settings.launch_browser()?;
+    let tab = browser.get_tab()?;
+    log_in(&browser, &tab, auth, &settings.navigation_options()).await?;
+    let cookies = tab.get_cookies().map_err(BrowserError::from)?;
+    let user_agent = browser.execute_script(&tab, "navigator.userAgent")?;
+    Ok((cookies, user_agent.as_str().unwrap_or_default().to_string()))
+}
+
+/// A browser cookie as a `Set-Cookie` value plus a URL on its domain.
+fn set_cookie_header(cookie: &Cookie) -> Option<(String, url::Url)> {
+    let scheme = if cookie.secure { "https" } else { "http" };
+    let host = cookie.domain.trim_start_matches('.');
+    let url = url::Url::parse(&format!("{}://{}{}", scheme, host, cookie.path)).ok()?;
+    let mut header = format!("{}={}; Path={}", cookie.name, cookie.value, cookie.path);
+    if cookie.domain.starts_with('.') {
+        header.push_str(&format!("; Domain={}", host));
+    }
+    if cookie.secure {
+        header.push_str("; Secure");
+    }
+    Some((header, url))
+}
+
+/// How often a waiting crawl publishes [`CrawlEvent::RecorderStats`], so
+/// health checks can tell a long delay, rate limit wait or kiosk run from a
+/// hung crawl.
+const WAIT_HEARTBEAT: Duration = Duration::from_secs(5);
+
+async fn emit_recorder_stats(events: &EventSink, recorder: &Recorder) {
+    events.emit(CrawlEvent::RecorderStats {
+        duration_secs: recorder.get_duration().await.unwrap_or(0),
+        frames_captured: recorder.frames_captured(),
+    });
+}
+
+/// Sleep for `duration`, publishing recorder stats every [`WAIT_HEARTBEAT`].
+async fn wait_with_heartbeat(events: &EventSink, recorder: &Recorder, duration: Duration) {
+    let until = Instant::now() + duration;
+    loop {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        tokio::time::sleep(left.min(WAIT_HEARTBEAT)).await;
+        emit_recorder_stats(events, recorder).await;
+    }
+}
+
+/// Sleep for `delay`, with the recording paused meanwhile if `pause` is set
+/// and it is running unpaused. A pause that fails is logged and the delay
+/// recorded as usual.
+pub async fn wait_between_pages(events: &EventSink, recorder: &Recorder, delay: Duration, pause: bool) {
+    let pause = pause && recorder.is_recording() && !recorder.is_paused();
+    let paused = pause && match recorder.pause_recording().await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Could not pause the recording during the delay: {}", e);
+            false
+        }
+    };
+    wait_with_heartbeat(events, recorder, delay).await;
+    if paused {
+        if let Err(e) = recorder.resume_recording().await {
+            warn!("Could not resume the recording after the delay: {}", e);
+        }
+    }
+}
+
+/// Record in a page's `metadata` how far into the video it appears, with
+/// paused time left out, while the recorder is running.
+pub async fn note_video_offset(recorder: &Recorder, metadata: &mut serde_json::Value) {
+    if let Some(position) = recorder.video_position().await {
+        metadata["video_offset_secs"] = serde_json::json!((position.as_secs_f64() * 10.0).round() / 10.0);
+    }
+}
+
+/// If the page queued as `url` ended up at `landed`, note both URLs in its
+/// `metadata` and tell the crawler, so the landing page isn't recorded again.
+pub fn note_redirect(crawler: &mut Crawler, url: &str, landed: &str, metadata: &mut serde_json::Value) {
+    if let Some(landed) = crawler.record_redirect(url, landed) {
+        info!("  Redirected to {}", landed);
+        metadata["original_url"] = serde_json::json!(url);
+        metadata["final_url"] = serde_json::json!(landed);
+    }
+}
+
+/// Links and assets from each frame, resolved against that frame's own URL.
+pub fn frame_links(crawler: &Crawler, frames: &[(String, String)]) -> PageLinks {
+    let mut links = PageLinks::default();
+    for (frame_url, html) in frames {
+        match crawler.extract_page_links(html, frame_url) {
+            Ok(found) => {
+                for link in found.links {
+                    if !links.links.contains(&link) {
+                        links.links.push(link);
+                    }
+                }
+                for asset in found.assets {
+                    if !links.assets.contains(&asset) {
+                        links.assets.push(asset);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to extract links from frame {}: {}", frame_url, e),
+        }
+    }
+    links
+}
+
+/// Stop `browser`'s HAR capture on `tab` and add its requests to `har`, so
+/// a crashed browser's entries end up in the same archive as its
+/// replacement's.
+fn collect_har(browser: &Browser, tab: &Arc<Tab>, har: &mut Option<Har>) -> Result<(), BrowserError> {
+    let captured = browser.stop_har_capture(tab)?;
+    match har {
+        Some(har) => har.log.entries.extend(captured.log.entries),
+        None => *har = Some(captured),
+    }
+    Ok(())
+}
+
+/// Write `har` next to the session's other output, returning the file path.
+pub fn save_har(har: &Har, session_dir: &Path, session_id: &str) -> Result<PathBuf, EngineError> {
+    let har_path = session_dir.join(format!("{}.har", session_id));
+    std::fs::write(&har_path, serde_json::to_string_pretty(&har)?)?;
+    info!("HAR saved to: {:?} ({} requests)", har_path, har.log.entries.len());
+    Ok(har_path)
+}
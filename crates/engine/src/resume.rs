@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use crawler::CrawlState;
+use serde::{Deserialize, Serialize};
+
+/// Where a session's crawl is kept between runs, so running the session
+/// again (the same `--session-id`) carries on where it was interrupted.
+pub fn saved_crawl_path(session_dir: &Path, session_id: &str) -> PathBuf {
+    session_dir.join(format!("{}_crawl_state.json", session_id))
+}
+
+/// An interrupted crawl of a session: the pages it visited, its frontier
+/// and its page count. Removed once a run of the session completes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedCrawl {
+    /// Pages visited by every run of the session so far.
+    pub pages_visited: usize,
+    #[serde(flatten)]
+    pub state: CrawlState,
+}
+
+impl SavedCrawl {
+    /// The crawl saved at `path`, or `None` for a new session.
+    pub fn load(path: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_crawl() {
+        let dir = std::env::temp_dir().join(format!("saved-crawl-{}", std::process::id()));
+        let path = saved_crawl_path(&dir, "session-1");
+        assert_eq!(SavedCrawl::load(&path), None);
+
+        let saved = SavedCrawl {
+            pages_visited: 42,
+            state: CrawlState {
+                visited: vec!["https://example.com/".to_string()],
+                frontier: vec!["https://example.com/a".to_string()],
+            },
+        };
+        saved.save(&path).unwrap();
+        assert_eq!(SavedCrawl::load(&path), Some(saved));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use browser::Browser;
+use headless_chrome::Tab;
+
+use crate::EngineError;
+
+/// Longest slug used in a screenshot file name, before any suffix.
+const MAX_SLUG_LEN: usize = 120;
+
+/// File-name-safe form of `url`: host and path without the scheme, with
+/// every run of other characters than letters, digits, `.` and `-` turned
+/// into `_`, e.g. `example.com_docs_intro_lang_en`.
+pub fn url_slug(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let mut slug = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    // Only ASCII was kept, so any byte offset is a char boundary
+    match slug[..slug.len().min(MAX_SLUG_LEN)].trim_matches(|c| c == '_' || c == '.') {
+        "" => "page".to_string(),
+        slug => slug.to_string(),
+    }
+}
+
+/// `<slug>.png` in `dir`, or `<slug>-2.png`, `<slug>-3.png` and so on if
+/// that is taken, e.g. by `?a=1` and `#a=1` variants of one page.
+fn unused_path(dir: &Path, slug: &str) -> PathBuf {
+    let path = dir.join(format!("{}.png", slug));
+    if !path.exists() {
+        return path;
+    }
+    (2..)
+        .map(|n| dir.join(format!("{}-{}.png", slug, n)))
+        .find(|path| !path.exists())
+        .expect("unbounded suffixes")
+}
+
+/// Save a full-page PNG of the page `tab` shows, loaded from `url`, into
+/// `dir`, returning the file written.
+pub fn save_page_screenshot(browser: &Browser, tab: &Arc<Tab>, dir: &Path, url: &str) -> Result<PathBuf, EngineError> {
+    let png = browser.capture_full_page_png(tab)?;
+    std::fs::create_dir_all(dir)?;
+    let path = unused_path(dir, &url_slug(url));
+    std::fs::write(&path, png)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_slug() {
+        assert_eq!(url_slug("https://example.com/"), "example.com");
+        assert_eq!(url_slug("https://example.com/docs/intro?lang=en"), "example.com_docs_intro_lang_en");
+        assert_eq!(url_slug("http://localhost:8080/a//b"), "localhost_8080_a_b");
+        assert_eq!(url_slug("https://例え.jp/"), "jp");
+        assert_eq!(url_slug("file:///"), "page");
+        assert!(url_slug(&format!("https://example.com/{}", "a".repeat(500))).len() <= MAX_SLUG_LEN);
+
+        let dir = std::env::temp_dir().join(format!("test_url_slug_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = unused_path(&dir, "example.com");
+        std::fs::write(&first, b"").unwrap();
+        let second = unused_path(&dir, "example.com");
+        assert_eq!(second.file_name().unwrap(), "example.com-2.png");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
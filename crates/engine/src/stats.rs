@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Page counts and load time for one host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostStats {
+    pub pages: usize,
+    pub failures: usize,
+    /// Time spent loading pages from this host, including failed loads.
+    pub total_nav_ms: u64,
+}
+
+impl HostStats {
+    /// Mean load time over every attempt, successful or not.
+    pub fn average_nav_ms(&self) -> u64 {
+        match self.pages + self.failures {
+            0 => 0,
+            attempts => self.total_nav_ms / attempts as u64,
+        }
+    }
+}
+
+/// Per-host stats collected over a crawl.
+#[derive(Debug, Clone, Default)]
+pub struct HostBreakdown {
+    hosts: HashMap<String, HostStats>,
+}
+
+impl HostBreakdown {
+    /// Count one page load of `url` that took `elapsed`.
+    pub fn record(&mut self, url: &str, ok: bool, elapsed: Duration) {
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "(unknown)".to_string());
+        let stats = self.hosts.entry(host).or_default();
+        if ok {
+            stats.pages += 1;
+        } else {
+            stats.failures += 1;
+        }
+        stats.total_nav_ms += elapsed.as_millis() as u64;
+    }
+
+    /// Hosts with the most pages first, ties broken by name.
+    pub fn sorted(&self) -> Vec<(String, HostStats)> {
+        let mut hosts: Vec<_> = self.hosts.iter().map(|(host, stats)| (host.clone(), stats.clone())).collect();
+        hosts.sort_by(|(a_host, a), (b_host, b)| b.pages.cmp(&a.pages).then_with(|| a_host.cmp(b_host)));
+        hosts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_breakdown() {
+        let mut breakdown = HostBreakdown::default();
+        breakdown.record("https://docs.example.com/a", true, Duration::from_millis(300));
+        breakdown.record("https://example.com/", true, Duration::from_millis(100));
+        breakdown.record("https://example.com/b", true, Duration::from_millis(200));
+        breakdown.record("https://example.com/c", false, Duration::from_millis(600));
+
+        let sorted = breakdown.sorted();
+        assert_eq!(sorted[0].0, "example.com");
+        assert_eq!(
+            sorted[0].1,
+            HostStats {
+                pages: 2,
+                failures: 1,
+                total_nav_ms: 900,
+            }
+        );
+        assert_eq!(sorted[0].1.average_nav_ms(), 300);
+        assert_eq!(sorted[1].0, "docs.example.com");
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Wait before the first retry of a rate-limited host that sent no
+/// `Retry-After`; doubled for each further 429 in a row.
+pub const RATE_LIMIT_BASE_WAIT: Duration = Duration::from_secs(5);
+
+/// Longest single wait, whatever `Retry-After` asks for.
+pub const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(120);
+
+/// Total time a crawl may spend waiting out rate limits. Once spent, 429s
+/// count as failed pages.
+pub const RATE_LIMIT_MAX_TOTAL_WAIT: Duration = Duration::from_secs(600);
+
+/// Random source for [`jittered_delay`], repeatable when `seed` is given.
+pub fn delay_rng(seed: Option<u64>) -> fastrand::Rng {
+    seed.map(fastrand::Rng::with_seed).unwrap_or_default()
+}
+
+/// Pause between pages: `delay_ms` moved by up to `jitter_ms` either way,
+/// so requests don't arrive at a mechanically regular pace. Never
+/// negative.
+pub fn jittered_delay(delay_ms: u64, jitter_ms: u64, rng: &mut fastrand::Rng) -> Duration {
+    if jitter_ms == 0 {
+        return Duration::from_millis(delay_ms);
+    }
+    let offset = rng.u64(0..=jitter_ms);
+    let delay = if rng.bool() {
+        delay_ms.saturating_add(offset)
+    } else {
+        delay_ms.saturating_sub(offset)
+    };
+    Duration::from_millis(delay)
+}
+
+/// Parse a `Retry-After` value: delay seconds or an HTTP date. Dates in
+/// the past mean no wait.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Backoff state for hosts answering 429 Too Many Requests.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// 429s in a row per host.
+    strikes: HashMap<String, u32>,
+    total_waited: Duration,
+}
+
+impl RateLimiter {
+    /// How long to wait before retrying `url`, honoring `retry_after` and
+    /// otherwise backing off exponentially per host. `None` once the
+    /// crawl's total wait budget would be exceeded.
+    pub fn wait_for(&mut self, url: &str, retry_after: Option<&str>) -> Option<Duration> {
+        let strikes = self.strikes.entry(host_of(url)).or_default();
+        *strikes += 1;
+        let wait = retry_after
+            .and_then(|value| parse_retry_after(value, Utc::now()))
+            .unwrap_or_else(|| RATE_LIMIT_BASE_WAIT.saturating_mul(2u32.saturating_pow(*strikes - 1)))
+            .min(RATE_LIMIT_MAX_WAIT);
+        if self.total_waited + wait > RATE_LIMIT_MAX_TOTAL_WAIT {
+            return None;
+        }
+        self.total_waited += wait;
+        Some(wait)
+    }
+
+    /// Reset the backoff for `url`'s host after a page loads.
+    pub fn succeeded(&mut self, url: &str) {
+        self.strikes.remove(&host_of(url));
+    }
+
+    pub fn total_waited(&self) -> Duration {
+        self.total_waited
+    }
+}
+
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Mon, 01 Jan 2024 00:00:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Sun, 31 Dec 2023 23:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_jittered_delay() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        assert_eq!(jittered_delay(2000, 0, &mut rng), Duration::from_millis(2000));
+        let delays: Vec<_> = (0..100).map(|_| jittered_delay(2000, 500, &mut rng)).collect();
+        assert!(delays.iter().all(|d| (1500..=2500).contains(&(d.as_millis() as u64))));
+        assert!(delays.iter().any(|d| *d != delays[0]));
+        // A jitter larger than the delay doesn't underflow
+        assert!((0..200).all(|_| jittered_delay(100, 500, &mut rng) <= Duration::from_millis(600)));
+
+        // The same seed gives the same pauses
+        let run = |seed| {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            (0..5).map(|_| jittered_delay(2000, 500, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(run(42), run(42));
+    }
+
+    #[test]
+    fn test_rate_limiter_backoff_and_budget() {
+        let mut limiter = RateLimiter::default();
+        let url = "https://example.com/a";
+        assert_eq!(limiter.wait_for(url, None), Some(Duration::from_secs(5)));
+        assert_eq!(limiter.wait_for(url, None), Some(Duration::from_secs(10)));
+        assert_eq!(limiter.wait_for(url, Some("600")), Some(RATE_LIMIT_MAX_WAIT));
+
+        limiter.succeeded(url);
+        assert_eq!(limiter.wait_for(url, None), Some(Duration::from_secs(5)));
+
+        while limiter.wait_for(url, Some("120")).is_some() {}
+        assert!(limiter.total_waited() <= RATE_LIMIT_MAX_TOTAL_WAIT);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::RecordingData;
+
+/// Whether a page loaded, and the HTTP status it failed with if known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStatus {
+    pub success: bool,
+    pub status: Option<u16>,
+}
+
+impl PageStatus {
+    /// Whether the page's outcome changed. A loaded page whose status only
+    /// one export recorded counts as unchanged, since older exports did not
+    /// record the status of successful loads.
+    fn differs_from(&self, other: &PageStatus) -> bool {
+        match (self.success, other.success) {
+            (true, true) => self.status.zip(other.status).is_some_and(|(a, b)| a != b),
+            _ => self != other,
+        }
+    }
+}
+
+impl fmt::Display for PageStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.success, self.status) {
+            (_, Some(status)) => write!(f, "{}", status),
+            (true, None) => write!(f, "ok"),
+            (false, None) => write!(f, "failed"),
+        }
+    }
+}
+
+/// A page whose status differs between the two exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub url: String,
+    pub before: PageStatus,
+    pub after: PageStatus,
+}
+
+/// A page whose title differs between the two exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleChange {
+    pub url: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// What changed between a baseline export and a current one, per page.
+/// Only `navigate` records count; URLs are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportDiff {
+    /// Pages only in the current export.
+    pub added: Vec<String>,
+    /// Pages only in the baseline export.
+    pub removed: Vec<String>,
+    pub status_changes: Vec<StatusChange>,
+    pub title_changes: Vec<TitleChange>,
+}
+
+struct Page {
+    status: PageStatus,
+    title: Option<String>,
+}
+
+/// The last `navigate` record of each URL, so a page that failed and was
+/// retried counts as its final outcome.
+fn pages(data: &[RecordingData]) -> BTreeMap<&str, Page> {
+    data.iter()
+        .filter(|record| record.action == "navigate")
+        .map(|record| {
+            let status = record.metadata["status"].as_u64().and_then(|status| u16::try_from(status).ok());
+            let title = record.metadata["title"].as_str().map(|title| title.trim().to_string());
+            let page = Page {
+                status: PageStatus {
+                    success: record.success,
+                    status,
+                },
+                title,
+            };
+            (record.url.as_str(), page)
+        })
+        .collect()
+}
+
+impl ExportDiff {
+    pub fn new(baseline: &[RecordingData], current: &[RecordingData]) -> Self {
+        let before = pages(baseline);
+        let after = pages(current);
+        let mut diff = Self::default();
+
+        for (url, old) in &before {
+            let Some(new) = after.get(url) else {
+                diff.removed.push(url.to_string());
+                continue;
+            };
+            if old.status.differs_from(&new.status) {
+                diff.status_changes.push(StatusChange {
+                    url: url.to_string(),
+                    before: old.status,
+                    after: new.status,
+                });
+            }
+            // A failed load has no title, so that is a status change only
+            if old.status.success && new.status.success && old.title != new.title {
+                diff.title_changes.push(TitleChange {
+                    url: url.to_string(),
+                    before: old.title.clone(),
+                    after: new.title.clone(),
+                });
+            }
+        }
+        diff.added = after
+            .keys()
+            .filter(|url| !before.contains_key(*url))
+            .map(|url| url.to_string())
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.status_changes.is_empty() && self.title_changes.is_empty()
+    }
+
+    /// Plain-text report, one line per change.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No changes\n".to_string();
+        }
+        let title = |title: &Option<String>| match title {
+            Some(title) => format!("{:?}", title),
+            None => "(none)".to_string(),
+        };
+
+        let mut out = format!(
+            "{} added, {} removed, {} status changes, {} title changes\n",
+            self.added.len(),
+            self.removed.len(),
+            self.status_changes.len(),
+            self.title_changes.len()
+        );
+        for url in &self.added {
+            out.push_str(&format!("+ {}\n", url));
+        }
+        for url in &self.removed {
+            out.push_str(&format!("- {}\n", url));
+        }
+        for change in &self.status_changes {
+            out.push_str(&format!("~ {}  status {} -> {}\n", change.url, change.before, change.after));
+        }
+        for change in &self.title_changes {
+            out.push_str(&format!(
+                "~ {}  title {} -> {}\n",
+                change.url,
+                title(&change.before),
+                title(&change.after)
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn page(url: &str, success: bool, metadata: serde_json::Value) -> RecordingData {
+        RecordingData {
+            session_id: "session".to_string(),
+            timestamp: Utc::now(),
+            url: url.to_string(),
+            action: "navigate".to_string(),
+            metadata,
+            success,
+        }
+    }
+
+    #[test]
+    fn test_export_diff() {
+        let baseline = vec![
+            page("https://example.com/", true, json!({"title": "Home"})),
+            page("https://example.com/about", true, json!({"title": "About"})),
+            page("https://example.com/old", true, json!({"title": "Old"})),
+            page("https://example.com/shop", true, json!({"title": "Shop"})),
+        ];
+        let current = vec![
+            page("https://example.com/", true, json!({"title": "Home"})),
+            page("https://example.com/about", true, json!({"title": "About us"})),
+            page("https://example.com/shop", false, json!({"error": "not found", "status": 404})),
+            page("https://example.com/new", true, json!({})),
+        ];
+
+        let diff = ExportDiff::new(&baseline, &current);
+        assert_eq!(diff.added, vec!["https://example.com/new"]);
+        assert_eq!(diff.removed, vec!["https://example.com/old"]);
+        assert_eq!(diff.status_changes.len(), 1);
+        assert_eq!(diff.status_changes[0].after.to_string(), "404");
+        assert_eq!(diff.status_changes[0].before.to_string(), "ok");
+        assert_eq!(diff.title_changes.len(), 1);
+        assert_eq!(diff.title_changes[0].after.as_deref(), Some("About us"));
+        assert!(diff.summary().contains("~ https://example.com/shop  status ok -> 404"));
+
+        assert!(ExportDiff::new(&baseline, &baseline).is_empty());
+
+        let with_status = vec![page("https://example.com/", true, json!({"title": "Home", "status": 200}))];
+        assert!(ExportDiff::new(&baseline[..1], &with_status).is_empty());
+        let moved = vec![page("https://example.com/", true, json!({"title": "Home", "status": 203}))];
+        assert_eq!(ExportDiff::new(&with_status, &moved).status_changes.len(), 1);
+    }
+}
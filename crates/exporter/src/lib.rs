@@ -1,9 +1,16 @@
 use anyhow::Result;
+use chrono::format::{Item, StrftimeItems};
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+pub mod diff;
+pub mod sitemap;
+pub use diff::{ExportDiff, PageStatus, StatusChange, TitleChange};
+pub use sitemap::{render_urlset, write_sitemap, SitemapEntry};
+
 #[derive(Debug, Error)]
 pub enum ExportError {
     #[error("Failed to export data: {0}")]
@@ -25,14 +32,230 @@ pub struct RecordingData {
     pub url: String,
     pub action: String,
     pub metadata: serde_json::Value,
+    /// `false` for records of failed actions, e.g. a page that didn't load.
+    #[serde(default = "default_success")]
+    pub success: bool,
+}
+
+fn default_success() -> bool {
+    true
+}
+
+/// A page or action that failed, pulled from a record with `success: false`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureRecord {
+    pub url: String,
+    pub action: String,
+    pub error: String,
+    /// HTTP status of the failed response, when one was received.
+    pub status: Option<u16>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl FailureRecord {
+    /// The failure `record` describes, or `None` if it succeeded. The
+    /// reason comes from the record's `error` and `status` metadata.
+    pub fn from_record(record: &RecordingData) -> Option<Self> {
+        if record.success {
+            return None;
+        }
+        Some(Self {
+            url: record.url.clone(),
+            action: record.action.clone(),
+            error: record.metadata["error"].as_str().unwrap_or("unknown error").to_string(),
+            status: record.metadata["status"].as_u64().and_then(|status| u16::try_from(status).ok()),
+            timestamp: record.timestamp,
+        })
+    }
+}
+
+/// Every failure in `data`, in order.
+pub fn failures(data: &[RecordingData]) -> Vec<FailureRecord> {
+    data.iter().filter_map(FailureRecord::from_record).collect()
+}
+
+/// Version of the [`VersionedExport`] layout. Bumped on breaking changes
+/// to the envelope or to [`RecordingData`].
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The session a JSON export covers, taken from its records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportSession {
+    pub id: String,
+    /// URL of the first record, normally the start URL.
+    pub url: Option<String>,
+    pub started: Option<DateTime<Utc>>,
+    pub ended: Option<DateTime<Utc>>,
+}
+
+impl ExportSession {
+    pub fn from_records(data: &[RecordingData]) -> Self {
+        Self {
+            id: data.first().map(|r| r.session_id.clone()).unwrap_or_default(),
+            url: data.first().map(|r| r.url.clone()),
+            started: data.first().map(|r| r.timestamp),
+            ended: data.last().map(|r| r.timestamp),
+        }
+    }
+}
+
+/// The JSON export: records wrapped in a versioned envelope so consumers
+/// can tell which layout they are reading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionedExport {
+    pub schema_version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub session: ExportSession,
+    pub records: Vec<RecordingData>,
+}
+
+impl VersionedExport {
+    pub fn new(data: &[RecordingData]) -> Self {
+        Self {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            generated_at: Utc::now(),
+            session: ExportSession::from_records(data),
+            records: data.to_vec(),
+        }
+    }
+}
+
+/// Records of a JSON export in either layout: the versioned envelope or
+/// the legacy bare array.
+pub fn parse_json_export(json: &str) -> Result<Vec<RecordingData>, ExportError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyExport {
+        Versioned(VersionedExport),
+        Legacy(Vec<RecordingData>),
+    }
+
+    match serde_json::from_str(json).map_err(|e| ExportError::InvalidFormat(e.to_string()))? {
+        AnyExport::Versioned(export) if export.schema_version > EXPORT_SCHEMA_VERSION => Err(ExportError::InvalidFormat(
+            format!("export schema version {} is newer than the supported {}", export.schema_version, EXPORT_SCHEMA_VERSION),
+        )),
+        AnyExport::Versioned(export) => Ok(export.records),
+        AnyExport::Legacy(records) => Ok(records),
+    }
+}
+
+/// Which records an export keeps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// Keep only the first record for each URL and action.
+    pub dedup: bool,
+    /// Keep records of failed actions.
+    pub include_failures: bool,
+    /// Write JSON as a bare array of records, as before
+    /// [`EXPORT_SCHEMA_VERSION`] 1. Deprecated; will be removed.
+    #[serde(default)]
+    pub legacy_json: bool,
+    /// strftime pattern for CSV and HTML timestamps, e.g. `%d/%m/%Y %H:%M`.
+    /// RFC 3339 when `None`.
+    #[serde(default)]
+    pub timestamp_format: Option<String>,
+    /// IANA time zone, e.g. `Europe/Paris`, to show CSV and HTML timestamps
+    /// in. UTC when `None`.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Formats the data is written in by
+    /// [`export_all`](Exporter::export_all), each to its own file.
+    #[serde(default = "default_export_formats")]
+    pub formats: Vec<ExportFormat>,
+}
+
+fn default_export_formats() -> Vec<ExportFormat> {
+    vec![ExportFormat::Json]
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            dedup: false,
+            include_failures: true,
+            legacy_json: false,
+            timestamp_format: None,
+            timezone: None,
+            formats: default_export_formats(),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// The records of `data` these options keep, in order.
+    pub fn filter(&self, data: &[RecordingData]) -> Vec<RecordingData> {
+        let mut seen = std::collections::HashSet::new();
+        data.iter()
+            .filter(|record| self.include_failures || record.success)
+            .filter(|record| !self.dedup || seen.insert((record.url.as_str(), record.action.as_str())))
+            .cloned()
+            .collect()
+    }
+
+    /// How these options render timestamps, or an error naming a bad
+    /// pattern or unknown time zone.
+    pub fn timestamps(&self) -> Result<TimestampFormat, ExportError> {
+        TimestampFormat::new(self.timestamp_format.as_deref(), self.timezone.as_deref())
+    }
+}
+
+/// Renders record timestamps in CSV and HTML exports.
+#[derive(Debug, Clone, Default)]
+pub struct TimestampFormat {
+    pattern: Option<String>,
+    zone: Option<Tz>,
+}
+
+impl TimestampFormat {
+    /// Format with the strftime `pattern` in `timezone`, defaulting to
+    /// RFC 3339 and UTC.
+    pub fn new(pattern: Option<&str>, timezone: Option<&str>) -> Result<Self, ExportError> {
+        if let Some(pattern) = pattern {
+            if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+                return Err(ExportError::InvalidFormat(format!("bad timestamp pattern {:?}", pattern)));
+            }
+        }
+        let zone = timezone
+            .map(|name| name.parse::<Tz>().map_err(|_| ExportError::InvalidFormat(format!("unknown time zone {:?}", name))))
+            .transpose()?;
+        Ok(Self {
+            pattern: pattern.map(str::to_string),
+            zone,
+        })
+    }
+
+    pub fn format(&self, timestamp: &DateTime<Utc>) -> String {
+        match (&self.pattern, self.zone) {
+            (None, None) => timestamp.to_rfc3339(),
+            (None, Some(zone)) => timestamp.with_timezone(&zone).to_rfc3339(),
+            (Some(pattern), None) => timestamp.format(pattern).to_string(),
+            (Some(pattern), Some(zone)) => timestamp.with_timezone(&zone).format(pattern).to_string(),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     Json,
     Csv,
     Html,
     Pdf,
+    /// sitemaps.org XML of the navigated URLs
+    Sitemap,
+}
+
+impl ExportFormat {
+    /// File extension of this format, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Sitemap => "xml",
+        }
+    }
 }
 
 pub struct Exporter;
@@ -42,6 +265,20 @@ impl Exporter {
         Self
     }
 
+    /// Write `data` as a [`VersionedExport`].
+    pub fn export_versioned_json<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        path: P,
+    ) -> Result<(), ExportError> {
+        let json = serde_json::to_string_pretty(&VersionedExport::new(data))
+            .map_err(|e| ExportError::ExportFailed(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Write `data` as a bare JSON array. Deprecated in favour of
+    /// [`export_versioned_json`](Self::export_versioned_json).
     pub fn export_to_json<P: AsRef<Path>>(
         &self,
         data: &[RecordingData],
@@ -57,15 +294,24 @@ impl Exporter {
         &self,
         data: &[RecordingData],
         path: P,
+    ) -> Result<(), ExportError> {
+        self.write_csv(data, path, &TimestampFormat::default())
+    }
+
+    fn write_csv<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        path: P,
+        timestamps: &TimestampFormat,
     ) -> Result<(), ExportError> {
         let mut wtr = csv::Writer::from_path(path)?;
         
-        wtr.write_record(&["session_id", "timestamp", "url", "action", "metadata"])?;
+        wtr.write_record(["session_id", "timestamp", "url", "action", "metadata"])?;
         
         for record in data {
-            wtr.write_record(&[
+            wtr.write_record([
                 &record.session_id,
-                &record.timestamp.to_rfc3339(),
+                &timestamps.format(&record.timestamp),
                 &record.url,
                 &record.action,
                 &record.metadata.to_string(),
@@ -80,6 +326,15 @@ impl Exporter {
         &self,
         data: &[RecordingData],
         path: P,
+    ) -> Result<(), ExportError> {
+        self.write_html(data, path, &TimestampFormat::default())
+    }
+
+    fn write_html<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        path: P,
+        timestamps: &TimestampFormat,
     ) -> Result<(), ExportError> {
         let mut html = String::from(
             r#"<!DOCTYPE html>
@@ -118,16 +373,52 @@ impl Exporter {
         </tr>
 "#,
                 record.session_id,
-                record.timestamp.to_rfc3339(),
+                timestamps.format(&record.timestamp),
                 record.url,
                 record.action,
                 record.metadata
             ));
         }
 
+        html.push_str("    </table>\n");
+
+        let failures = failures(data);
+        if !failures.is_empty() {
+            html.push_str(&format!(
+                r#"    <h2>Failures ({})</h2>
+    <table>
+        <tr>
+            <th>URL</th>
+            <th>Action</th>
+            <th>Status</th>
+            <th>Error</th>
+            <th>Timestamp</th>
+        </tr>
+"#,
+                failures.len()
+            ));
+            for failure in &failures {
+                html.push_str(&format!(
+                    r#"        <tr>
+            <td>{}</td>
+            <td>{}</td>
+            <td>{}</td>
+            <td>{}</td>
+            <td>{}</td>
+        </tr>
+"#,
+                    failure.url,
+                    failure.action,
+                    failure.status.map_or("-".to_string(), |status| status.to_string()),
+                    failure.error,
+                    timestamps.format(&failure.timestamp)
+                ));
+            }
+            html.push_str("    </table>\n");
+        }
+
         html.push_str(
-            r#"    </table>
-</body>
+            r#"</body>
 </html>
 "#,
         );
@@ -144,7 +435,7 @@ impl Exporter {
         use printpdf::{PdfDocument, Mm};
 
         let (doc, page1, layer1) = PdfDocument::new(
-            &format!("SiteRecorder Export - {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")),
+            format!("SiteRecorder Export - {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")),
             Mm(210.0),
             Mm(297.0),
             "Layer 1",
@@ -155,8 +446,8 @@ impl Exporter {
         let font = doc.add_builtin_font(printpdf::BuiltinFont::Helvetica).map_err(|e| ExportError::PdfError(e.to_string()))?;
         current_layer.use_text("SiteRecorder Recording Export", 18.0, Mm(20.0), Mm(260.0), &font);
 
-        current_layer.use_text(&format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M:%S")), 10.0, Mm(20.0), Mm(250.0), &font);
-        current_layer.use_text(&format!("Total Records: {}", data.len()), 10.0, Mm(20.0), Mm(244.0), &font);
+        current_layer.use_text(format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M:%S")), 10.0, Mm(20.0), Mm(250.0), &font);
+        current_layer.use_text(format!("Total Records: {}", data.len()), 10.0, Mm(20.0), Mm(244.0), &font);
 
         let mut y_pos = 230.0;
 
@@ -185,7 +476,7 @@ impl Exporter {
             };
 
             current_layer.use_text(&session_display, 8.0, Mm(20.0), Mm(y_pos), &font);
-            current_layer.use_text(&record.timestamp.format("%Y-%m-%d %H:%M").to_string(), 8.0, Mm(60.0), Mm(y_pos), &font);
+            current_layer.use_text(record.timestamp.format("%Y-%m-%d %H:%M").to_string(), 8.0, Mm(60.0), Mm(y_pos), &font);
             current_layer.use_text(&url_display, 8.0, Mm(100.0), Mm(y_pos), &font);
             current_layer.use_text(&record.action, 8.0, Mm(150.0), Mm(y_pos), &font);
 
@@ -194,7 +485,7 @@ impl Exporter {
 
         if data.len() > 30 {
             y_pos -= 3.0;
-            current_layer.use_text(&format!("... and {} more records", data.len() - 30), 9.0, Mm(20.0), Mm(y_pos), &font);
+            current_layer.use_text(format!("... and {} more records", data.len() - 30), 9.0, Mm(20.0), Mm(y_pos), &font);
         }
 
         let file = std::fs::File::create(path)
@@ -205,6 +496,70 @@ impl Exporter {
         Ok(())
     }
 
+    /// Write the URLs of `navigate` records as a sitemap, with each URL's
+    /// latest visit as `<lastmod>`. Large sets are split as described in
+    /// [`write_sitemap`], with the index pointing at the site's root.
+    pub fn export_to_sitemap<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        path: P,
+    ) -> Result<(), ExportError> {
+        let mut entries: Vec<SitemapEntry> = Vec::new();
+        for record in data.iter().filter(|r| r.action == "navigate") {
+            match entries.iter_mut().find(|e| e.loc == record.url) {
+                Some(entry) => entry.lastmod = entry.lastmod.max(Some(record.timestamp)),
+                None => entries.push(SitemapEntry {
+                    loc: record.url.clone(),
+                    lastmod: Some(record.timestamp),
+                }),
+            }
+        }
+        let base_url = entries.first().map(|e| site_root(&e.loc)).unwrap_or_default();
+        write_sitemap(&entries, path.as_ref(), &base_url)?;
+        Ok(())
+    }
+
+    /// [`export`](Self::export) only the records `options` keep.
+    pub fn export_with_options<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        path: P,
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        let data = options.filter(data);
+        match format {
+            ExportFormat::Json if options.legacy_json => self.export_to_json(&data, path),
+            ExportFormat::Csv => self.write_csv(&data, path, &options.timestamps()?),
+            ExportFormat::Html => self.write_html(&data, path, &options.timestamps()?),
+            format => self.export(&data, path, format),
+        }
+    }
+
+    /// [`export_with_options`](Self::export_with_options) once per format in
+    /// `options.formats`, to `stem` plus that format's extension, and return
+    /// the paths written in that order.
+    pub fn export_all<P: AsRef<Path>>(
+        &self,
+        data: &[RecordingData],
+        stem: P,
+        options: &ExportOptions,
+    ) -> Result<Vec<PathBuf>, ExportError> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for format in &options.formats {
+            let mut name = stem.as_ref().as_os_str().to_owned();
+            name.push(".");
+            name.push(format.extension());
+            let path = PathBuf::from(name);
+            if paths.contains(&path) {
+                continue;
+            }
+            self.export_with_options(data, &path, *format, options)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
     pub fn export<P: AsRef<Path>>(
         &self,
         data: &[RecordingData],
@@ -212,14 +567,24 @@ impl Exporter {
         format: ExportFormat,
     ) -> Result<(), ExportError> {
         match format {
-            ExportFormat::Json => self.export_to_json(data, path),
+            ExportFormat::Json => self.export_versioned_json(data, path),
             ExportFormat::Csv => self.export_to_csv(data, path),
             ExportFormat::Html => self.export_to_html(data, path),
             ExportFormat::Pdf => self.export_to_pdf(data, path),
+            ExportFormat::Sitemap => self.export_to_sitemap(data, path),
         }
     }
 }
 
+/// `scheme://host[:port]` of `url`, or `url` itself if it has no path.
+fn site_root(url: &str) -> String {
+    let after_scheme = url.find("://").map(|i| i + 3).unwrap_or(0);
+    match url[after_scheme..].find('/') {
+        Some(i) => url[..after_scheme + i].to_string(),
+        None => url.to_string(),
+    }
+}
+
 impl Default for Exporter {
     fn default() -> Self {
         Self::new()
@@ -245,6 +610,7 @@ mod tests {
             url: "https://example.com".to_string(),
             action: "navigate".to_string(),
             metadata: serde_json::json!({"test": "data"}),
+            success: true,
         }];
 
         let temp_path = std::env::temp_dir().join("test_export.json");
@@ -252,4 +618,144 @@ mod tests {
         assert!(result.is_ok());
         std::fs::remove_file(temp_path).ok();
     }
+
+    #[test]
+    fn test_versioned_json_export() {
+        let data = vec![RecordingData {
+            session_id: "test-123".to_string(),
+            timestamp: Utc::now(),
+            url: "https://example.com".to_string(),
+            action: "navigate".to_string(),
+            metadata: serde_json::json!({}),
+            success: true,
+        }];
+        let path = std::env::temp_dir().join("test_versioned_export.json");
+        Exporter::new().export(&data, &path, ExportFormat::Json).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], EXPORT_SCHEMA_VERSION);
+        assert_eq!(value["session"]["id"], "test-123");
+        assert_eq!(parse_json_export(&json).unwrap().len(), 1);
+
+        // Legacy bare arrays still parse; newer schemas are refused
+        let legacy = serde_json::to_string(&data).unwrap();
+        assert_eq!(parse_json_export(&legacy).unwrap()[0].url, "https://example.com");
+        let future = json.replace("\"schema_version\": 1", "\"schema_version\": 99");
+        assert!(parse_json_export(&future).is_err());
+    }
+
+    #[test]
+    fn test_export_options_filter() {
+        let record = |url: &str, action: &str, success: bool| RecordingData {
+            session_id: "test-123".to_string(),
+            timestamp: Utc::now(),
+            url: url.to_string(),
+            action: action.to_string(),
+            metadata: serde_json::Value::Null,
+            success,
+        };
+        let data = vec![
+            record("https://example.com/", "navigate", true),
+            record("https://example.com/", "extract_title", true),
+            record("https://example.com/broken", "navigate", false),
+            record("https://example.com/", "navigate", true),
+        ];
+
+        assert_eq!(ExportOptions::default().filter(&data).len(), 4);
+
+        let clean = ExportOptions {
+            dedup: true,
+            include_failures: false,
+            ..ExportOptions::default()
+        };
+        let kept: Vec<_> = clean.filter(&data).into_iter().map(|r| (r.url, r.action)).collect();
+        assert_eq!(
+            kept,
+            vec![
+                ("https://example.com/".to_string(), "navigate".to_string()),
+                ("https://example.com/".to_string(), "extract_title".to_string()),
+            ]
+        );
+
+        let old: RecordingData = serde_json::from_value(serde_json::json!({
+            "session_id": "s",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "url": "https://example.com/",
+            "action": "navigate",
+            "metadata": {},
+        }))
+        .unwrap();
+        assert!(old.success);
+    }
+
+    #[test]
+    fn test_failures_from_records() {
+        let record = |url: &str, success: bool, metadata: serde_json::Value| RecordingData {
+            session_id: "s".to_string(),
+            timestamp: Utc::now(),
+            url: url.to_string(),
+            action: "navigate".to_string(),
+            metadata,
+            success,
+        };
+        let data = vec![
+            record("https://example.com/", true, serde_json::json!({})),
+            record("https://example.com/gone", false, serde_json::json!({"error": "Not Found", "status": 404})),
+            record("https://example.com/slow", false, serde_json::json!({"error": "timed out"})),
+        ];
+
+        let failures = failures(&data);
+        assert_eq!(failures.len(), 2);
+        assert_eq!((failures[0].url.as_str(), failures[0].status), ("https://example.com/gone", Some(404)));
+        assert_eq!((failures[1].error.as_str(), failures[1].status), ("timed out", None));
+
+        let path = std::env::temp_dir().join(format!("test_failures_{}.html", std::process::id()));
+        Exporter::new().export_to_html(&data, &path).unwrap();
+        let html = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(html.contains("<h2>Failures (2)</h2>"));
+        assert!(html.contains("<td>404</td>"));
+    }
+
+    #[test]
+    fn test_export_all_formats() {
+        let data = vec![RecordingData {
+            session_id: "s".to_string(),
+            timestamp: Utc::now(),
+            url: "https://example.com/".to_string(),
+            action: "navigate".to_string(),
+            metadata: serde_json::json!({"title": "Home"}),
+            success: true,
+        }];
+        let dir = std::env::temp_dir().join(format!("test_export_all_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let options = ExportOptions {
+            formats: vec![ExportFormat::Json, ExportFormat::Csv, ExportFormat::Html, ExportFormat::Csv],
+            ..ExportOptions::default()
+        };
+
+        let paths = Exporter::new().export_all(&data, dir.join("v1.2_data"), &options).unwrap();
+        let names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, vec!["v1.2_data.json", "v1.2_data.csv", "v1.2_data.html"]);
+        assert!(paths.iter().all(|path| path.exists()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-07-01T22:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(TimestampFormat::default().format(&timestamp), "2024-07-01T22:30:00+00:00");
+
+        let options = ExportOptions {
+            timestamp_format: Some("%d/%m/%Y %H:%M %Z".to_string()),
+            timezone: Some("Europe/Paris".to_string()),
+            ..ExportOptions::default()
+        };
+        assert_eq!(options.timestamps().unwrap().format(&timestamp), "02/07/2024 00:30 CEST");
+
+        assert!(TimestampFormat::new(Some("%Q"), None).is_err());
+        assert!(TimestampFormat::new(None, Some("Mars/Olympus")).is_err());
+    }
 }
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::ExportError;
+
+/// Most URLs allowed in one sitemap file by the sitemaps.org protocol.
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+
+/// Largest uncompressed sitemap file allowed by the protocol.
+pub const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+
+const URLSET_OPEN: &str =
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n";
+const URLSET_CLOSE: &str = "</urlset>\n";
+
+/// One `<url>` in a sitemap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn url_element(entry: &SitemapEntry) -> String {
+    match entry.lastmod {
+        Some(lastmod) => format!(
+            "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n  </url>\n",
+            escape_xml(&entry.loc),
+            lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ),
+        None => format!("  <url>\n    <loc>{}</loc>\n  </url>\n", escape_xml(&entry.loc)),
+    }
+}
+
+/// Render `entries` as a single `<urlset>` document, ignoring size limits.
+pub fn render_urlset(entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from(URLSET_OPEN);
+    for entry in entries {
+        xml.push_str(&url_element(entry));
+    }
+    xml.push_str(URLSET_CLOSE);
+    xml
+}
+
+/// Render `entries` as one or more `<urlset>` documents, each within
+/// `max_urls` and `max_bytes`.
+pub fn render_urlsets(entries: &[SitemapEntry], max_urls: usize, max_bytes: usize) -> Vec<String> {
+    let mut documents = Vec::new();
+    let mut current = String::from(URLSET_OPEN);
+    let mut count = 0;
+    for entry in entries {
+        let element = url_element(entry);
+        if count > 0 && (count >= max_urls || current.len() + element.len() + URLSET_CLOSE.len() > max_bytes) {
+            current.push_str(URLSET_CLOSE);
+            documents.push(std::mem::replace(&mut current, String::from(URLSET_OPEN)));
+            count = 0;
+        }
+        current.push_str(&element);
+        count += 1;
+    }
+    if count > 0 || documents.is_empty() {
+        current.push_str(URLSET_CLOSE);
+        documents.push(current);
+    }
+    documents
+}
+
+/// Write a sitemap for `entries` to `path`. If the protocol limits are
+/// exceeded, the URLs are split across `<stem>-1.xml`, `<stem>-2.xml`, ...
+/// next to `path`, and `path` becomes a `<sitemapindex>` pointing at them
+/// under `base_url` (the public location the files will be served from).
+/// Returns every file written, index first.
+pub fn write_sitemap(entries: &[SitemapEntry], path: &Path, base_url: &str) -> Result<Vec<PathBuf>, ExportError> {
+    let documents = render_urlsets(entries, MAX_SITEMAP_URLS, MAX_SITEMAP_BYTES);
+    if documents.len() == 1 {
+        std::fs::write(path, &documents[0])?;
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| ExportError::ExportFailed(format!("Invalid sitemap path: {}", path.display())))?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let base_url = base_url.trim_end_matches('/');
+    let lastmod = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut index = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    let mut written = vec![path.to_path_buf()];
+    for (i, document) in documents.iter().enumerate() {
+        let file_name = format!("{}-{}.xml", stem, i + 1);
+        let part = dir.join(&file_name);
+        std::fs::write(&part, document)?;
+        index.push_str(&format!(
+            "  <sitemap>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n  </sitemap>\n",
+            escape_xml(&format!("{}/{}", base_url, file_name)),
+            lastmod
+        ));
+        written.push(part);
+    }
+    index.push_str("</sitemapindex>\n");
+    std::fs::write(path, index)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(loc: &str) -> SitemapEntry {
+        SitemapEntry {
+            loc: loc.to_string(),
+            lastmod: None,
+        }
+    }
+
+    #[test]
+    fn test_render_urlset_escapes_and_dates() {
+        let lastmod = DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z").unwrap().with_timezone(&Utc);
+        let xml = render_urlset(&[SitemapEntry {
+            loc: "https://example.com/?a=1&b=2".to_string(),
+            lastmod: Some(lastmod),
+        }]);
+        assert!(xml.contains("<loc>https://example.com/?a=1&amp;b=2</loc>"));
+        assert!(xml.contains("<lastmod>2024-05-01T12:30:00Z</lastmod>"));
+        assert!(xml.ends_with("</urlset>\n"));
+    }
+
+    #[test]
+    fn test_render_urlsets_splits_on_limits() {
+        let entries: Vec<_> = (0..5).map(|i| entry(&format!("https://example.com/{}", i))).collect();
+        assert_eq!(render_urlsets(&entries, 2, MAX_SITEMAP_BYTES).len(), 3);
+        assert_eq!(render_urlsets(&entries, 10, MAX_SITEMAP_BYTES).len(), 1);
+
+        let one = render_urlset(&entries[..1]).len();
+        assert_eq!(render_urlsets(&entries, 10, one).len(), 5);
+        assert_eq!(render_urlsets(&[], 10, MAX_SITEMAP_BYTES).len(), 1);
+    }
+}
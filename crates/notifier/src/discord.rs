@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use crate::{NotificationLevel, NotifierError};
+
+/// Attempts per message, including the first, when Discord answers 429.
+const MAX_ATTEMPTS: u32 = 4;
+/// Used when a 429 response doesn't say how long to wait.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(2);
+
+/// Posts notifications to a Discord channel webhook as embeds.
+#[derive(Debug, Clone)]
+pub struct DiscordBackend {
+    webhook_url: String,
+}
+
+impl DiscordBackend {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            webhook_url: webhook_url.into(),
+        }
+    }
+
+    /// Post one embed, backing off and retrying while Discord rate limits us.
+    /// This blocks, so don't call it from an async task; use
+    /// `send_in_background` there.
+    pub fn send(
+        &self,
+        title: &str,
+        message: &str,
+        level: &NotificationLevel,
+        fields: &[(&str, String)],
+    ) -> Result<(), NotifierError> {
+        let payload = build_payload(title, message, level, fields);
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| NotifierError::SendFailed(e.to_string()))?;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let response = client
+                .post(&self.webhook_url)
+                .json(&payload)
+                .send()
+                .map_err(|e| NotifierError::SendFailed(e.to_string()))?;
+
+            let status = response.status();
+            if status.is_success() {
+                debug!("Discord notification sent");
+                return Ok(());
+            }
+            if status != reqwest::StatusCode::TOO_MANY_REQUESTS || attempt == MAX_ATTEMPTS {
+                return Err(NotifierError::SendFailed(format!("Discord webhook returned {}", status)));
+            }
+
+            let wait = retry_after(response);
+            warn!("Discord rate limited, retrying in {:?} (attempt {}/{})", wait, attempt, MAX_ATTEMPTS);
+            std::thread::sleep(wait);
+        }
+        unreachable!("the last attempt always returns")
+    }
+
+    /// Send on a background thread so callers on an async runtime never
+    /// block on the webhook. Failures are logged.
+    pub fn send_in_background(
+        &self,
+        title: &str,
+        message: &str,
+        level: NotificationLevel,
+        fields: Vec<(&'static str, String)>,
+    ) -> std::thread::JoinHandle<()> {
+        let backend = self.clone();
+        let title = title.to_string();
+        let message = message.to_string();
+        std::thread::spawn(move || {
+            if let Err(e) = backend.send(&title, &message, &level, &fields) {
+                warn!("Discord notification failed: {}", e);
+            }
+        })
+    }
+}
+
+/// Wait time from a 429 response: the JSON `retry_after` (seconds) or the
+/// `Retry-After` header.
+fn retry_after(response: reqwest::blocking::Response) -> Duration {
+    let header = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<f64>().ok());
+    let body = response
+        .json::<Value>()
+        .ok()
+        .and_then(|body| body.get("retry_after").and_then(Value::as_f64));
+    body.or(header)
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+fn embed_color(level: &NotificationLevel) -> u32 {
+    match level {
+        NotificationLevel::Info => 0x3498db,
+        NotificationLevel::Success => 0x2ecc71,
+        NotificationLevel::Warning => 0xf1c40f,
+        NotificationLevel::Error => 0xe74c3c,
+    }
+}
+
+fn build_payload(title: &str, message: &str, level: &NotificationLevel, fields: &[(&str, String)]) -> Value {
+    let fields: Vec<Value> = fields
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value, "inline": true }))
+        .collect();
+    json!({
+        "embeds": [{
+            "title": title,
+            "description": message,
+            "color": embed_color(level),
+            "fields": fields,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_payload() {
+        let payload = build_payload(
+            "Recording Completed",
+            "Done",
+            &NotificationLevel::Success,
+            &[("Session ID", "abc".to_string()), ("Duration", "42s".to_string())],
+        );
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "Recording Completed");
+        assert_eq!(embed["color"], 0x2ecc71);
+        assert_eq!(embed["fields"][0]["name"], "Session ID");
+        assert_eq!(embed["fields"][1]["value"], "42s");
+
+        let payload = build_payload("Error Occurred", "boom", &NotificationLevel::Error, &[]);
+        assert_eq!(payload["embeds"][0]["color"], 0xe74c3c);
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+use std::time::Duration;
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::NotifierError;
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailTls {
+    /// Plain connection (local relays only).
+    None,
+    /// Upgrade with STARTTLS, usually on port 587.
+    #[default]
+    StartTls,
+    /// TLS from the first byte, usually on port 465.
+    Tls,
+}
+
+/// SMTP settings for end-of-run and error emails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub smtp_server: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub tls: EmailTls,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+impl EmailConfig {
+    /// Load a `.toml` file as TOML and anything else as JSON.
+    pub fn load(path: &Path) -> Result<Self, NotifierError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| NotifierError::NotificationError(format!("{}: {}", path.display(), e)))?;
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        let config: Self = if is_toml {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        }
+        .map_err(|e| NotifierError::NotificationError(format!("{}: {}", path.display(), e)))?;
+
+        if config.to.is_empty() {
+            return Err(NotifierError::NotificationError(format!(
+                "{}: at least one recipient is required in `to`",
+                path.display()
+            )));
+        }
+        Ok(config)
+    }
+}
+
+/// Sends notifications as plain-text email over SMTP.
+#[derive(Debug, Clone)]
+pub struct EmailBackend {
+    config: EmailConfig,
+}
+
+impl EmailBackend {
+    pub fn new(config: EmailConfig) -> Self {
+        Self { config }
+    }
+
+    /// Send one email to every recipient. This blocks on the SMTP exchange,
+    /// so async callers should use `send_in_background`.
+    pub fn send(&self, subject: &str, body: &str) -> Result<(), NotifierError> {
+        let message = self.build_message(subject, body)?;
+        self.transport()?
+            .send(&message)
+            .map_err(|e| NotifierError::SendFailed(format!("SMTP {}: {}", self.config.smtp_server, e)))?;
+        debug!("Email sent to {} recipient(s)", self.config.to.len());
+        Ok(())
+    }
+
+    /// Send on a background thread; failures are logged, never propagated,
+    /// so an unreachable SMTP server can't abort a crawl.
+    pub fn send_in_background(&self, subject: &str, body: &str) -> std::thread::JoinHandle<()> {
+        let backend = self.clone();
+        let subject = subject.to_string();
+        let body = body.to_string();
+        std::thread::spawn(move || {
+            if let Err(e) = backend.send(&subject, &body) {
+                warn!("Email notification failed: {}", e);
+            }
+        })
+    }
+
+    fn build_message(&self, subject: &str, body: &str) -> Result<Message, NotifierError> {
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| NotifierError::NotificationError(format!("Invalid email address '{}': {}", address, e)))
+        };
+        let mut builder = Message::builder().from(parse(&self.config.from)?).subject(subject);
+        for recipient in &self.config.to {
+            builder = builder.to(parse(recipient)?);
+        }
+        builder
+            .body(body.to_string())
+            .map_err(|e| NotifierError::NotificationError(e.to_string()))
+    }
+
+    fn transport(&self) -> Result<SmtpTransport, NotifierError> {
+        let server = &self.config.smtp_server;
+        let builder = match self.config.tls {
+            EmailTls::None => Ok(SmtpTransport::builder_dangerous(server)),
+            EmailTls::StartTls => SmtpTransport::starttls_relay(server),
+            EmailTls::Tls => SmtpTransport::relay(server),
+        }
+        .map_err(|e| NotifierError::SendFailed(format!("SMTP {}: {}", server, e)))?;
+
+        let mut builder = builder.port(self.config.port).timeout(Some(Duration::from_secs(30)));
+        if let Some(username) = &self.config.username {
+            let password = self.config.password.clone().unwrap_or_default();
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_config_defaults_and_message() {
+        let config: EmailConfig = serde_json::from_str(
+            r#"{"smtp_server": "smtp.example.com", "from": "recorder@example.com", "to": ["a@example.com", "b@example.com"]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.port, 587);
+        assert_eq!(config.tls, EmailTls::StartTls);
+
+        let backend = EmailBackend::new(config);
+        let message = backend.build_message("Crawl Completed", "Visited 3 pages").unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("To: a@example.com, b@example.com"));
+        assert!(raw.contains("Subject: Crawl Completed"));
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::Result;
 use notify_rust::Notification;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
 use thiserror::Error;
 use tracing::{debug, info};
 
+pub mod discord;
+pub use discord::DiscordBackend;
+pub mod email;
+pub use email::{EmailBackend, EmailConfig, EmailTls};
+
 #[derive(Debug, Error)]
 pub enum NotifierError {
     #[error("Failed to send notification: {0}")]
@@ -26,6 +33,35 @@ pub struct NotificationConfig {
     pub icon: Option<String>,
     pub timeout_ms: i32,
     pub sound_enabled: bool,
+    /// Show desktop notifications. Off for headless CLI runs.
+    #[serde(default = "default_desktop_enabled")]
+    pub desktop_enabled: bool,
+    /// Also post every notification to this Discord webhook.
+    #[serde(default)]
+    pub discord_webhook_url: Option<String>,
+    /// Email warnings, errors and the end-of-run summary.
+    #[serde(default)]
+    pub email: Option<EmailConfig>,
+}
+
+fn default_desktop_enabled() -> bool {
+    true
+}
+
+/// End-of-run summary sent to the remote backends (Discord, email).
+#[derive(Debug, Clone, Default)]
+pub struct RunSummary {
+    pub session_id: String,
+    pub url: String,
+    pub pages_visited: usize,
+    pub pages_failed: usize,
+    /// Pages skipped because their content matched an earlier page.
+    pub pages_duplicate: usize,
+    pub duration_secs: u64,
+    /// Why the crawl ended, e.g. "page limit reached".
+    pub stopped_by: Option<String>,
+    /// Problems with the recording, e.g. a black or frozen capture.
+    pub capture_warnings: Vec<String>,
 }
 
 impl Default for NotificationConfig {
@@ -35,22 +71,59 @@ impl Default for NotificationConfig {
             icon: None,
             timeout_ms: 5000,
             sound_enabled: true,
+            desktop_enabled: true,
+            discord_webhook_url: None,
+            email: None,
         }
     }
 }
 
 pub struct Notifier {
     config: NotificationConfig,
+    discord: Option<DiscordBackend>,
+    email: Option<EmailBackend>,
+    /// Background deliveries not yet joined by `wait_for_deliveries`.
+    pending: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl Notifier {
     pub fn new(config: NotificationConfig) -> Self {
-        Self { config }
+        let discord = config.discord_webhook_url.as_deref().map(DiscordBackend::new);
+        let email = config.email.clone().map(EmailBackend::new);
+        Self {
+            config,
+            discord,
+            email,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn send(&self, title: &str, message: &str, level: NotificationLevel) -> Result<(), NotifierError> {
+        self.send_with_fields(title, message, level, Vec::new())
     }
 
-    pub fn send(&self, title: &str, message: &str, _level: NotificationLevel) -> Result<(), NotifierError> {
+    /// Like `send`, with extra name/value pairs shown as fields by backends
+    /// that support them (Discord).
+    pub fn send_with_fields(
+        &self,
+        title: &str,
+        message: &str,
+        level: NotificationLevel,
+        fields: Vec<(&'static str, String)>,
+    ) -> Result<(), NotifierError> {
         info!("Sending notification: {} - {}", title, message);
 
+        // Email is for things worth reading later, not every info event.
+        if matches!(level, NotificationLevel::Warning | NotificationLevel::Error) {
+            self.send_email(title, message, &fields);
+        }
+        if let Some(discord) = &self.discord {
+            self.track(discord.send_in_background(title, message, level, fields));
+        }
+        if !self.config.desktop_enabled {
+            return Ok(());
+        }
+
         #[cfg(not(target_os = "macos"))]
         {
             let mut notification = Notification::new();
@@ -93,6 +166,67 @@ impl Notifier {
         Ok(())
     }
 
+    /// Send the end-of-run summary to Discord and email. Desktop
+    /// notifications already cover this through the crawl/recording events.
+    pub fn notify_run_summary(&self, summary: &RunSummary) {
+        let title = "Crawl Summary";
+        let message = format!(
+            "Crawl of {} finished: {} pages visited, {} failed",
+            summary.url, summary.pages_visited, summary.pages_failed
+        );
+        let mut fields = vec![
+            ("Session ID", summary.session_id.clone()),
+            ("Duration", format!("{}s", summary.duration_secs)),
+        ];
+        if summary.pages_duplicate > 0 {
+            fields.push(("Duplicates skipped", summary.pages_duplicate.to_string()));
+        }
+        if let Some(stopped_by) = &summary.stopped_by {
+            fields.push(("Stopped by", stopped_by.clone()));
+        }
+        if !summary.capture_warnings.is_empty() {
+            fields.push(("Recording", summary.capture_warnings.join("\n")));
+        }
+        let level = if summary.pages_failed > 0 || !summary.capture_warnings.is_empty() {
+            NotificationLevel::Warning
+        } else {
+            NotificationLevel::Success
+        };
+        info!("Sending run summary: {}", message);
+
+        self.send_email(title, &message, &fields);
+        if let Some(discord) = &self.discord {
+            self.track(discord.send_in_background(title, &message, level, fields));
+        }
+    }
+
+    /// Block until queued Discord and email deliveries finish. Call before
+    /// the process exits so the final summary isn't lost.
+    pub fn wait_for_deliveries(&self) {
+        let handles: Vec<_> = self.pending.lock().map(|mut p| p.drain(..).collect()).unwrap_or_default();
+        for handle in handles {
+            let _ = handle.join();
+        }
+    }
+
+    fn send_email(&self, title: &str, message: &str, fields: &[(&'static str, String)]) {
+        if let Some(email) = &self.email {
+            let mut body = format!("{}\n", message);
+            for (name, value) in fields {
+                body.push_str(&format!("\n{}: {}", name, value));
+            }
+            let subject = format!("[{}] {}", self.config.app_name, title);
+            self.track(email.send_in_background(&subject, &body));
+        }
+    }
+
+    fn track(&self, handle: JoinHandle<()>) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|h| !h.is_finished());
+            pending.push(handle);
+        }
+    }
+
     pub fn notify_info(&self, title: &str, message: &str) -> Result<(), NotifierError> {
         self.send(title, message, NotificationLevel::Info)
     }
@@ -110,19 +244,26 @@ impl Notifier {
     }
 
     pub fn notify_recording_started(&self, session_id: &str) -> Result<(), NotifierError> {
-        self.notify_info(
+        self.send_with_fields(
             "Recording Started",
             &format!("Session {} recording has started", session_id),
+            NotificationLevel::Info,
+            vec![("Session ID", session_id.to_string())],
         )
     }
 
     pub fn notify_recording_stopped(&self, session_id: &str, duration_secs: u64) -> Result<(), NotifierError> {
-        self.notify_success(
+        self.send_with_fields(
             "Recording Completed",
             &format!(
                 "Session {} recording completed. Duration: {} seconds",
                 session_id, duration_secs
             ),
+            NotificationLevel::Success,
+            vec![
+                ("Session ID", session_id.to_string()),
+                ("Duration", format!("{}s", duration_secs)),
+            ],
         )
     }
 
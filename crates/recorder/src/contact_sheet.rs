@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use headless_chrome::protocol::cdp::Page;
+use headless_chrome::Tab;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::{ensure_writable, Recorder, RecorderError};
+
+/// Grid and tile size of a contact sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactSheetConfig {
+    pub columns: u32,
+    pub rows: u32,
+    /// Tile width in pixels; tiles are 16:10.
+    #[serde(default = "default_tile_width")]
+    pub tile_width: u32,
+}
+
+/// Default for `ContactSheetConfig::tile_width`.
+pub const DEFAULT_TILE_WIDTH: u32 = 480;
+
+fn default_tile_width() -> u32 {
+    DEFAULT_TILE_WIDTH
+}
+
+impl Default for ContactSheetConfig {
+    fn default() -> Self {
+        Self {
+            columns: 4,
+            rows: 4,
+            tile_width: DEFAULT_TILE_WIDTH,
+        }
+    }
+}
+
+impl ContactSheetConfig {
+    fn tiles(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    /// Tile height, rounded down to an even number for the JPEG encoder.
+    fn tile_height(&self) -> u32 {
+        (self.tile_width * 10 / 16) & !1
+    }
+}
+
+/// One screenshot per visited page, tiled into a single image labeled with
+/// each page's URL.
+pub struct ContactSheet {
+    config: ContactSheetConfig,
+    dir: PathBuf,
+    /// Screenshot path and URL of each page, in visit order.
+    pages: Vec<(PathBuf, String)>,
+}
+
+impl ContactSheet {
+    /// Keep page screenshots in `dir`, creating it if needed.
+    pub fn new(dir: PathBuf, config: ContactSheetConfig) -> Result<Self, RecorderError> {
+        ensure_writable(&dir)?;
+        Ok(Self {
+            config,
+            dir,
+            pages: Vec::new(),
+        })
+    }
+
+    /// Screenshot the page `tab` shows.
+    pub fn add_page(&mut self, tab: &Tab, url: &str) -> Result<(), RecorderError> {
+        let data = tab
+            .capture_screenshot(Page::CaptureScreenshotFormatOption::Jpeg, Some(80), None, true)
+            .map_err(|e| RecorderError::RecordingError(format!("Failed to capture {}: {}", url, e)))?;
+        let path = self.dir.join(format!("page_{:04}.jpg", self.pages.len() + 1));
+        std::fs::write(&path, data)?;
+        self.pages.push((path, url.to_string()));
+        Ok(())
+    }
+
+    /// Tile the pages into `output`, evenly sampling them when there are
+    /// more pages than tiles. Returns `false` when no page was captured.
+    pub fn render(&self, output: &Path) -> Result<bool, RecorderError> {
+        if self.pages.is_empty() {
+            return Ok(false);
+        }
+        Recorder::check_ffmpeg()?;
+
+        let picked = sample(self.pages.len(), self.config.tiles());
+        info!(
+            "Building {}x{} contact sheet from {} of {} pages",
+            self.config.columns,
+            self.config.rows,
+            picked.len(),
+            self.pages.len()
+        );
+        let result = self.label_tiles(&picked).and_then(|_| self.tile(output));
+        for n in 1..=picked.len() {
+            let _ = std::fs::remove_file(self.dir.join(format!("tile_{:04}.jpg", n)));
+            let _ = std::fs::remove_file(self.dir.join(format!("label_{:04}.txt", n)));
+        }
+        result.map(|_| true)
+    }
+
+    /// Scale each picked page to the tile size and caption it with its URL.
+    fn label_tiles(&self, picked: &[usize]) -> Result<(), RecorderError> {
+        let (width, height) = (self.config.tile_width, self.config.tile_height());
+        for (n, &index) in picked.iter().enumerate() {
+            let (ref screenshot, ref url) = self.pages[index];
+            // drawtext reads the URL from a file so it needs no filter escaping
+            let label = format!("label_{:04}.txt", n + 1);
+            std::fs::write(self.dir.join(&label), url)?;
+            let filter = format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:white,\
+                 drawtext=textfile={label}:expansion=none:fontsize={font}:fontcolor=white:\
+                 box=1:boxcolor=black@0.7:boxborderw=6:x=6:y=h-text_h-6",
+                w = width,
+                h = height,
+                label = label,
+                font = (height / 18).max(10),
+            );
+            run_ffmpeg(
+                Command::new("ffmpeg")
+                    .current_dir(&self.dir)
+                    .arg("-i")
+                    .arg(screenshot)
+                    .arg("-vf")
+                    .arg(filter)
+                    .arg("-y")
+                    .arg(format!("tile_{:04}.jpg", n + 1)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn tile(&self, output: &Path) -> Result<(), RecorderError> {
+        run_ffmpeg(
+            Command::new("ffmpeg")
+                .arg("-framerate")
+                .arg("1")
+                .arg("-i")
+                .arg(self.dir.join("tile_%04d.jpg"))
+                .arg("-vf")
+                .arg(format!(
+                    "tile={}x{}:padding=4:margin=4",
+                    self.config.columns, self.config.rows
+                ))
+                .arg("-frames:v")
+                .arg("1")
+                .arg("-y")
+                .arg(output),
+        )?;
+        info!("Contact sheet written to {:?}", output);
+        Ok(())
+    }
+}
+
+/// Indices of up to `tiles` pages out of `pages`, evenly spaced and always
+/// including the first.
+fn sample(pages: usize, tiles: usize) -> Vec<usize> {
+    if pages <= tiles {
+        return (0..pages).collect();
+    }
+    (0..tiles).map(|i| i * pages / tiles).collect()
+}
+
+fn run_ffmpeg(cmd: &mut Command) -> Result<(), RecorderError> {
+    let output = cmd
+        .output()
+        .map_err(|e| RecorderError::EncodingError(format!("Failed to run FFmpeg: {}", e)))?;
+    if !output.status.success() {
+        error!("FFmpeg stderr: {}", String::from_utf8_lossy(&output.stderr));
+        return Err(RecorderError::EncodingError(format!(
+            "FFmpeg failed with exit code: {}",
+            output.status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_pages() {
+        assert_eq!(sample(3, 16), vec![0, 1, 2]);
+        assert_eq!(sample(8, 4), vec![0, 2, 4, 6]);
+        assert_eq!(sample(10, 3), vec![0, 3, 6]);
+        assert_eq!(ContactSheetConfig::default().tile_height(), 300);
+    }
+}
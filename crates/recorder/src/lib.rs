@@ -1,16 +1,21 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 use url::Url;
+use headless_chrome::protocol::cdp::Page;
 use headless_chrome::Tab;
 
+pub mod contact_sheet;
+pub use contact_sheet::{ContactSheet, ContactSheetConfig};
+
 #[derive(Debug, Error)]
 pub enum RecorderError {
     #[error("Failed to start recording: {0}")]
@@ -23,6 +28,16 @@ pub enum RecorderError {
     IoError(#[from] std::io::Error),
     #[error("Encoding error: {0}")]
     EncodingError(String),
+    #[error("Unsupported audio settings: {0}")]
+    UnsupportedAudio(String),
+    #[error("FFmpeg not found. Please install FFmpeg and make sure it is on your PATH")]
+    FfmpegNotFound,
+    #[error("Output directory {} is not writable: {source}", .dir.display())]
+    OutputNotWritable {
+        dir: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,11 +59,187 @@ impl VideoFormat {
     }
 }
 
+/// Codec for the audio track of screen recordings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioCodec {
+    /// Opus for WebM, AAC for the other containers.
+    #[default]
+    Auto,
+    Aac,
+    Opus,
+}
+
+impl AudioCodec {
+    /// The codec used in `format`, or an error if `format` can't hold it.
+    pub fn for_format(self, format: &VideoFormat) -> Result<AudioCodec, RecorderError> {
+        match (self, format) {
+            (AudioCodec::Auto, VideoFormat::Webm) => Ok(AudioCodec::Opus),
+            (AudioCodec::Auto, _) => Ok(AudioCodec::Aac),
+            (AudioCodec::Aac, VideoFormat::Webm) => Err(RecorderError::UnsupportedAudio(
+                "WebM can't hold AAC audio; use Opus".to_string(),
+            )),
+            (AudioCodec::Opus, VideoFormat::Avi) => Err(RecorderError::UnsupportedAudio(
+                "AVI can't hold Opus audio; use AAC".to_string(),
+            )),
+            (codec, _) => Ok(codec),
+        }
+    }
+
+    fn encoder(&self) -> &'static str {
+        match self {
+            AudioCodec::Auto | AudioCodec::Aac => "aac",
+            AudioCodec::Opus => "libopus",
+        }
+    }
+}
+
+/// Accepted range for `RecordingConfig::audio_bitrate_kbps`.
+pub const AUDIO_BITRATE_KBPS: std::ops::RangeInclusive<u32> = 8..=512;
+
+/// Image format for browser screenshot frames. JPEG frames are several
+/// times smaller than PNG, which matters at 30fps.
+///
+/// Frames are an intermediate: [`convert_frames_to_video`] re-encodes them
+/// to H.264 at libx264's default CRF, so a frame quality above about 90
+/// mostly costs disk space without a visible gain in the video, while a low
+/// one bakes its artifacts into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg {
+        /// 1-100
+        #[serde(default = "default_screenshot_quality")]
+        quality: u8,
+    },
+    Webp {
+        /// 1-100
+        #[serde(default = "default_screenshot_quality")]
+        quality: u8,
+    },
+}
+
+/// Default quality of JPEG and WebP frames.
+pub const DEFAULT_SCREENSHOT_QUALITY: u8 = 85;
+
+fn default_screenshot_quality() -> u8 {
+    DEFAULT_SCREENSHOT_QUALITY
+}
+
+impl ScreenshotFormat {
+    /// Frame quality for lossy formats; `None` for PNG.
+    pub fn quality(&self) -> Option<u8> {
+        match self {
+            ScreenshotFormat::Png => None,
+            ScreenshotFormat::Jpeg { quality } | ScreenshotFormat::Webp { quality } => Some(*quality),
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Jpeg { .. } => "jpg",
+            ScreenshotFormat::Webp { .. } => "webp",
+        }
+    }
+
+    /// CDP format and quality arguments for `Tab::capture_screenshot`.
+    fn capture_options(&self) -> (Page::CaptureScreenshotFormatOption, Option<u32>) {
+        match self {
+            ScreenshotFormat::Png => (Page::CaptureScreenshotFormatOption::Png, None),
+            ScreenshotFormat::Jpeg { .. } => (Page::CaptureScreenshotFormatOption::Jpeg, self.capture_quality()),
+            ScreenshotFormat::Webp { .. } => (Page::CaptureScreenshotFormatOption::Webp, self.capture_quality()),
+        }
+    }
+
+    /// [`quality`](Self::quality) clamped to the 1-100 CDP accepts.
+    fn capture_quality(&self) -> Option<u32> {
+        self.quality().map(|quality| u32::from(quality.clamp(1, 100)))
+    }
+
+    /// File name of frame `index`, matching [`frame_pattern`](Self::frame_pattern).
+    fn frame_file_name(&self, index: u64) -> String {
+        format!("frame_{:06}.{}", index, self.extension())
+    }
+
+    /// FFmpeg image2 input pattern for the frames.
+    fn frame_pattern(&self) -> String {
+        format!("frame_%06d.{}", self.extension())
+    }
+
+    /// The format whose frames have file extension `extension`.
+    fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "png" => Some(ScreenshotFormat::Png),
+            "jpg" => Some(ScreenshotFormat::Jpeg { quality: DEFAULT_SCREENSHOT_QUALITY }),
+            "webp" => Some(ScreenshotFormat::Webp { quality: DEFAULT_SCREENSHOT_QUALITY }),
+            _ => None,
+        }
+    }
+}
+
+/// Where FFmpeg writes the screen recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "path", rename_all = "lowercase")]
+pub enum RecordingOutput {
+    /// A regular file.
+    File(PathBuf),
+    /// This process's standard output, for piping into another program.
+    Stdout,
+    /// An existing named pipe (FIFO), e.g. read by a streaming process.
+    Pipe(PathBuf),
+}
+
+impl RecordingOutput {
+    /// Output argument for FFmpeg.
+    fn ffmpeg_target(&self) -> String {
+        match self {
+            RecordingOutput::File(path) | RecordingOutput::Pipe(path) => path.to_string_lossy().into_owned(),
+            RecordingOutput::Stdout => "pipe:1".to_string(),
+        }
+    }
+
+    /// Whether the output can't be seeked, so the container must be
+    /// written front to back.
+    fn is_stream(&self) -> bool {
+        !matches!(self, RecordingOutput::File(_))
+    }
+
+    /// Muxer arguments for `format`. Streams use fragmented MP4 for MP4
+    /// and Matroska otherwise, since neither needs to seek back to finish
+    /// the file.
+    fn muxer_args(&self, format: &VideoFormat) -> Vec<&'static str> {
+        match (self.is_stream(), format) {
+            (false, _) => Vec::new(),
+            (true, VideoFormat::Mp4) => vec!["-f", "mp4", "-movflags", "frag_keyframe+empty_moov+default_base_moof"],
+            (true, _) => vec!["-f", "matroska"],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RecordingMode {
     Screen,      // Record the actual screen only
     Browser,     // Record browser screenshots only
     Both,        // Record both screen and browser screenshots simultaneously
+    None,        // Capture nothing; sessions still get metadata and exports
+    /// Browser screenshots cropped to the first element matching
+    /// `selector`, following it if it moves.
+    Element { selector: String },
+}
+
+impl RecordingMode {
+    /// Whether this mode produces a video file.
+    pub fn records(&self) -> bool {
+        !matches!(self, RecordingMode::None)
+    }
+
+    /// Whether this mode captures the screen with FFmpeg.
+    pub fn records_screen(&self) -> bool {
+        matches!(self, RecordingMode::Screen | RecordingMode::Both)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +253,361 @@ pub struct RecordingConfig {
     pub screen_width: Option<u32>,
     pub screen_height: Option<u32>,
     pub screen_region: Option<(i32, i32, i32, i32)>, // (x, y, width, height)
+    /// Place every artifact of a session (videos, frames, metadata) under
+    /// `output_dir/<session_id>/` instead of directly in `output_dir`.
+    #[serde(default)]
+    pub organize_by_session: bool,
+    /// How long FFmpeg may take to finalize the file after `q` before it is
+    /// killed. Large recordings need longer to write the moov atom.
+    #[serde(default = "default_finalize_timeout_secs")]
+    pub finalize_timeout_secs: u64,
+    /// Format of browser screenshot frames.
+    #[serde(default)]
+    pub screenshot_format: ScreenshotFormat,
+    /// Send the screen recording here instead of a timestamped file in the
+    /// session directory.
+    #[serde(default)]
+    pub output: Option<RecordingOutput>,
+    /// Stop recording once the video file and browser frames on disk add up
+    /// to this many megabytes. See [`Recorder::size_limit_reached`].
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    /// Slow browser frame capture down while the resident memory of this
+    /// process, the FFmpeg capture and the browser (see
+    /// [`Recorder::set_browser_pid`]) is over this many megabytes, instead
+    /// of risking the OOM killer. See [`Recorder::capture_slowdown`].
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+    /// Prepend a title card to each finished video file.
+    #[serde(default)]
+    pub intro_card: Option<IntroCard>,
+    /// Browser frames per `batch_NNNN` subdirectory of the frames
+    /// directory. Each batch is encoded into its own segment at stop and
+    /// the segments joined, so no directory or FFmpeg input grows unbounded.
+    #[serde(default = "default_frame_batch_size")]
+    pub frame_batch_size: u64,
+    /// Probe each finished video with `ffprobe` and add a capture warning
+    /// when it is empty, has no video stream or no duration. See
+    /// [`verify_playable`].
+    #[serde(default)]
+    pub verify_output: bool,
+    /// Codec for recorded audio.
+    #[serde(default)]
+    pub audio_codec: AudioCodec,
+    /// Audio bitrate; the encoder's default when `None`.
+    #[serde(default)]
+    pub audio_bitrate_kbps: Option<u32>,
+    /// Pixel size of the video made from browser frames, normally the
+    /// viewport times the device pixel ratio; frames of another size are
+    /// scaled to it. `None` keeps the frames' own size.
+    #[serde(default)]
+    pub frame_size: Option<(u32, u32)>,
+}
+
+/// A title card shown before the footage, naming the recorded URL, the
+/// date and the session ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntroCard {
+    #[serde(default = "default_intro_card_secs")]
+    pub duration_secs: u32,
+}
+
+/// Default for `IntroCard::duration_secs`.
+pub const DEFAULT_INTRO_CARD_SECS: u32 = 3;
+
+fn default_intro_card_secs() -> u32 {
+    DEFAULT_INTRO_CARD_SECS
+}
+
+impl Default for IntroCard {
+    fn default() -> Self {
+        Self {
+            duration_secs: DEFAULT_INTRO_CARD_SECS,
+        }
+    }
+}
+
+/// Default for `RecordingConfig::frame_batch_size`.
+pub const DEFAULT_FRAME_BATCH_SIZE: u64 = 5000;
+
+fn default_frame_batch_size() -> u64 {
+    DEFAULT_FRAME_BATCH_SIZE
+}
+
+/// Default for `RecordingConfig::finalize_timeout_secs`.
+pub const DEFAULT_FINALIZE_TIMEOUT_SECS: u64 = 30;
+
+fn default_finalize_timeout_secs() -> u64 {
+    DEFAULT_FINALIZE_TIMEOUT_SECS
+}
+
+/// Browser frames that may wait in memory for a disk writer before new
+/// frames are dropped.
+const FRAME_QUEUE_CAPACITY: usize = 60;
+
+/// Concurrent tasks writing browser frames to disk.
+const FRAME_WRITERS: usize = 2;
+
+/// Attempts at writing one browser frame before it is left out.
+const FRAME_WRITE_ATTEMPTS: u32 = 3;
+
+/// Which browser frames are on disk. Writers finish out of order, so frames
+/// past a gap are kept aside until the frames before them are written.
+#[derive(Debug)]
+struct WrittenFrames {
+    first: u64,
+    next: u64,
+    ahead: std::collections::BTreeSet<u64>,
+}
+
+impl WrittenFrames {
+    fn new(first: u64) -> Self {
+        Self { first, next: first, ahead: Default::default() }
+    }
+
+    fn mark(&mut self, index: u64) {
+        if index < self.next {
+            return;
+        }
+        self.ahead.insert(index);
+        while self.ahead.remove(&self.next) {
+            self.next += 1;
+        }
+    }
+
+    /// Highest frame index with every frame of this recording up to it on
+    /// disk, or `None` before the first frame is written.
+    fn last_contiguous(&self) -> Option<u64> {
+        (self.next > self.first).then(|| self.next - 1)
+    }
+}
+
+/// How often the size watchdog checks the recording on disk.
+const SIZE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// How often the memory guard samples the process's resident memory.
+const MEMORY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Most the memory guard stretches the browser frame interval, as a
+/// multiple of the normal one.
+const MAX_CAPTURE_SLOWDOWN: u64 = 8;
+
+/// Resident memory of this process in bytes, from `/proc/self/status`.
+/// `None` where that file doesn't exist.
+fn process_rss_bytes() -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// Resident memory of this process plus the FFmpeg capture process and
+/// every process in the browser's tree, which is where most of a crawl's
+/// memory goes. `None` where `/proc` can't be read.
+fn recording_rss_bytes(ffmpeg_pid: Option<u32>, browser_pid: Option<u32>) -> Option<u64> {
+    let pid_rss = |pid: u32| {
+        std::fs::read_to_string(format!("/proc/{}/status", pid))
+            .ok()
+            .and_then(|status| parse_vm_rss(&status))
+            .unwrap_or(0)
+    };
+    let mut rss = process_rss_bytes()?;
+    rss += ffmpeg_pid.map_or(0, pid_rss);
+    if let Some(root) = browser_pid {
+        rss += process_tree(root, &process_parents()).into_iter().map(pid_rss).sum::<u64>();
+    }
+    Some(rss)
+}
+
+/// `(pid, parent pid)` of every process in `/proc`.
+fn process_parents() -> Vec<(u32, u32)> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            Some((pid, parse_stat_ppid(&stat)?))
+        })
+        .collect()
+}
+
+/// Parent PID from a `/proc/<pid>/stat` line. The command name in
+/// parentheses may itself contain spaces and parentheses, so fields are
+/// read after the last `)`.
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// `root` and all its descendants among `parents`.
+fn process_tree(root: u32, parents: &[(u32, u32)]) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&pid) = tree.get(next) {
+        tree.extend(parents.iter().filter(|&&(child, parent)| parent == pid && child != root).map(|&(child, _)| child));
+        next += 1;
+    }
+    tree
+}
+
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|line| line.strip_prefix("VmRSS:"))?;
+    let kb: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// The frame interval multiplier after sampling `rss` bytes against a
+/// `limit`: doubled while over it, up to [`MAX_CAPTURE_SLOWDOWN`], and
+/// halved once usage is back under three quarters of it.
+fn next_capture_slowdown(current: u64, rss: u64, limit: u64) -> u64 {
+    if rss >= limit {
+        (current * 2).min(MAX_CAPTURE_SLOWDOWN)
+    } else if rss < limit / 4 * 3 {
+        (current / 2).max(1)
+    } else {
+        current
+    }
+}
+
+/// Page-coordinate bounding box of a recorded element.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct ElementRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl ElementRect {
+    /// Screenshot clip at this position. The size is fixed by the first
+    /// call (rounded up to even numbers for H.264) and reused after.
+    fn clip(&self, size: &mut Option<(f64, f64)>) -> Page::Viewport {
+        let (width, height) = *size.get_or_insert((even_ceil(self.width), even_ceil(self.height)));
+        Page::Viewport {
+            x: self.x,
+            y: self.y,
+            width,
+            height,
+            scale: 1.0,
+        }
+    }
+}
+
+fn even_ceil(value: f64) -> f64 {
+    (value / 2.0).ceil().max(1.0) * 2.0
+}
+
+/// Bounding box of the first visible element matching `selector`, or
+/// `None` if nothing matches.
+fn element_rect(tab: &Tab, selector: &str) -> Result<Option<ElementRect>, RecorderError> {
+    let script = format!(
+        r#"(() => {{
+            let el;
+            try {{ el = document.querySelector({}); }} catch (e) {{ return JSON.stringify({{ error: e.message }}); }}
+            if (!el) return null;
+            const r = el.getBoundingClientRect();
+            if (r.width === 0 || r.height === 0) return null;
+            return JSON.stringify({{ x: r.left + window.scrollX, y: r.top + window.scrollY, width: r.width, height: r.height }});
+        }})()"#,
+        serde_json::Value::from(selector)
+    );
+    let value = tab
+        .evaluate(&script, false)
+        .map_err(|e| RecorderError::RecordingError(e.to_string()))?
+        .value;
+    let Some(json) = value.as_ref().and_then(|value| value.as_str()) else {
+        return Ok(None);
+    };
+    let probe: serde_json::Value = serde_json::from_str(json).map_err(|e| RecorderError::RecordingError(e.to_string()))?;
+    if let Some(error) = probe.get("error").and_then(|error| error.as_str()) {
+        return Err(RecorderError::RecordingError(format!("Invalid element selector {:?}: {}", selector, error)));
+    }
+    serde_json::from_value(probe)
+        .map(Some)
+        .map_err(|e| RecorderError::RecordingError(e.to_string()))
+}
+
+/// Create `dir` if needed and check that files can be written in it, by
+/// creating and removing a probe file.
+fn ensure_writable(dir: &Path) -> Result<(), RecorderError> {
+    let not_writable = |source| RecorderError::OutputNotWritable {
+        dir: dir.to_path_buf(),
+        source,
+    };
+    std::fs::create_dir_all(dir).map_err(not_writable)?;
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"").map_err(not_writable)?;
+    std::fs::remove_file(&probe).map_err(not_writable)?;
+    Ok(())
+}
+
+/// Bytes on disk for a recording's video file plus its browser frames,
+/// including those in batch subdirectories.
+fn recording_size(video: Option<&Path>, frames_dir: Option<&Path>) -> u64 {
+    let video = video.and_then(|path| std::fs::metadata(path).ok()).map_or(0, |meta| meta.len());
+    let frames = frames_dir.map_or(0, |dir| {
+        let batches = std::fs::read_dir(dir).into_iter().flatten().flatten().map(|entry| entry.path());
+        files_size(dir) + batches.filter(|path| path.is_dir()).map(|batch| files_size(&batch)).sum::<u64>()
+    });
+    video + frames
+}
+
+/// Bytes in the files directly inside `dir`.
+fn files_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|meta| meta.is_file())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// File name of the metadata sidecar written into per-session directories.
+pub const METADATA_FILE: &str = "metadata.json";
+
+impl RecordingConfig {
+    /// Directory that holds a session's videos and exports.
+    pub fn session_dir(&self, session_id: &str) -> PathBuf {
+        if self.organize_by_session {
+            self.output_dir.join(session_id)
+        } else {
+            self.output_dir.clone()
+        }
+    }
+
+    /// FFmpeg output arguments for the audio track: none without audio,
+    /// or an error when the codec doesn't fit the container or the bitrate
+    /// is outside [`AUDIO_BITRATE_KBPS`].
+    pub fn audio_args(&self) -> Result<Vec<String>, RecorderError> {
+        if !self.audio_enabled {
+            return Ok(Vec::new());
+        }
+        let codec = self.audio_codec.for_format(&self.format)?;
+        let mut args = vec!["-c:a".to_string(), codec.encoder().to_string()];
+        if let Some(kbps) = self.audio_bitrate_kbps {
+            if !AUDIO_BITRATE_KBPS.contains(&kbps) {
+                return Err(RecorderError::UnsupportedAudio(format!(
+                    "audio bitrate {} kbps is outside {}-{} kbps",
+                    kbps,
+                    AUDIO_BITRATE_KBPS.start(),
+                    AUDIO_BITRATE_KBPS.end()
+                )));
+            }
+            args.extend(["-b:a".to_string(), format!("{}k", kbps)]);
+        }
+        Ok(args)
+    }
+
+    /// Directory browser screenshot frames are written to.
+    pub fn frames_dir(&self, session_id: &str) -> PathBuf {
+        if self.organize_by_session {
+            self.session_dir(session_id).join("frames")
+        } else {
+            self.output_dir.join(session_id)
+        }
+    }
 }
 
 impl Default for RecordingConfig {
@@ -76,6 +622,18 @@ impl Default for RecordingConfig {
             screen_width: Some(1920),
             screen_height: Some(1080),
             screen_region: None,
+            organize_by_session: false,
+            finalize_timeout_secs: default_finalize_timeout_secs(),
+            screenshot_format: ScreenshotFormat::default(),
+            output: None,
+            max_file_size_mb: None,
+            memory_limit_mb: None,
+            intro_card: None,
+            frame_batch_size: DEFAULT_FRAME_BATCH_SIZE,
+            verify_output: false,
+            audio_codec: AudioCodec::default(),
+            audio_bitrate_kbps: None,
+            frame_size: None,
         }
     }
 }
@@ -89,44 +647,150 @@ pub struct RecordingMetadata {
     pub duration_secs: Option<u64>,
     pub file_path: Option<PathBuf>,
     pub format: VideoFormat,
+    /// Total time spent paused; included in `duration_secs`. Cut from a
+    /// browser video, but a screen video shows a frozen frame for it.
+    #[serde(default)]
+    pub paused_duration_secs: u64,
+    /// Set when recording stopped after this many pages while the crawl
+    /// went on; later pages are not in the video.
+    #[serde(default)]
+    pub recorded_pages: Option<usize>,
+    /// Problems found in the finished video, e.g. a black or frozen
+    /// capture. See [`check_capture`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capture_warnings: Vec<String>,
+    /// Index of the last browser frame with no gap before it on disk,
+    /// checkpointed to the sidecar while capturing so an interrupted
+    /// recording's frames can still be encoded up to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_contiguous_frame: Option<u64>,
+}
+
+/// Structured lifecycle events published by a [`Recorder`].
+///
+/// Consumers (progress UIs, the GUI) obtain a receiver with
+/// [`Recorder::subscribe`] instead of scraping log output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecorderEvent {
+    Started {
+        session_id: String,
+        mode: RecordingMode,
+        file_path: PathBuf,
+    },
+    Stopped {
+        session_id: String,
+        file_path: PathBuf,
+        duration_secs: u64,
+    },
+    Paused {
+        session_id: String,
+    },
+    Resumed {
+        session_id: String,
+        paused_secs: u64,
+    },
+    Error {
+        message: String,
+    },
 }
 
 pub struct Recorder {
     config: RecordingConfig,
     is_recording: Arc<AtomicBool>,
+    is_paused: Arc<AtomicBool>,
+    paused_at: Arc<RwLock<Option<DateTime<Utc>>>>,
+    /// Milliseconds spent paused in the current recording, before any
+    /// pause still in progress.
+    paused_ms: Arc<AtomicU64>,
     metadata: Arc<RwLock<Option<RecordingMetadata>>>,
     stop_tx: Arc<RwLock<Option<std::sync::mpsc::Sender<()>>>>,
     browser_tab: Arc<RwLock<Option<Arc<Tab>>>>,
+    /// Root of the browser's process tree, counted by the memory guard.
+    browser_pid: Arc<RwLock<Option<u32>>>,
     ffmpeg_process: Arc<RwLock<Option<Child>>>,
+    capture_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+    size_watchdog: Arc<RwLock<Option<JoinHandle<()>>>>,
+    size_limit_reached: Arc<AtomicBool>,
+    memory_watchdog: Arc<RwLock<Option<JoinHandle<()>>>>,
+    capture_slowdown: Arc<AtomicU64>,
+    frames_captured: Arc<AtomicU64>,
+    events: broadcast::Sender<RecorderEvent>,
 }
 
 impl Recorder {
     pub fn new(config: RecordingConfig) -> Self {
+        let (events, _) = broadcast::channel(64);
         Self {
             config,
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
+            paused_at: Arc::new(RwLock::new(None)),
+            paused_ms: Arc::new(AtomicU64::new(0)),
             metadata: Arc::new(RwLock::new(None)),
             stop_tx: Arc::new(RwLock::new(None)),
             browser_tab: Arc::new(RwLock::new(None)),
+            browser_pid: Arc::new(RwLock::new(None)),
             ffmpeg_process: Arc::new(RwLock::new(None)),
+            capture_task: Arc::new(RwLock::new(None)),
+            size_watchdog: Arc::new(RwLock::new(None)),
+            size_limit_reached: Arc::new(AtomicBool::new(false)),
+            memory_watchdog: Arc::new(RwLock::new(None)),
+            capture_slowdown: Arc::new(AtomicU64::new(1)),
+            frames_captured: Arc::new(AtomicU64::new(0)),
+            events,
         }
     }
+
+    /// Subscribe to recorder lifecycle events. Events sent before the
+    /// subscription are not replayed.
+    pub fn subscribe(&self) -> broadcast::Receiver<RecorderEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: RecorderEvent) {
+        // Sending only fails when nobody is subscribed, which is fine.
+        let _ = self.events.send(event);
+    }
     
     pub async fn set_browser_tab(&self, tab: Arc<Tab>) {
         let mut tab_guard = self.browser_tab.write().await;
         *tab_guard = Some(tab);
     }
 
+    /// Count the memory of the browser process `pid` and its children
+    /// against `memory_limit_mb`.
+    pub async fn set_browser_pid(&self, pid: Option<u32>) {
+        *self.browser_pid.write().await = pid;
+    }
+
     pub async fn start_recording(&self, session_id: String, url: Option<String>) -> Result<(), RecorderError> {
-        if self.is_recording.load(Ordering::SeqCst) {
+        // Claimed in one step so concurrent starts can't both get through
+        if self
+            .is_recording
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
             return Err(RecorderError::StartFailed("Already recording".to_string()));
         }
 
         info!("Starting recording for session: {} (mode: {:?})", session_id, self.config.mode);
 
-        // Create output directory
-        std::fs::create_dir_all(&self.config.output_dir)
-            .map_err(|e| RecorderError::StartFailed(format!("Failed to create output directory: {}", e)))?;
+        // Fail now rather than drop every frame or the audio later
+        let session_dir = self.config.session_dir(&session_id);
+        let checked = self.config.audio_args().and_then(|_| ensure_writable(&session_dir)).and_then(|_| {
+            if matches!(
+                self.config.mode,
+                RecordingMode::Browser | RecordingMode::Both | RecordingMode::Element { .. }
+            ) {
+                ensure_writable(&self.config.frames_dir(&session_id))?;
+            }
+            Ok(())
+        });
+        if let Err(e) = checked {
+            self.is_recording.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
 
         let video_name = if let Some(ref url_str) = url {
             extract_domain_name(url_str)
@@ -134,12 +798,20 @@ impl Recorder {
             session_id.clone()
         };
 
-        let output_path = self.config.output_dir.join(format!(
-            "{}_{}.{}",
-            video_name,
-            chrono::Utc::now().format("%Y%m%d_%H%M%S"),
-            self.config.format.extension()
-        ));
+        let output = self.config.output.clone().unwrap_or_else(|| {
+            RecordingOutput::File(session_dir.join(format!(
+                "{}_{}.{}",
+                video_name,
+                chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+                self.config.format.extension()
+            )))
+        });
+        // Streams have no file to report; events name the FFmpeg target
+        let output_path = match output {
+            RecordingOutput::File(ref path) => Some(path.clone()),
+            _ => None,
+        };
+        let records = self.config.mode.records();
 
         let metadata = RecordingMetadata {
             session_id: session_id.clone(),
@@ -147,53 +819,106 @@ impl Recorder {
             start_time: Utc::now(),
             end_time: None,
             duration_secs: None,
-            file_path: Some(output_path.clone()),
+            file_path: output_path.clone().filter(|_| records),
             format: self.config.format.clone(),
+            paused_duration_secs: 0,
+            recorded_pages: None,
+            capture_warnings: Vec::new(),
+            last_contiguous_frame: None,
         };
 
         let mut meta = self.metadata.write().await;
         *meta = Some(metadata);
 
-        self.is_recording.store(true, Ordering::SeqCst);
+        self.size_limit_reached.store(false, Ordering::SeqCst);
+        self.capture_slowdown.store(1, Ordering::SeqCst);
+        self.paused_ms.store(0, Ordering::SeqCst);
+
+        if let Err(e) = self.start_mode(&session_id, &output).await {
+            // Release the claim so a later start can get through, without
+            // leaving behind the FFmpeg that Both mode started first
+            if self.ffmpeg_process.read().await.is_some() {
+                let _ = self.stop_screen_recording().await;
+            }
+            *meta = None;
+            self.is_recording.store(false, Ordering::SeqCst);
+            self.emit(RecorderEvent::Error { message: e.to_string() });
+            return Err(e);
+        }
+        if !records {
+            info!("Recording disabled; tracking session metadata only");
+            return Ok(());
+        }
+        
+        info!("Recording started successfully: {:?}", output);
+        if let Some(limit_mb) = self.config.max_file_size_mb {
+            self.start_size_watchdog(&session_id, output_path.as_deref(), limit_mb).await;
+        }
+        if let Some(limit_mb) = self.config.memory_limit_mb {
+            self.start_memory_watchdog(limit_mb).await;
+        }
+        self.emit(RecorderEvent::Started {
+            session_id,
+            mode: self.config.mode.clone(),
+            file_path: PathBuf::from(output.ffmpeg_target()),
+        });
+        Ok(())
+    }
 
+    async fn start_mode(&self, session_id: &str, output: &RecordingOutput) -> Result<(), RecorderError> {
         match self.config.mode {
             RecordingMode::Screen => {
-                self.start_screen_recording(&output_path).await?;
+                self.start_screen_recording(output).await?;
             }
             RecordingMode::Browser => {
-                self.start_browser_recording(&session_id).await?;
+                self.start_browser_recording(session_id).await?;
+            }
+            RecordingMode::Element { ref selector } => {
+                let tab = self.browser_tab.read().await.clone().ok_or_else(|| {
+                    RecorderError::StartFailed("Element recording needs a browser tab".to_string())
+                })?;
+                // Before the first navigation there is nothing to match yet;
+                // the caller checks again with check_element once a page loads
+                if tab.get_url() != "about:blank" {
+                    self.check_element().await?;
+                }
+                info!("Recording element: {}", selector);
+                self.start_browser_recording(session_id).await?;
             }
             RecordingMode::Both => {
                 // Start screen recording first
                 info!("Starting screen recording (Both mode)...");
-                self.start_screen_recording(&output_path).await?;
+                self.start_screen_recording(output).await?;
                 
                 // Give FFmpeg time to initialize before starting browser screenshots
                 tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
                 
                 // Then start browser screenshots
                 info!("Starting browser screenshot capture (Both mode)...");
-                self.start_browser_recording(&session_id).await?;
+                self.start_browser_recording(session_id).await?;
                 
                 info!("Started both screen recording and browser screenshot capture");
             }
+            RecordingMode::None => {}
         }
-        
-        info!("Recording started successfully: {:?}", output_path);
         Ok(())
     }
 
-    async fn start_screen_recording(&self, output_path: &PathBuf) -> Result<(), RecorderError> {
+    async fn start_screen_recording(&self, output: &RecordingOutput) -> Result<(), RecorderError> {
         info!("Starting screen recording with FFmpeg");
 
-        // Check if ffmpeg is available
-        let ffmpeg_check = Command::new("ffmpeg").arg("-version").output();
-        if ffmpeg_check.is_err() {
-            return Err(RecorderError::StartFailed(
-                "FFmpeg not found. Please install FFmpeg for screen recording.".to_string()
-            ));
+        if let RecordingOutput::Pipe(ref path) = output {
+            if !path.exists() {
+                return Err(RecorderError::StartFailed(format!(
+                    "Named pipe {:?} does not exist; create it with mkfifo first",
+                    path
+                )));
+            }
         }
 
+        let version = Self::check_ffmpeg()?;
+        info!("Using FFmpeg {}", version);
+
         // Build platform-specific FFmpeg command
         let mut cmd = Command::new("ffmpeg");
         
@@ -284,17 +1009,19 @@ impl Recorder {
            .arg("-crf").arg(format!("{}", 51 - (self.config.quality * 51 / 100)))
            .arg("-pix_fmt").arg("yuv420p");
 
-        if self.config.audio_enabled {
-            cmd.arg("-c:a").arg("aac");
-        }
+        cmd.args(self.config.audio_args()?);
 
-        cmd.arg("-y") // Overwrite output file
-           .arg(output_path.to_str().unwrap())
+        cmd.args(output.muxer_args(&self.config.format))
+           .arg("-y") // Overwrite output file
+           .arg(output.ffmpeg_target())
            .stdin(Stdio::piped())
-           .stdout(Stdio::piped())
+           .stdout(match output {
+               RecordingOutput::Stdout => Stdio::inherit(),
+               _ => Stdio::piped(),
+           })
            .stderr(Stdio::piped());
 
-        info!("Launching FFmpeg process for: {:?}", output_path);
+        info!("Launching FFmpeg process for: {:?}", output);
         info!("FFmpeg command: {:?}", cmd);
         
         let mut child = cmd.spawn()
@@ -331,9 +1058,20 @@ impl Recorder {
     async fn start_browser_recording(&self, session_id: &str) -> Result<(), RecorderError> {
         info!("Starting browser screenshot capture");
 
-        let output_dir = self.config.output_dir.join(session_id);
-        std::fs::create_dir_all(&output_dir)
-            .map_err(|e| RecorderError::StartFailed(format!("Failed to create output directory: {}", e)))?;
+        let output_dir = self.config.frames_dir(session_id);
+        ensure_writable(&output_dir)?;
+        // Frames left by an interrupted recording of this session are kept
+        // and new ones numbered after them
+        let first_frame = next_frame_index(&output_dir);
+        if first_frame > 0 {
+            info!("Numbering frames from {} after an earlier recording's frames", first_frame);
+        }
+        let batch_size = self.config.frame_batch_size.max(1);
+        ensure_writable(&batch_dir(&output_dir, first_frame, batch_size))?;
+        let sidecar = self
+            .config
+            .organize_by_session
+            .then(|| self.config.session_dir(session_id).join(METADATA_FILE));
 
         let (stop_tx, stop_rx) = std::sync::mpsc::channel();
         let mut stop_tx_guard = self.stop_tx.write().await;
@@ -341,58 +1079,317 @@ impl Recorder {
         drop(stop_tx_guard);
 
         let is_recording = self.is_recording.clone();
+        let is_paused = self.is_paused.clone();
         let fps = self.config.fps;
         let output_dir_clone = output_dir.clone();
         let browser_tab = self.browser_tab.clone();
+        let frames_captured = self.frames_captured.clone();
+        let capture_slowdown = self.capture_slowdown.clone();
+        let metadata = self.metadata.clone();
+        let screenshot_format = self.config.screenshot_format;
+        let element = match self.config.mode {
+            RecordingMode::Element { ref selector } => Some(selector.clone()),
+            _ => None,
+        };
 
-        tokio::spawn(async move {
+        let capture = tokio::spawn(async move {
+            // Element frames keep the size of the first match so every
+            // frame has the same dimensions
+            let mut clip_size = None;
+            let (capture_format, capture_quality) = screenshot_format.capture_options();
             let frame_duration = tokio::time::Duration::from_millis(1000 / fps as u64);
             let mut frame_count = 0u64;
+            let mut dropped = 0u64;
+            let written = Arc::new(std::sync::Mutex::new(WrittenFrames::new(first_frame)));
+
+            // Disk writes happen on separate tasks so a slow disk doesn't
+            // lower the capture rate. The queue is bounded; when it is full
+            // the frame is dropped rather than buffered.
+            let (frame_tx, frame_rx) = mpsc::channel::<(u64, Vec<u8>)>(FRAME_QUEUE_CAPACITY);
+            let frame_rx = Arc::new(tokio::sync::Mutex::new(frame_rx));
+            let writers: Vec<_> = (0..FRAME_WRITERS)
+                .map(|_| {
+                    let frame_rx = frame_rx.clone();
+                    let output_dir = output_dir_clone.clone();
+                    let frames_captured = frames_captured.clone();
+                    let written = written.clone();
+                    tokio::spawn(async move {
+                        loop {
+                            let Some((index, data)) = frame_rx.lock().await.recv().await else {
+                                break;
+                            };
+                            let filepath = batch_dir(&output_dir, index, batch_size)
+                                .join(screenshot_format.frame_file_name(index));
+                            // A missing frame ends the sequence FFmpeg reads,
+                            // so a failed write is retried before giving up
+                            for attempt in 1..=FRAME_WRITE_ATTEMPTS {
+                                match tokio::fs::write(&filepath, &data).await {
+                                    Ok(()) => {
+                                        frames_captured.fetch_add(1, Ordering::Relaxed);
+                                        written.lock().unwrap().mark(index);
+                                        break;
+                                    }
+                                    Err(e) if attempt < FRAME_WRITE_ATTEMPTS => {
+                                        warn!("Failed to save screenshot {}, retrying: {}", index, e);
+                                        tokio::time::sleep(tokio::time::Duration::from_millis(100 * attempt as u64)).await;
+                                    }
+                                    Err(e) => warn!(
+                                        "Failed to save screenshot {}, frames after it won't be encoded: {}",
+                                        index, e
+                                    ),
+                                }
+                            }
+                        }
+                    })
+                })
+                .collect();
 
             loop {
                 if !is_recording.load(Ordering::SeqCst) {
                     break;
                 }
 
+                if is_paused.load(Ordering::SeqCst) {
+                    tokio::time::sleep(frame_duration).await;
+                    if stop_rx.try_recv().is_ok() {
+                        break;
+                    }
+                    continue;
+                }
+
                 let tab_guard = browser_tab.read().await;
-                if let Some(ref tab) = *tab_guard {
-                    match tab.capture_screenshot(headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption::Png, None, None, true) {
-                        Ok(screenshot_data) => {
-                            let filename = format!("frame_{:06}.png", frame_count);
-                            let filepath = output_dir_clone.join(filename);
-                            
-                            if let Err(e) = std::fs::write(&filepath, &screenshot_data) {
-                                warn!("Failed to save screenshot {}: {}", frame_count, e);
-                            } else {
+                let clip = match (tab_guard.as_ref(), element.as_deref()) {
+                    (Some(tab), Some(selector)) => match element_rect(tab, selector) {
+                        Ok(Some(rect)) => Some(Some(rect.clip(&mut clip_size))),
+                        // Not on this page (or mid-navigation); skip the frame
+                        Ok(None) => None,
+                        Err(e) => {
+                            warn!("Failed to locate element: {}", e);
+                            None
+                        }
+                    },
+                    _ => Some(None),
+                };
+                if let (Some(tab), Some(clip)) = (tab_guard.as_ref(), clip) {
+                    match tab.capture_screenshot(capture_format.clone(), capture_quality, clip, true) {
+                        // Numbers are only assigned to queued frames so the
+                        // sequence FFmpeg reads has no gaps.
+                        Ok(screenshot_data) => match frame_tx.try_send((first_frame + frame_count, screenshot_data)) {
+                            Ok(()) => {
                                 frame_count += 1;
-                                if frame_count % (fps as u64 * 10) == 0 {
+                                // The next batch's directory must exist before
+                                // its first frame reaches a writer
+                                let next = first_frame + frame_count;
+                                if next.is_multiple_of(batch_size) {
+                                    if let Err(e) = std::fs::create_dir_all(batch_dir(&output_dir_clone, next, batch_size)) {
+                                        warn!("Failed to create frame batch directory: {}", e);
+                                    }
+                                }
+                                if frame_count.is_multiple_of(fps as u64 * 10) {
                                     info!("Captured {} screenshots", frame_count);
+                                    if let Some(ref sidecar) = sidecar {
+                                        if let Some(mut checkpoint) = metadata.read().await.clone() {
+                                            checkpoint.last_contiguous_frame = written.lock().unwrap().last_contiguous();
+                                            write_sidecar(sidecar, &checkpoint);
+                                        }
+                                    }
                                 }
                             }
-                        }
+                            Err(mpsc::error::TrySendError::Full(_)) => {
+                                dropped += 1;
+                                if dropped == 1 || dropped.is_multiple_of(100) {
+                                    warn!("Disk can't keep up with capture, dropped {} frames so far", dropped);
+                                }
+                            }
+                            Err(mpsc::error::TrySendError::Closed(_)) => break,
+                        },
                         Err(e) => {
                             warn!("Failed to capture screenshot: {}", e);
                         }
                     }
-                } else {
+                } else if tab_guard.is_none() {
                     warn!("No browser tab set for recording");
                 }
                 drop(tab_guard);
 
-                tokio::time::sleep(frame_duration).await;
+                let slowdown = capture_slowdown.load(Ordering::Relaxed).max(1);
+                tokio::time::sleep(frame_duration * slowdown as u32).await;
 
                 if stop_rx.try_recv().is_ok() {
                     break;
                 }
             }
 
+            // Let the writers drain the queue before the frames are encoded.
+            drop(frame_tx);
+            for writer in writers {
+                let _ = writer.await;
+            }
+
+            if let Some(metadata) = metadata.write().await.as_mut() {
+                metadata.last_contiguous_frame = written.lock().unwrap().last_contiguous();
+            }
+            if dropped > 0 {
+                warn!("Dropped {} frames because the disk could not keep up", dropped);
+            }
             info!("Browser screenshot capture stopped. Captured {} frames", frame_count);
         });
+        *self.capture_task.write().await = Some(capture);
+
+        Ok(())
+    }
+
+    /// In [`RecordingMode::Element`], fail unless the selector matches an
+    /// element on the tab's current page. Other modes always pass.
+    pub async fn check_element(&self) -> Result<(), RecorderError> {
+        let RecordingMode::Element { ref selector } = self.config.mode else {
+            return Ok(());
+        };
+        let tab = self.browser_tab.read().await.clone().ok_or_else(|| {
+            RecorderError::RecordingError("Element recording needs a browser tab".to_string())
+        })?;
+        match element_rect(&tab, selector)? {
+            Some(_) => Ok(()),
+            None => Err(RecorderError::RecordingError(format!(
+                "No element matches selector {:?} on {}",
+                selector,
+                tab.get_url()
+            ))),
+        }
+    }
 
+    /// Save one screenshot of the browser tab to `path`, in the configured
+    /// screenshot format, without starting a recording.
+    pub async fn capture_single_frame(&self, path: &Path) -> Result<(), RecorderError> {
+        let tab = self.browser_tab.read().await.clone().ok_or_else(|| {
+            RecorderError::RecordingError("Capturing a frame needs a browser tab".to_string())
+        })?;
+        let (format, quality) = self.config.screenshot_format.capture_options();
+        let data = tab
+            .capture_screenshot(format, quality, None, true)
+            .map_err(|e| RecorderError::RecordingError(format!("Failed to capture screenshot: {}", e)))?;
+        std::fs::write(path, data)?;
+        info!("Saved screenshot to {:?}", path);
         Ok(())
     }
 
-    pub async fn stop_recording(&self) -> Result<PathBuf, RecorderError> {
+    /// Watch the recording's size on disk and raise
+    /// [`Recorder::size_limit_reached`] once it exceeds `limit_mb`.
+    async fn start_size_watchdog(&self, session_id: &str, video: Option<&Path>, limit_mb: u64) {
+        let video = video.map(Path::to_path_buf);
+        let frames_dir = matches!(self.config.mode, RecordingMode::Browser | RecordingMode::Both | RecordingMode::Element { .. })
+            .then(|| self.config.frames_dir(session_id));
+        let limit = limit_mb.saturating_mul(1024 * 1024);
+        let is_recording = self.is_recording.clone();
+        let reached = self.size_limit_reached.clone();
+
+        let watchdog = tokio::spawn(async move {
+            while is_recording.load(Ordering::SeqCst) {
+                tokio::time::sleep(SIZE_CHECK_INTERVAL).await;
+                let size = recording_size(video.as_deref(), frames_dir.as_deref());
+                if size >= limit {
+                    warn!(
+                        "Recording reached {:.1} MB, over the {} MB size limit; stopping",
+                        size as f64 / (1024.0 * 1024.0),
+                        limit_mb
+                    );
+                    reached.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+        });
+        *self.size_watchdog.write().await = Some(watchdog);
+    }
+
+    /// Sample this process's resident memory and stretch the browser frame
+    /// interval while it is over `limit_mb`, relaxing again once usage drops.
+    /// Screen recording runs in FFmpeg and keeps its frame rate.
+    async fn start_memory_watchdog(&self, limit_mb: u64) {
+        if !matches!(self.config.mode, RecordingMode::Browser | RecordingMode::Both | RecordingMode::Element { .. }) {
+            return;
+        }
+        if process_rss_bytes().is_none() {
+            warn!("Can't read this process's memory use on this platform; --memory-limit-mb has no effect");
+            return;
+        }
+        let limit = limit_mb.saturating_mul(1024 * 1024);
+        let is_recording = self.is_recording.clone();
+        let slowdown = self.capture_slowdown.clone();
+        let metadata = self.metadata.clone();
+        let ffmpeg_process = self.ffmpeg_process.clone();
+        let browser_pid = self.browser_pid.clone();
+        let fps = self.config.fps.max(1);
+
+        let watchdog = tokio::spawn(async move {
+            let mut warned = false;
+            while is_recording.load(Ordering::SeqCst) {
+                tokio::time::sleep(MEMORY_CHECK_INTERVAL).await;
+                let ffmpeg_pid = ffmpeg_process.read().await.as_ref().map(Child::id);
+                let Some(rss) = recording_rss_bytes(ffmpeg_pid, *browser_pid.read().await) else {
+                    continue;
+                };
+                let current = slowdown.load(Ordering::Relaxed);
+                let next = next_capture_slowdown(current, rss, limit);
+                if next == current {
+                    continue;
+                }
+                slowdown.store(next, Ordering::Relaxed);
+                let rss_mb = rss as f64 / (1024.0 * 1024.0);
+                if next > current {
+                    warn!(
+                        "Memory use is {:.0} MB, over the {} MB limit; capturing browser frames at {:.1} fps",
+                        rss_mb,
+                        limit_mb,
+                        fps as f64 / next as f64
+                    );
+                    if !warned {
+                        warned = true;
+                        let warning = format!(
+                            "Browser frames were captured below {} fps while memory use was over the {} MB limit",
+                            fps, limit_mb
+                        );
+                        if let Some(metadata) = metadata.write().await.as_mut() {
+                            metadata.capture_warnings.push(warning);
+                        }
+                    }
+                } else {
+                    info!(
+                        "Memory use is down to {:.0} MB; capturing browser frames at {:.1} fps",
+                        rss_mb,
+                        fps as f64 / next as f64
+                    );
+                }
+            }
+        });
+        *self.memory_watchdog.write().await = Some(watchdog);
+    }
+
+    /// How many times longer than normal the browser frame interval
+    /// currently is because of [`RecordingConfig::memory_limit_mb`]; 1 when
+    /// capture runs at full rate.
+    pub fn capture_slowdown(&self) -> u64 {
+        self.capture_slowdown.load(Ordering::Relaxed).max(1)
+    }
+
+    /// Whether the recording outgrew [`RecordingConfig::max_file_size_mb`].
+    /// Capture carries on until the caller stops the recording, so the
+    /// video is finalized the normal way.
+    pub fn size_limit_reached(&self) -> bool {
+        self.size_limit_reached.load(Ordering::SeqCst)
+    }
+
+    /// Stop recording after the first `pages` pages while the crawl carries
+    /// on, noting the boundary in [`RecordingMetadata::recorded_pages`].
+    pub async fn stop_recording_after_pages(&self, pages: usize) -> Result<Option<PathBuf>, RecorderError> {
+        if let Some(metadata) = self.metadata.write().await.as_mut() {
+            metadata.recorded_pages = Some(pages);
+        }
+        self.stop_recording().await
+    }
+
+    /// Stop capturing and finalize the video. Returns the video path, or
+    /// `None` in [`RecordingMode::None`].
+    pub async fn stop_recording(&self) -> Result<Option<PathBuf>, RecorderError> {
         if !self.is_recording.load(Ordering::SeqCst) {
             return Err(RecorderError::StopFailed("Not currently recording".to_string()));
         }
@@ -403,19 +1400,30 @@ impl Recorder {
         let meta = self.metadata.read().await;
         if let Some(metadata) = meta.as_ref() {
             let duration = (Utc::now() - metadata.start_time).num_seconds();
-            if duration < 2 && matches!(self.config.mode, RecordingMode::Screen | RecordingMode::Both) {
+            if duration < 2 && self.config.mode.records_screen() {
                 warn!("Recording duration is very short ({}s), video may not be properly encoded", duration);
             }
         }
         drop(meta);
+
+        // FFmpeg must be running again to receive the quit command.
+        if self.is_paused() {
+            self.resume_recording().await?;
+        }
         
         self.is_recording.store(false, Ordering::SeqCst);
+        if let Some(watchdog) = self.size_watchdog.write().await.take() {
+            watchdog.abort();
+        }
+        if let Some(watchdog) = self.memory_watchdog.write().await.take() {
+            watchdog.abort();
+        }
 
         match self.config.mode {
             RecordingMode::Screen => {
                 self.stop_screen_recording().await?;
             }
-            RecordingMode::Browser => {
+            RecordingMode::Browser | RecordingMode::Element { .. } => {
                 self.stop_browser_recording().await?;
             }
             RecordingMode::Both => {
@@ -424,6 +1432,15 @@ impl Recorder {
                 self.stop_browser_recording().await?;
                 info!("Stopped both screen recording and browser screenshot capture");
             }
+            RecordingMode::None => {}
+        }
+
+        if self.config.mode.records_screen() {
+            let video = self.metadata.read().await.as_ref().and_then(|m| m.file_path.clone());
+            if let Some(video) = video {
+                self.check_video(&video).await;
+                self.add_intro_card(&video).await;
+            }
         }
 
         let mut meta = self.metadata.write().await;
@@ -436,8 +1453,18 @@ impl Recorder {
 
             info!("Recording stopped. Duration: {} seconds", duration);
             
-            let output_path = metadata.file_path.clone()
-                .ok_or_else(|| RecorderError::StopFailed("No output path found".to_string()))?;
+            if self.config.organize_by_session {
+                self.write_metadata_sidecar(metadata);
+            }
+
+            let output_path = metadata.file_path.clone();
+            if let Some(ref file_path) = output_path {
+                self.emit(RecorderEvent::Stopped {
+                    session_id: metadata.session_id.clone(),
+                    file_path: file_path.clone(),
+                    duration_secs: duration,
+                });
+            }
             
             Ok(output_path)
         } else {
@@ -471,17 +1498,17 @@ impl Recorder {
                 drop(child.stdin.take());
             }
 
-            // Give FFmpeg time to finalize the video
-            info!("Waiting for FFmpeg to finalize video...");
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+            // Give FFmpeg up to the configured timeout to finalize the video
+            let timeout = std::time::Duration::from_secs(self.config.finalize_timeout_secs);
+            info!("Waiting up to {:?} for FFmpeg to finalize video...", timeout);
+            let started = std::time::Instant::now();
 
-            // Check if process exited
-            match child.try_wait() {
+            match wait_with_timeout(&mut child, timeout).await {
                 Ok(Some(status)) => {
-                    info!("FFmpeg exited with status: {}", status);
+                    info!("FFmpeg exited with status {} after {:.1}s", status, started.elapsed().as_secs_f64());
                 }
                 Ok(None) => {
-                    warn!("FFmpeg still running, sending SIGTERM...");
+                    warn!("FFmpeg still running after {:?}, killing it...", timeout);
                     // Force kill if still running
                     if let Err(e) = child.kill() {
                         error!("Failed to kill FFmpeg: {}", e);
@@ -516,12 +1543,17 @@ impl Recorder {
         }
         drop(stop_tx_guard);
 
-        // Give capture thread time to finish
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        // Wait for capture to stop and queued frames to be written
+        let capture = self.capture_task.write().await.take();
+        if let Some(capture) = capture {
+            if let Err(e) = capture.await {
+                warn!("Screenshot capture task failed: {}", e);
+            }
+        }
 
-        let meta = self.metadata.read().await;
+        let meta = self.metadata.read().await.clone();
         if let Some(metadata) = meta.as_ref() {
-            let frames_dir = self.config.output_dir.join(&metadata.session_id);
+            let frames_dir = self.config.frames_dir(&metadata.session_id);
             
             // Create a SEPARATE video path for browser screenshots (don't overwrite screen recording!)
             let video_name = if let Some(ref url_str) = metadata.url {
@@ -530,7 +1562,7 @@ impl Recorder {
                 metadata.session_id.clone()
             };
             
-            let screenshot_video_path = self.config.output_dir.join(format!(
+            let screenshot_video_path = self.config.session_dir(&metadata.session_id).join(format!(
                 "{}_screenshots_{}.{}",
                 video_name,
                 chrono::Utc::now().format("%Y%m%d_%H%M%S"),
@@ -538,9 +1570,11 @@ impl Recorder {
             ));
 
             info!("Converting frames to video: {:?}", screenshot_video_path);
-            match convert_frames_to_video(&frames_dir, &screenshot_video_path, self.config.fps) {
+            match encode_frames(&frames_dir, &screenshot_video_path, self.config.fps, self.config.frame_size) {
                 Ok(_) => {
                     info!("Screenshot video created successfully: {:?}", screenshot_video_path);
+                    self.check_video(&screenshot_video_path).await;
+                    self.add_intro_card(&screenshot_video_path).await;
                 }
                 Err(e) => {
                     warn!("Failed to create screenshot video: {}. Frames available at: {:?}", e, frames_dir);
@@ -551,8 +1585,72 @@ impl Recorder {
         Ok(())
     }
 
-    pub fn is_recording(&self) -> bool {
-        self.is_recording.load(Ordering::SeqCst)
+    /// Look for a black or frozen capture in `video` and note it in the
+    /// metadata. Analysis failures are logged and otherwise ignored.
+    async fn check_video(&self, video: &Path) {
+        if self.config.verify_output {
+            if let Err(e) = verify_playable(video) {
+                let warning = format!("{} is not playable: {}", video.display(), e);
+                error!("{}", warning);
+                if let Some(metadata) = self.metadata.write().await.as_mut() {
+                    metadata.capture_warnings.push(warning);
+                }
+                return;
+            }
+        }
+        match check_capture(video) {
+            Ok(None) => {}
+            Ok(Some(warning)) => {
+                warn!("{}", warning);
+                if let Some(metadata) = self.metadata.write().await.as_mut() {
+                    metadata.capture_warnings.push(warning);
+                }
+            }
+            Err(e) => warn!("Failed to check {:?} for a black or frozen capture: {}", video, e),
+        }
+    }
+
+    /// Prepend the configured intro card to `video`. Failures are logged
+    /// and leave the video as it was.
+    async fn add_intro_card(&self, video: &Path) {
+        let Some(ref card) = self.config.intro_card else {
+            return;
+        };
+        let Some(metadata) = self.metadata.read().await.clone() else {
+            return;
+        };
+        // Only screen recordings capture audio
+        let audio = self.config.audio_enabled && Some(video) == metadata.file_path.as_deref();
+        info!("Adding {}s intro card to {:?}", card.duration_secs, video);
+        if let Err(e) = prepend_intro_card(video, card, &metadata, self.config.fps, audio) {
+            warn!("Failed to add intro card to {:?}: {}", video, e);
+        }
+    }
+
+    fn write_metadata_sidecar(&self, metadata: &RecordingMetadata) {
+        write_sidecar(&self.config.session_dir(&metadata.session_id).join(METADATA_FILE), metadata);
+    }
+
+    /// Check that FFmpeg is installed and return its version string.
+    pub fn check_ffmpeg() -> Result<String, RecorderError> {
+        let output = match Command::new("ffmpeg").arg("-version").output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(RecorderError::FfmpegNotFound),
+            Err(e) => return Err(RecorderError::IoError(e)),
+        };
+        if !output.status.success() {
+            return Err(RecorderError::FfmpegNotFound);
+        }
+        parse_ffmpeg_version(&String::from_utf8_lossy(&output.stdout)).ok_or(RecorderError::FfmpegNotFound)
+    }
+
+    /// Browser frames saved by this recorder across all recordings.
+    pub fn frames_captured(&self) -> u64 {
+        self.frames_captured.load(Ordering::Relaxed)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.is_recording.load(Ordering::SeqCst)
     }
 
     pub async fn get_metadata(&self) -> Option<RecordingMetadata> {
@@ -575,12 +1673,29 @@ impl Recorder {
         }
     }
 
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
+    }
+
+    /// Suspend capture until [`Recorder::resume_recording`]. Browser frames
+    /// are skipped, so the pause is cut from a browser video. The FFmpeg
+    /// screen capture process is stopped, but FFmpeg timestamps screen
+    /// frames by the wall clock: a screen video still spans the pause,
+    /// holding the last frame captured before it.
     pub async fn pause_recording(&self) -> Result<(), RecorderError> {
         if !self.is_recording() {
             return Err(RecorderError::RecordingError("Not currently recording".to_string()));
         }
-        
+        if self.is_paused.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        self.signal_ffmpeg(true).await;
+        *self.paused_at.write().await = Some(Utc::now());
+
+        let session_id = self.session_id().await;
         info!("Recording paused");
+        self.emit(RecorderEvent::Paused { session_id });
         Ok(())
     }
 
@@ -588,10 +1703,79 @@ impl Recorder {
         if !self.is_recording() {
             return Err(RecorderError::RecordingError("Not currently recording".to_string()));
         }
-        
-        info!("Recording resumed");
+        if !self.is_paused.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        self.signal_ffmpeg(false).await;
+
+        let paused_ms = match self.paused_at.write().await.take() {
+            Some(paused_at) => (Utc::now() - paused_at).num_milliseconds().max(0) as u64,
+            None => 0,
+        };
+        let paused_secs = paused_ms / 1000;
+        // Summed in milliseconds so many short pauses don't lose time to rounding
+        let total_ms = self.paused_ms.fetch_add(paused_ms, Ordering::SeqCst) + paused_ms;
+        let mut meta = self.metadata.write().await;
+        let session_id = match meta.as_mut() {
+            Some(metadata) => {
+                metadata.paused_duration_secs = total_ms / 1000;
+                metadata.session_id.clone()
+            }
+            None => String::new(),
+        };
+        drop(meta);
+
+        info!("Recording resumed after {} seconds", paused_secs);
+        self.emit(RecorderEvent::Resumed { session_id, paused_secs });
         Ok(())
     }
+
+    /// How far into the video the capture has got: the time since recording
+    /// started, minus the time spent paused unless the screen is captured,
+    /// since a screen video still spans its pauses (see
+    /// [`Recorder::pause_recording`]). `None` when not recording.
+    pub async fn video_position(&self) -> Option<std::time::Duration> {
+        if !self.is_recording() {
+            return None;
+        }
+        let start_time = self.metadata.read().await.as_ref()?.start_time;
+        let now = Utc::now();
+        let elapsed = (now - start_time).to_std().unwrap_or_default();
+        if self.config.mode.records_screen() {
+            return Some(elapsed);
+        }
+        let mut paused = std::time::Duration::from_millis(self.paused_ms.load(Ordering::SeqCst));
+        if let Some(paused_at) = *self.paused_at.read().await {
+            paused += (now - paused_at).to_std().unwrap_or_default();
+        }
+        Some(elapsed.saturating_sub(paused))
+    }
+
+    async fn session_id(&self) -> String {
+        let meta = self.metadata.read().await;
+        meta.as_ref().map(|m| m.session_id.clone()).unwrap_or_default()
+    }
+
+    /// Stop (`pause == true`) or continue the FFmpeg capture process.
+    #[cfg(unix)]
+    async fn signal_ffmpeg(&self, pause: bool) {
+        let ffmpeg_guard = self.ffmpeg_process.read().await;
+        if let Some(ref child) = *ffmpeg_guard {
+            let signal = if pause { libc::SIGSTOP } else { libc::SIGCONT };
+            // SAFETY: kill(2) on the PID of a child process we own.
+            if unsafe { libc::kill(child.id() as libc::pid_t, signal) } != 0 {
+                warn!("Failed to signal FFmpeg: {}", std::io::Error::last_os_error());
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn signal_ffmpeg(&self, _pause: bool) {
+        if self.ffmpeg_process.read().await.is_some() {
+            warn!("Pausing screen capture is not supported on this platform; only browser frames are paused");
+        }
+    }
 }
 
 impl Default for Recorder {
@@ -617,28 +1801,187 @@ fn extract_domain_name(url_str: &str) -> String {
     format!("recording_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
 }
 
-// Convert frames to video using FFmpeg
-fn convert_frames_to_video(frames_dir: &PathBuf, output_path: &PathBuf, fps: u32) -> Result<(), RecorderError> {
-    // Check if ffmpeg is available
-    let ffmpeg_check = Command::new("ffmpeg")
-        .arg("-version")
-        .output();
+/// Poll `child` until it exits or `timeout` passes. `Ok(None)` means it is
+/// still running.
+async fn wait_with_timeout(
+    child: &mut Child,
+    timeout: std::time::Duration,
+) -> std::io::Result<Option<std::process::ExitStatus>> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Ok(None);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+}
 
-    if ffmpeg_check.is_err() {
-        return Err(RecorderError::EncodingError(
-            "FFmpeg not found. Please install FFmpeg to generate videos. Frames are saved and can be converted manually.".to_string()
-        ));
+/// Extract the version from the first line of `ffmpeg -version` output,
+/// e.g. `ffmpeg version 6.1.1-3ubuntu5 Copyright ...` -> `6.1.1-3ubuntu5`.
+fn parse_ffmpeg_version(output: &str) -> Option<String> {
+    let line = output.lines().next()?;
+    let rest = line.trim().strip_prefix("ffmpeg version ")?;
+    rest.split_whitespace().next().map(|v| v.to_string())
+}
+
+fn write_sidecar(path: &Path, metadata: &RecordingMetadata) {
+    let result = serde_json::to_string_pretty(metadata)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to write recording metadata to {:?}: {}", path, e);
     }
+}
+
+/// Index and extension of each `frame_NNNNNN.<ext>` file in `dir`, in
+/// index order.
+fn frame_files(dir: &Path) -> Vec<(u64, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut frames: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (index, extension) = name.strip_prefix("frame_")?.split_once('.')?;
+            Some((index.parse().ok()?, extension.to_string()))
+        })
+        .collect();
+    frames.sort();
+    frames
+}
+
+/// Subdirectory of `frames_dir` that holds frame `index` when frames are
+/// batched `batch_size` to a directory.
+fn batch_dir(frames_dir: &Path, index: u64, batch_size: u64) -> PathBuf {
+    frames_dir.join(format!("batch_{:04}", index / batch_size))
+}
+
+/// Directories holding frames, in frame order: `frames_dir` itself when
+/// frames were written there before batching, then its `batch_NNNN`
+/// subdirectories. Directories without frames are left out.
+fn frame_dirs(frames_dir: &Path) -> Vec<PathBuf> {
+    let mut batches: Vec<PathBuf> = std::fs::read_dir(frames_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with("batch_")))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    batches.sort();
+    batches.insert(0, frames_dir.to_path_buf());
+    batches.retain(|dir| !frame_files(dir).is_empty());
+    batches
+}
+
+/// Index the next frame written under `frames_dir` gets, after any frames
+/// already there.
+fn next_frame_index(frames_dir: &Path) -> u64 {
+    frame_dirs(frames_dir)
+        .last()
+        .and_then(|dir| frame_files(dir).last().map(|&(index, _)| index + 1))
+        .unwrap_or(0)
+}
+
+/// Encode the browser frames in `frames_dir` into `output`, e.g. frames
+/// an interrupted recording never converted. Each batch directory is
+/// encoded into a segment and the segments joined. Returns the number of
+/// frames found; a batch's encoding stops at the first gap in its
+/// numbering. Frames are scaled to `frame_size` if given.
+pub fn encode_frames(
+    frames_dir: &Path,
+    output: &Path,
+    fps: u32,
+    frame_size: Option<(u32, u32)>,
+) -> Result<usize, RecorderError> {
+    let batches: Vec<_> = frame_dirs(frames_dir)
+        .into_iter()
+        .filter_map(|dir| {
+            let frames = frame_files(&dir);
+            let (first, ref extension) = *frames.first()?;
+            let format = ScreenshotFormat::from_extension(extension)?;
+            Some((dir, format, first, frames.len()))
+        })
+        .collect();
+    if batches.is_empty() {
+        return Err(RecorderError::EncodingError(format!("No frames found in {:?}", frames_dir)));
+    }
+    let total = batches.iter().map(|&(_, _, _, count)| count).sum();
+    info!("Encoding {} frames from {:?} into {:?}", total, frames_dir, output);
+
+    if let [(ref dir, format, first, _)] = batches[..] {
+        convert_frames_to_video(dir, format, first, output, fps, frame_size)?;
+        return Ok(total);
+    }
+    let mut segments = Vec::new();
+    for (n, (dir, format, first, _)) in batches.into_iter().enumerate() {
+        let segment = format!("segment_{:04}.mp4", n);
+        info!("Encoding batch {:?} into {}", dir, segment);
+        convert_frames_to_video(&dir, format, first, &frames_dir.join(&segment), fps, frame_size)?;
+        segments.push(segment);
+    }
+    concat_segments(frames_dir, &segments, output)?;
+    Ok(total)
+}
+
+/// Join the `segments` in `dir` into `output` without re-encoding, then
+/// delete them.
+fn concat_segments(dir: &Path, segments: &[String], output: &Path) -> Result<(), RecorderError> {
+    // Concat demuxer paths are relative to the list file
+    let list = dir.join("segments.txt");
+    let entries: String = segments.iter().map(|segment| format!("file '{}'\n", segment)).collect();
+    std::fs::write(&list, entries)?;
+    let result = Command::new("ffmpeg")
+        .args(["-f", "concat", "-safe", "0", "-i"])
+        .arg(&list)
+        .args(["-c", "copy", "-y"])
+        .arg(output)
+        .output()
+        .map_err(|e| RecorderError::EncodingError(format!("Failed to run FFmpeg: {}", e)))?;
+    if !result.status.success() {
+        error!("FFmpeg stderr: {}", String::from_utf8_lossy(&result.stderr));
+        return Err(RecorderError::EncodingError(format!(
+            "FFmpeg failed to join {} segments with exit code: {}",
+            segments.len(),
+            result.status
+        )));
+    }
+    let _ = std::fs::remove_file(&list);
+    for segment in segments {
+        let _ = std::fs::remove_file(dir.join(segment));
+    }
+    info!("Joined {} segments into {:?}", segments.len(), output);
+    Ok(())
+}
+
+// Convert frames to video using FFmpeg
+fn convert_frames_to_video(
+    frames_dir: &Path,
+    format: ScreenshotFormat,
+    first_frame: u64,
+    output_path: &Path,
+    fps: u32,
+    frame_size: Option<(u32, u32)>,
+) -> Result<(), RecorderError> {
+    Recorder::check_ffmpeg()?;
 
     info!("Running FFmpeg to create video...");
     
     // Build ffmpeg command
-    let frame_pattern = frames_dir.join("frame_%06d.png");
+    let frame_pattern = frames_dir.join(format.frame_pattern());
     let output = Command::new("ffmpeg")
         .arg("-framerate")
         .arg(fps.to_string())
+        .arg("-start_number")
+        .arg(first_frame.to_string())
         .arg("-i")
         .arg(frame_pattern.to_str().unwrap())
+        .arg("-vf")
+        .arg(frame_scale_filter(frame_size))
         .arg("-c:v")
         .arg("libx264")
         .arg("-pix_fmt")
@@ -663,6 +2006,265 @@ fn convert_frames_to_video(frames_dir: &PathBuf, output_path: &PathBuf, fps: u32
     Ok(())
 }
 
+/// FFmpeg filter sizing frames for H.264 in yuv420p, which needs even
+/// dimensions: scaled to `frame_size` when given, otherwise kept at their
+/// own size, either way rounded down to even numbers. A fractional device
+/// pixel ratio easily gives odd sizes.
+fn frame_scale_filter(frame_size: Option<(u32, u32)>) -> String {
+    match frame_size {
+        Some((width, height)) => format!("scale={}:{}", (width & !1).max(2), (height & !1).max(2)),
+        None => "scale=trunc(iw/2)*2:trunc(ih/2)*2".to_string(),
+    }
+}
+
+/// Share of a recording that may be black or frozen before it is reported
+/// as a bad capture.
+const BAD_CAPTURE_RATIO: f64 = 0.8;
+
+/// How much of a video is black or frozen, from FFmpeg's blackdetect and
+/// freezedetect output.
+#[derive(Debug, Default, PartialEq)]
+struct CaptureStats {
+    duration_secs: f64,
+    black_secs: f64,
+    frozen_secs: f64,
+}
+
+impl CaptureStats {
+    fn parse(stderr: &str) -> Self {
+        let mut stats = CaptureStats::default();
+        let mut freeze_start = None;
+        for line in stderr.lines() {
+            if let Some(duration) = line.trim().strip_prefix("Duration: ") {
+                if stats.duration_secs == 0.0 {
+                    stats.duration_secs = duration.split(',').next().and_then(parse_timestamp).unwrap_or(0.0);
+                }
+            } else if let Some(black) = field(line, "black_duration:") {
+                stats.black_secs += black;
+            } else if let Some(start) = field(line, "freeze_start:") {
+                freeze_start = Some(start);
+            } else if let Some(end) = field(line, "freeze_end:") {
+                stats.frozen_secs += end - freeze_start.take().unwrap_or(0.0);
+            }
+        }
+        // A freeze that lasts to the end of the video is never closed
+        if let Some(start) = freeze_start {
+            stats.frozen_secs += (stats.duration_secs - start).max(0.0);
+        }
+        stats
+    }
+
+    /// Warning for a capture that is mostly black or frozen.
+    fn warning(&self, video: &Path) -> Option<String> {
+        if self.duration_secs <= 0.0 {
+            return None;
+        }
+        if self.black_secs / self.duration_secs >= BAD_CAPTURE_RATIO {
+            Some(format!(
+                "Recording {:?} is black for {:.0}s of {:.0}s; check which display is captured",
+                video, self.black_secs, self.duration_secs
+            ))
+        } else if self.frozen_secs / self.duration_secs >= BAD_CAPTURE_RATIO {
+            Some(format!(
+                "Recording {:?} shows the same frame for {:.0}s of {:.0}s; the capture may be stuck",
+                video, self.frozen_secs, self.duration_secs
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Number after `name` in an FFmpeg log line.
+fn field(line: &str, name: &str) -> Option<f64> {
+    let rest = &line[line.find(name)? + name.len()..];
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Seconds in an FFmpeg `HH:MM:SS.ms` timestamp.
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut secs = 0.0;
+    for part in value.trim().split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(secs)
+}
+
+/// Check with `ffprobe` that `video` is non-empty and has a video stream
+/// with a positive duration, which it lacks when FFmpeg was stopped before
+/// it could encode anything. Returns the duration in seconds; the error
+/// includes what ffprobe reported.
+pub fn verify_playable(video: &Path) -> Result<f64, RecorderError> {
+    if std::fs::metadata(video)?.len() == 0 {
+        return Err(RecorderError::EncodingError("the file is empty".to_string()));
+    }
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=codec_type:format=duration", "-of", "json"])
+        .arg(video)
+        .output()
+        .map_err(|e| RecorderError::EncodingError(format!("Failed to run ffprobe: {}", e)))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(RecorderError::EncodingError(format!("ffprobe failed: {}", stderr.trim())));
+    }
+    probe_duration(&String::from_utf8_lossy(&output.stdout)).map_err(|e| {
+        if stderr.trim().is_empty() {
+            RecorderError::EncodingError(e)
+        } else {
+            RecorderError::EncodingError(format!("{} (ffprobe: {})", e, stderr.trim()))
+        }
+    })
+}
+
+/// Duration of the video in ffprobe's JSON output, or why it isn't
+/// playable.
+fn probe_duration(json: &str) -> Result<f64, String> {
+    #[derive(Deserialize)]
+    struct Probe {
+        #[serde(default)]
+        streams: Vec<Stream>,
+        format: Option<ProbeFormat>,
+    }
+    #[derive(Deserialize)]
+    struct Stream {
+        codec_type: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct ProbeFormat {
+        duration: Option<String>,
+    }
+
+    let probe: Probe = serde_json::from_str(json).map_err(|e| format!("unreadable ffprobe output: {}", e))?;
+    if !probe.streams.iter().any(|stream| stream.codec_type.as_deref() == Some("video")) {
+        return Err("no video stream".to_string());
+    }
+    match probe.format.and_then(|format| format.duration?.parse::<f64>().ok()) {
+        Some(duration) if duration > 0.0 => Ok(duration),
+        _ => Err("no duration".to_string()),
+    }
+}
+
+/// Analyze `video` with FFmpeg and describe the problem if it is mostly
+/// black or mostly one unchanging frame, e.g. the wrong display or a page
+/// that never finished loading.
+pub fn check_capture(video: &Path) -> Result<Option<String>, RecorderError> {
+    Recorder::check_ffmpeg()?;
+
+    let output = Command::new("ffmpeg")
+        .arg("-i")
+        .arg(video)
+        .arg("-vf")
+        .arg("blackdetect=d=1:pix_th=0.10,freezedetect=n=0.001:d=2")
+        .arg("-an")
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .map_err(|e| RecorderError::EncodingError(format!("Failed to run FFmpeg: {}", e)))?;
+    if !output.status.success() {
+        return Err(RecorderError::EncodingError(format!(
+            "FFmpeg failed with exit code: {}",
+            output.status
+        )));
+    }
+
+    Ok(CaptureStats::parse(&String::from_utf8_lossy(&output.stderr)).warning(video))
+}
+
+/// Text drawn on the card for the recording described by `metadata`.
+fn intro_card_text(metadata: &RecordingMetadata) -> String {
+    let mut lines = Vec::new();
+    if let Some(ref url) = metadata.url {
+        lines.push(url.clone());
+    }
+    lines.push(metadata.start_time.format("%Y-%m-%d %H:%M UTC").to_string());
+    lines.push(format!("Session {}", metadata.session_id));
+    lines.join("\n")
+}
+
+/// FFmpeg filter graph that renders the card from `[1:v]` at the size of
+/// the recording in `[0:v]` and plays it before the recording. With
+/// `audio`, silence from `[2:a]` covers the card.
+fn intro_card_filter(audio: bool) -> String {
+    let card = "[1:v][0:v]scale2ref[card][main];\
+        [card]drawtext=textfile=intro_card.txt:expansion=none:fontcolor=white:fontsize=h/20:\
+        line_spacing=h/60:x=(w-text_w)/2:y=(h-text_h)/2,setsar=1[intro];\
+        [main]setsar=1[rec];";
+    if audio {
+        format!("{}[intro][2:a][rec][0:a]concat=n=2:v=1:a=1[v][a]", card)
+    } else {
+        format!("{}[intro][rec]concat=n=2:v=1:a=0[v]", card)
+    }
+}
+
+/// Re-encode `video` with an intro card in front, replacing the file.
+fn prepend_intro_card(
+    video: &Path,
+    card: &IntroCard,
+    metadata: &RecordingMetadata,
+    fps: u32,
+    audio: bool,
+) -> Result<(), RecorderError> {
+    Recorder::check_ffmpeg()?;
+
+    let dir = video.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = video
+        .file_name()
+        .ok_or_else(|| RecorderError::EncodingError(format!("Not a video file: {:?}", video)))?;
+    // drawtext reads the text from a file so URLs need no filter escaping
+    let text_file = dir.join("intro_card.txt");
+    std::fs::write(&text_file, intro_card_text(metadata))?;
+    let staged = dir.join(format!("intro_{}", file_name.to_string_lossy()));
+
+    let duration = card.duration_secs.to_string();
+    let mut cmd = Command::new("ffmpeg");
+    cmd.current_dir(dir)
+        .arg("-i")
+        .arg(video)
+        .arg("-f")
+        .arg("lavfi")
+        .arg("-t")
+        .arg(&duration)
+        .arg("-i")
+        .arg(format!("color=c=black:s=16x16:r={}", fps));
+    if audio {
+        cmd.arg("-f")
+            .arg("lavfi")
+            .arg("-t")
+            .arg(&duration)
+            .arg("-i")
+            .arg("anullsrc=r=48000:cl=stereo");
+    }
+    cmd.arg("-filter_complex").arg(intro_card_filter(audio)).arg("-map").arg("[v]");
+    if audio {
+        cmd.arg("-map").arg("[a]").arg("-c:a").arg("aac");
+    }
+    let output = cmd
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-y")
+        .arg(&staged)
+        .output()
+        .map_err(|e| RecorderError::EncodingError(format!("Failed to run FFmpeg: {}", e)));
+    let _ = std::fs::remove_file(&text_file);
+    let output = output?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&staged);
+        error!("FFmpeg stderr: {}", String::from_utf8_lossy(&output.stderr));
+        return Err(RecorderError::EncodingError(format!(
+            "FFmpeg failed with exit code: {}",
+            output.status
+        )));
+    }
+
+    std::fs::rename(&staged, video)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -682,7 +2284,7 @@ mod tests {
         recorder.start_recording("test-123".to_string(), Some("https://example.com".to_string())).await.unwrap();
         assert!(recorder.is_recording());
         
-        let file_path = recorder.stop_recording().await.unwrap();
+        let file_path = recorder.stop_recording().await.unwrap().unwrap();
         assert!(!recorder.is_recording());
         assert!(file_path.exists());
         
@@ -690,6 +2292,410 @@ mod tests {
         std::fs::remove_file(file_path).ok();
     }
 
+    #[tokio::test]
+    async fn test_failed_start_can_be_retried() {
+        let output_dir = std::env::temp_dir().join(format!("recorder-failed-start-{}", std::process::id()));
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::Element { selector: "#chart".to_string() },
+            ..RecordingConfig::default()
+        });
+
+        // No browser tab is set, so the element can't be recorded
+        for _ in 0..2 {
+            let err = recorder.start_recording("failed-1".to_string(), None).await.unwrap_err();
+            assert!(err.to_string().contains("needs a browser tab"), "{}", err);
+            assert!(!recorder.is_recording());
+            assert!(recorder.get_metadata().await.is_none());
+        }
+        std::fs::remove_dir_all(&output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_mode_none_records_metadata_only() {
+        let output_dir = std::env::temp_dir().join(format!("recorder-none-{}", std::process::id()));
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::None,
+            ..RecordingConfig::default()
+        });
+
+        recorder.start_recording("none-1".to_string(), Some("https://example.com".to_string())).await.unwrap();
+        assert!(recorder.is_recording());
+        assert_eq!(recorder.stop_recording().await.unwrap(), None);
+
+        let metadata = recorder.get_metadata().await.unwrap();
+        assert!(metadata.file_path.is_none());
+        assert!(metadata.duration_secs.is_some());
+        assert_eq!(recorder.frames_captured(), 0);
+        std::fs::remove_dir_all(output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_stop_after_pages_marks_boundary() {
+        let output_dir = std::env::temp_dir().join(format!("recorder-partial-{}", std::process::id()));
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::None,
+            ..RecordingConfig::default()
+        });
+
+        recorder.start_recording("partial-1".to_string(), None).await.unwrap();
+        recorder.stop_recording_after_pages(3).await.unwrap();
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.get_metadata().await.unwrap().recorded_pages, Some(3));
+        std::fs::remove_dir_all(output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pause_requires_active_recording() {
+        let recorder = Recorder::default();
+        assert!(recorder.pause_recording().await.is_err());
+        assert!(recorder.resume_recording().await.is_err());
+        assert!(!recorder.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_video_position_skips_pauses() {
+        let output_dir = std::env::temp_dir().join(format!("recorder-position-{}", std::process::id()));
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::None,
+            ..RecordingConfig::default()
+        });
+        assert_eq!(recorder.video_position().await, None);
+
+        let started = std::time::Instant::now();
+        recorder.start_recording("position-1".to_string(), None).await.unwrap();
+        recorder.pause_recording().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+        recorder.resume_recording().await.unwrap();
+
+        let position = recorder.video_position().await.unwrap();
+        assert!(position + std::time::Duration::from_millis(250) < started.elapsed());
+        recorder.stop_recording().await.unwrap();
+        std::fs::remove_dir_all(output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_video_position_in_paused_screen_recording() {
+        // Needs FFmpeg and an X display to record from
+        if Recorder::check_ffmpeg().is_err() || std::env::var_os("DISPLAY").is_none() {
+            return;
+        }
+        let output_dir = std::env::temp_dir().join(format!("recorder-screen-position-{}", std::process::id()));
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::Screen,
+            screen_width: Some(320),
+            screen_height: Some(240),
+            ..RecordingConfig::default()
+        });
+
+        recorder.start_recording("screen-position-1".to_string(), None).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        recorder.pause_recording().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        recorder.resume_recording().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+        // The pause is still in the video, so the offset must count it
+        let position = recorder.video_position().await.unwrap().as_secs_f64();
+        let video = recorder.stop_recording().await.unwrap().unwrap();
+        let duration = verify_playable(&video).unwrap();
+        assert!(position >= 3.5, "offset {} left the pause out", position);
+        assert!((duration - position).abs() < 1.5, "offset {} vs video length {}", position, duration);
+        std::fs::remove_dir_all(output_dir).ok();
+    }
+
+    #[test]
+    fn test_session_dirs() {
+        let mut config = RecordingConfig {
+            output_dir: PathBuf::from("/tmp/out"),
+            ..RecordingConfig::default()
+        };
+        assert_eq!(config.session_dir("s1"), PathBuf::from("/tmp/out"));
+        assert_eq!(config.frames_dir("s1"), PathBuf::from("/tmp/out/s1"));
+
+        config.organize_by_session = true;
+        assert_eq!(config.session_dir("s1"), PathBuf::from("/tmp/out/s1"));
+        assert_eq!(config.frames_dir("s1"), PathBuf::from("/tmp/out/s1/frames"));
+    }
+
+    #[tokio::test]
+    async fn test_unwritable_output_dir() {
+        let blocker = std::env::temp_dir().join(format!("site-recorder-blocker-{}", std::process::id()));
+        std::fs::write(&blocker, b"").unwrap();
+        let recorder = Recorder::new(RecordingConfig {
+            output_dir: blocker.join("out"),
+            mode: RecordingMode::None,
+            ..RecordingConfig::default()
+        });
+        let result = recorder.start_recording("s1".to_string(), None).await;
+        std::fs::remove_file(&blocker).unwrap();
+
+        match result {
+            Err(RecorderError::OutputNotWritable { dir, .. }) => assert_eq!(dir, blocker.join("out")),
+            other => panic!("expected OutputNotWritable, got {:?}", other),
+        }
+        assert!(!recorder.is_recording());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_starts() {
+        let output_dir = std::env::temp_dir().join(format!("recorder-double-start-{}", std::process::id()));
+        let recorder = Arc::new(Recorder::new(RecordingConfig {
+            output_dir: output_dir.clone(),
+            mode: RecordingMode::None,
+            ..RecordingConfig::default()
+        }));
+        let starts: Vec<_> = ["s1", "s2"]
+            .into_iter()
+            .map(|session| {
+                let recorder = recorder.clone();
+                tokio::spawn(async move { recorder.start_recording(session.to_string(), None).await })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for start in starts {
+            results.push(start.await.unwrap());
+        }
+        recorder.stop_recording().await.unwrap();
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .any(|result| matches!(result, Err(RecorderError::StartFailed(_)))));
+    }
+
+    #[test]
+    fn test_frame_files() {
+        let dir = std::env::temp_dir().join(format!("recorder-frames-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["frame_000010.jpg", "frame_000002.jpg", "metadata.json", "frame_x.jpg"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let frames = frame_files(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(frames, vec![(2, "jpg".to_string()), (10, "jpg".to_string())]);
+        assert!(frame_files(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_memory_guard() {
+        let status = "Name:\tsite-recorder\nVmPeak:\t  912340 kB\nVmRSS:\t  204800 kB\nThreads:\t12\n";
+        assert_eq!(parse_vm_rss(status), Some(200 * 1024 * 1024));
+        assert_eq!(parse_vm_rss("VmRSS: lots"), None);
+        assert_eq!(parse_stat_ppid("4242 (chrome) S 4100 4242 4100 0 -1"), Some(4100));
+        assert_eq!(parse_stat_ppid("4243 (Web Content (1)) R 4242 4242"), Some(4242));
+        let parents = [(10, 1), (11, 10), (12, 11), (13, 10), (20, 1)];
+        assert_eq!(process_tree(10, &parents), vec![10, 11, 13, 12]);
+        assert_eq!(process_tree(99, &parents), vec![99]);
+
+        let limit = 400;
+        assert_eq!(next_capture_slowdown(1, 450, limit), 2);
+        assert_eq!(next_capture_slowdown(8, 450, limit), MAX_CAPTURE_SLOWDOWN);
+        assert_eq!(next_capture_slowdown(4, 350, limit), 4);
+        assert_eq!(next_capture_slowdown(4, 200, limit), 2);
+        assert_eq!(next_capture_slowdown(1, 200, limit), 1);
+    }
+
+    #[test]
+    fn test_frame_scale_filter() {
+        assert_eq!(frame_scale_filter(Some((2560, 1440))), "scale=2560:1440");
+        assert_eq!(frame_scale_filter(Some((2049, 1151))), "scale=2048:1150");
+        assert_eq!(frame_scale_filter(None), "scale=trunc(iw/2)*2:trunc(ih/2)*2");
+    }
+
+    #[test]
+    fn test_frame_batches() {
+        let dir = std::env::temp_dir().join(format!("recorder-batches-{}", std::process::id()));
+        assert_eq!(batch_dir(&dir, 4999, 5000), dir.join("batch_0000"));
+        assert_eq!(batch_dir(&dir, 5000, 5000), dir.join("batch_0001"));
+
+        // Frames from before batching, then two batches of three and an
+        // empty batch created ahead of its first frame
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("frame_000000.png"), b"").unwrap();
+        for index in 1..6 {
+            let batch = batch_dir(&dir, index, 3);
+            std::fs::create_dir_all(&batch).unwrap();
+            std::fs::write(batch.join(format!("frame_{:06}.png", index)), b"").unwrap();
+        }
+        std::fs::create_dir_all(batch_dir(&dir, 6, 3)).unwrap();
+
+        let dirs = frame_dirs(&dir);
+        let next = next_frame_index(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(dirs, vec![dir.clone(), dir.join("batch_0000"), dir.join("batch_0001")]);
+        assert_eq!(next, 6);
+    }
+
+    #[test]
+    fn test_recording_size() {
+        let dir = std::env::temp_dir().join(format!("recorder-size-{}", std::process::id()));
+        let frames = dir.join("frames");
+        std::fs::create_dir_all(&frames).unwrap();
+        std::fs::write(dir.join("video.mp4"), vec![0u8; 1000]).unwrap();
+        std::fs::write(frames.join("frame_000000.png"), vec![0u8; 300]).unwrap();
+        std::fs::write(frames.join("frame_000001.png"), vec![0u8; 200]).unwrap();
+        std::fs::create_dir_all(frames.join("batch_0001")).unwrap();
+        std::fs::write(frames.join("batch_0001").join("frame_000002.png"), vec![0u8; 100]).unwrap();
+
+        assert_eq!(recording_size(Some(&dir.join("video.mp4")), Some(&frames)), 1600);
+        assert_eq!(recording_size(None, Some(&frames)), 600);
+        assert_eq!(recording_size(Some(&dir.join("missing.mp4")), None), 0);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_element_clip_keeps_first_size() {
+        let mut size = None;
+        let first = ElementRect { x: 10.0, y: 20.0, width: 301.5, height: 199.0 }.clip(&mut size);
+        assert_eq!((first.width, first.height), (302.0, 200.0));
+
+        let moved = ElementRect { x: 10.0, y: 400.0, width: 280.0, height: 210.0 }.clip(&mut size);
+        assert_eq!((moved.y, moved.width, moved.height), (400.0, 302.0, 200.0));
+    }
+
+    #[test]
+    fn test_probe_duration() {
+        let probe = probe_duration;
+        assert_eq!(
+            probe(r#"{"programs": [], "streams": [{"codec_type": "video"}], "format": {"duration": "12.400000"}}"#),
+            Ok(12.4)
+        );
+        assert_eq!(probe(r#"{"streams": [], "format": {"duration": "2.0"}}"#), Err("no video stream".to_string()));
+        assert_eq!(probe(r#"{"streams": [{"codec_type": "video"}], "format": {}}"#), Err("no duration".to_string()));
+        assert_eq!(
+            probe(r#"{"streams": [{"codec_type": "video"}], "format": {"duration": "0.000000"}}"#),
+            Err("no duration".to_string())
+        );
+        assert!(probe("").is_err());
+    }
+
+    #[test]
+    fn test_capture_stats() {
+        let stderr = "\
+Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'out.mp4':
+  Duration: 00:01:40.00, start: 0.000000, bitrate: 12 kb/s
+[blackdetect @ 0x1] black_start:0 black_end:45.5 black_duration:45.5
+[freezedetect @ 0x2] lavfi.freezedetect.freeze_start: 50
+[freezedetect @ 0x2] lavfi.freezedetect.freeze_duration: 10
+[freezedetect @ 0x2] lavfi.freezedetect.freeze_end: 60
+[freezedetect @ 0x2] lavfi.freezedetect.freeze_start: 70
+";
+        let stats = CaptureStats::parse(stderr);
+        assert_eq!(
+            stats,
+            CaptureStats {
+                duration_secs: 100.0,
+                black_secs: 45.5,
+                frozen_secs: 40.0,
+            }
+        );
+        assert_eq!(stats.warning(Path::new("out.mp4")), None);
+
+        let black = CaptureStats { duration_secs: 100.0, black_secs: 95.0, frozen_secs: 0.0 };
+        assert!(black.warning(Path::new("out.mp4")).unwrap().contains("black for 95s of 100s"));
+        let frozen = CaptureStats { duration_secs: 100.0, black_secs: 0.0, frozen_secs: 90.0 };
+        assert!(frozen.warning(Path::new("out.mp4")).unwrap().contains("same frame"));
+    }
+
+    #[test]
+    fn test_written_frames_last_contiguous() {
+        let mut written = WrittenFrames::new(10);
+        assert_eq!(written.last_contiguous(), None);
+        written.mark(11);
+        assert_eq!(written.last_contiguous(), None);
+        written.mark(10);
+        assert_eq!(written.last_contiguous(), Some(11));
+        // 12 never lands, so 13 and 14 don't count
+        written.mark(14);
+        written.mark(13);
+        assert_eq!(written.last_contiguous(), Some(11));
+        written.mark(12);
+        assert_eq!(written.last_contiguous(), Some(14));
+    }
+
+    #[test]
+    fn test_intro_card_text() {
+        let metadata = RecordingMetadata {
+            session_id: "abc123".to_string(),
+            url: Some("https://example.com/a?b=c".to_string()),
+            start_time: DateTime::parse_from_rfc3339("2024-05-01T09:30:00Z").unwrap().with_timezone(&Utc),
+            end_time: None,
+            duration_secs: None,
+            file_path: None,
+            format: VideoFormat::Mp4,
+            paused_duration_secs: 0,
+            recorded_pages: None,
+            capture_warnings: Vec::new(),
+            last_contiguous_frame: None,
+        };
+        assert_eq!(
+            intro_card_text(&metadata),
+            "https://example.com/a?b=c\n2024-05-01 09:30 UTC\nSession abc123"
+        );
+        assert!(intro_card_filter(false).ends_with("[intro][rec]concat=n=2:v=1:a=0[v]"));
+        assert!(intro_card_filter(true).contains("[intro][2:a][rec][0:a]concat=n=2:v=1:a=1"));
+    }
+
+    #[test]
+    fn test_recording_output_args() {
+        let file = RecordingOutput::File(PathBuf::from("/tmp/out.mp4"));
+        assert_eq!(file.ffmpeg_target(), "/tmp/out.mp4");
+        assert!(file.muxer_args(&VideoFormat::Mp4).is_empty());
+
+        assert_eq!(RecordingOutput::Stdout.ffmpeg_target(), "pipe:1");
+        assert_eq!(RecordingOutput::Stdout.muxer_args(&VideoFormat::Mp4)[..2], ["-f", "mp4"]);
+        let pipe = RecordingOutput::Pipe(PathBuf::from("/tmp/live.fifo"));
+        assert_eq!(pipe.muxer_args(&VideoFormat::Avi), ["-f", "matroska"]);
+    }
+
+    #[test]
+    fn test_screenshot_format_files() {
+        let jpeg = ScreenshotFormat::Jpeg { quality: 80 };
+        assert_eq!(jpeg.frame_file_name(7), "frame_000007.jpg");
+        assert_eq!(jpeg.frame_pattern(), "frame_%06d.jpg");
+        assert_eq!(ScreenshotFormat::default().frame_file_name(0), "frame_000000.png");
+        let webp = ScreenshotFormat::Webp { quality: 60 };
+        assert_eq!(webp.frame_pattern(), "frame_%06d.webp");
+        assert_eq!(webp.capture_options().1, Some(60));
+        assert_eq!(ScreenshotFormat::Jpeg { quality: 150 }.capture_options().1, Some(100));
+        assert_eq!(ScreenshotFormat::Png.capture_options().1, None);
+
+        // Settings saved before WebP had a quality still load
+        let old: ScreenshotFormat = serde_json::from_str(r#"{"type":"webp"}"#).unwrap();
+        assert_eq!(old.quality(), Some(DEFAULT_SCREENSHOT_QUALITY));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_wait_with_timeout() {
+        let mut quick = Command::new("true").spawn().unwrap();
+        let status = wait_with_timeout(&mut quick, std::time::Duration::from_secs(5)).await.unwrap();
+        assert!(status.is_some_and(|s| s.success()));
+
+        let mut slow = Command::new("sleep").arg("5").spawn().unwrap();
+        let started = std::time::Instant::now();
+        let status = wait_with_timeout(&mut slow, std::time::Duration::from_millis(300)).await.unwrap();
+        assert!(status.is_none());
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+        slow.kill().unwrap();
+        slow.wait().unwrap();
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        let output = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with gcc 13\n";
+        assert_eq!(parse_ffmpeg_version(output), Some("6.1.1-3ubuntu5".to_string()));
+        assert_eq!(parse_ffmpeg_version("command not found"), None);
+        assert_eq!(parse_ffmpeg_version(""), None);
+    }
+
     #[test]
     fn test_video_format_extension() {
         assert_eq!(VideoFormat::Mp4.extension(), "mp4");
@@ -697,4 +2703,25 @@ mod tests {
         assert_eq!(VideoFormat::Avi.extension(), "avi");
         assert_eq!(VideoFormat::Mkv.extension(), "mkv");
     }
+
+    #[test]
+    fn test_audio_args() {
+        let mut config = RecordingConfig {
+            format: VideoFormat::Webm,
+            ..RecordingConfig::default()
+        };
+        assert!(config.audio_args().unwrap().is_empty());
+
+        config.audio_enabled = true;
+        assert_eq!(config.audio_args().unwrap(), ["-c:a", "libopus"]);
+        config.audio_codec = AudioCodec::Aac;
+        assert!(matches!(config.audio_args(), Err(RecorderError::UnsupportedAudio(_))));
+
+        config.format = VideoFormat::Mp4;
+        config.audio_codec = AudioCodec::Auto;
+        config.audio_bitrate_kbps = Some(192);
+        assert_eq!(config.audio_args().unwrap(), ["-c:a", "aac", "-b:a", "192k"]);
+        config.audio_bitrate_kbps = Some(4);
+        assert!(config.audio_args().is_err());
+    }
 }
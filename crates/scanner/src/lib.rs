@@ -474,7 +474,7 @@ impl VulnerabilityScanner {
                 }
             }
         }
-        self.fetch_page_content(&test.to_string()).await.ok()
+        self.fetch_page_content(test.as_ref()).await.ok()
     }
 
     /// GET parameters present on a URL (empty if none).
@@ -853,7 +853,7 @@ impl VulnerabilityScanner {
                                     }
                                 }
                             }
-                            if let Ok(body) = self.fetch_page_content(&test.to_string()).await {
+                            if let Ok(body) = self.fetch_page_content(test.as_ref()).await {
                                 if body.contains(p) {
                                     reflected_count += 1;
                                     if reflected_samples.len() < 5 {
@@ -2135,23 +2135,23 @@ impl VulnerabilityScanner {
                     ];
 
                     let cookie_name = cookie.split('=').next().unwrap_or("").trim().to_lowercase();
-                    if sensitive_names.iter().any(|n| cookie_name.contains(n)) {
-                        if !has_secure || !has_httponly {
-                            findings.push(VulnerabilityFinding {
-                                title: format!("Sensitive Cookie '{}' Without Full Protection", cookie_name),
-                                severity: Severity::High,
-                                status: ScanStatus::Vulnerable,
-                                description: format!("Cookie '{}' contains sensitive data but is missing security flags.", cookie_name),
-                                details: vec![
-                                    format!("Has Secure: {}", has_secure),
-                                    format!("Has HttpOnly: {}", has_httponly),
-                                    format!("Has SameSite: {}", has_samesite),
-                                ],
-                                remediation: "Ensure sensitive cookies have Secure, HttpOnly, and SameSite=Strict flags.".to_string(),
-                                cwe_id: Some("CWE-614".to_string()),
-                                references: vec![],
-                            });
-                        }
+                    if sensitive_names.iter().any(|n| cookie_name.contains(n))
+                        && (!has_secure || !has_httponly)
+                    {
+                        findings.push(VulnerabilityFinding {
+                            title: format!("Sensitive Cookie '{}' Without Full Protection", cookie_name),
+                            severity: Severity::High,
+                            status: ScanStatus::Vulnerable,
+                            description: format!("Cookie '{}' contains sensitive data but is missing security flags.", cookie_name),
+                            details: vec![
+                                format!("Has Secure: {}", has_secure),
+                                format!("Has HttpOnly: {}", has_httponly),
+                                format!("Has SameSite: {}", has_samesite),
+                            ],
+                            remediation: "Ensure sensitive cookies have Secure, HttpOnly, and SameSite=Strict flags.".to_string(),
+                            cwe_id: Some("CWE-614".to_string()),
+                            references: vec![],
+                        });
                     }
                 }
             }
@@ -16,6 +16,10 @@ pub enum SessionError {
     StorageError(String),
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+    #[error("Invalid TOTP secret: {0}")]
+    InvalidTotpSecret(String),
+    #[error("Invalid credentials file: {0}")]
+    InvalidCredentialsFile(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +31,90 @@ pub struct LoginCredentials {
     pub password_field: String,
     pub submit_selector: Option<String>,
     pub login_script: Option<String>,
+    #[serde(default)]
+    pub post_login_wait: PostLoginWait,
+    /// Base32 TOTP secret for sites that ask for a one-time code after the password.
+    #[serde(default)]
+    pub totp_secret: Option<String>,
+    #[serde(default)]
+    pub totp_field_selector: Option<String>,
+}
+
+/// What to wait for after the login form is submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum PostLoginWait {
+    /// Sleep for a fixed number of milliseconds.
+    FixedMs(u64),
+    /// Wait until an element matching the CSS selector exists.
+    ForSelector(String),
+    /// Wait until the page URL differs from the login page URL.
+    ForUrlChange,
+}
+
+impl Default for PostLoginWait {
+    fn default() -> Self {
+        PostLoginWait::FixedMs(3000)
+    }
+}
+
+impl std::str::FromStr for PostLoginWait {
+    type Err = String;
+
+    /// Parse `<ms>`, `selector:<css>` or `url-change`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "url-change" {
+            Ok(PostLoginWait::ForUrlChange)
+        } else if let Some(selector) = s.strip_prefix("selector:") {
+            if selector.trim().is_empty() {
+                return Err("selector: requires a CSS selector".to_string());
+            }
+            Ok(PostLoginWait::ForSelector(selector.trim().to_string()))
+        } else {
+            s.parse::<u64>()
+                .map(PostLoginWait::FixedMs)
+                .map_err(|_| "Expected milliseconds, selector:<css> or url-change".to_string())
+        }
+    }
+}
+
+/// `LoginCredentials` as read from `--credentials-file`. Every field is
+/// optional so the file can hold only the secrets and leave the rest to flags.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CredentialsFile {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub login_url: Option<String>,
+    pub username_field: Option<String>,
+    pub password_field: Option<String>,
+    pub submit_selector: Option<String>,
+    pub login_script: Option<String>,
+    pub post_login_wait: Option<PostLoginWait>,
+    pub totp_secret: Option<String>,
+    pub totp_field_selector: Option<String>,
+}
+
+impl CredentialsFile {
+    /// Load a `.toml` file as TOML and anything else as JSON.
+    pub fn load(path: &std::path::Path) -> Result<Self, SessionError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SessionError::InvalidCredentialsFile(format!("{}: {}", path.display(), e)))?;
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        Self::parse(&content, is_toml)
+            .map_err(|e| SessionError::InvalidCredentialsFile(format!("{}: {}", path.display(), e)))
+    }
+
+    fn parse(content: &str, is_toml: bool) -> Result<Self, String> {
+        if is_toml {
+            toml::from_str(content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +123,23 @@ pub struct SessionData {
     pub cookies: Vec<SerializableCookie>,
     pub created_at: i64,
     pub expires_at: Option<i64>,
+    /// When set, each use of the session pushes `expires_at` this many
+    /// seconds into the future.
+    #[serde(default)]
+    pub sliding_ttl_secs: Option<i64>,
+}
+
+impl SessionData {
+    /// Slide the expiry forward if sliding expiry is on and the session
+    /// hasn't already expired.
+    fn slide_expiry(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        if let Some(ttl) = self.sliding_ttl_secs {
+            if self.expires_at.is_none_or(|expires_at| now <= expires_at) {
+                self.expires_at = Some(now + ttl);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +153,9 @@ pub struct SerializableCookie {
     pub expires: Option<i64>,
 }
 
+/// Idle time after which a session with sliding expiry lapses.
+pub const DEFAULT_SLIDING_EXPIRY_SECS: i64 = 30 * 60;
+
 pub struct SessionManager {
     session_data: Arc<RwLock<Option<SessionData>>>,
     #[allow(dead_code)]
@@ -68,6 +176,7 @@ impl SessionManager {
             cookies: Vec::new(),
             created_at: chrono::Utc::now().timestamp(),
             expires_at: None,
+            sliding_ttl_secs: None,
         };
 
         let mut data = self.session_data.write().await;
@@ -80,6 +189,7 @@ impl SessionManager {
         let mut data = self.session_data.write().await;
         if let Some(session) = data.as_mut() {
             session.cookies.push(cookie);
+            session.slide_expiry();
             debug!("Cookie added to session");
             Ok(())
         } else {
@@ -88,8 +198,9 @@ impl SessionManager {
     }
 
     pub async fn get_cookies(&self) -> Result<Vec<SerializableCookie>, SessionError> {
-        let data = self.session_data.read().await;
-        if let Some(session) = data.as_ref() {
+        let mut data = self.session_data.write().await;
+        if let Some(session) = data.as_mut() {
+            session.slide_expiry();
             Ok(session.cookies.clone())
         } else {
             Ok(Vec::new())
@@ -146,6 +257,32 @@ impl SessionManager {
         }
     }
 
+    /// Expire the session `ttl_secs` after it was last used rather than at
+    /// a fixed time. Adding or reading cookies and [`touch`](Self::touch)
+    /// count as use.
+    pub async fn set_sliding_expiry(&self, ttl_secs: i64) -> Result<(), SessionError> {
+        let mut data = self.session_data.write().await;
+        if let Some(session) = data.as_mut() {
+            session.sliding_ttl_secs = Some(ttl_secs);
+            session.expires_at = Some(chrono::Utc::now().timestamp() + ttl_secs);
+            Ok(())
+        } else {
+            Err(SessionError::SessionError("No active session".to_string()))
+        }
+    }
+
+    /// Mark the session as used, sliding its expiry forward. Does nothing
+    /// without sliding expiry or once the session has expired.
+    pub async fn touch(&self) -> Result<(), SessionError> {
+        let mut data = self.session_data.write().await;
+        if let Some(session) = data.as_mut() {
+            session.slide_expiry();
+            Ok(())
+        } else {
+            Err(SessionError::SessionError("No active session".to_string()))
+        }
+    }
+
     pub async fn is_expired(&self) -> bool {
         let data = self.session_data.read().await;
         if let Some(session) = data.as_ref() {
@@ -163,6 +300,40 @@ impl Default for SessionManager {
     }
 }
 
+/// Length of a TOTP time step in seconds (RFC 6238 default).
+pub const TOTP_STEP_SECS: u64 = 30;
+
+/// Generate the 6-digit RFC 6238 TOTP code (HMAC-SHA1) for `unix_time`.
+/// The secret is base32; spaces, padding and case are ignored.
+pub fn generate_totp(secret: &str, unix_time: u64) -> Result<String, SessionError> {
+    use hmac::{Hmac, Mac};
+
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let key = data_encoding::BASE32_NOPAD
+        .decode(normalized.as_bytes())
+        .map_err(|e| SessionError::InvalidTotpSecret(e.to_string()))?;
+
+    let counter = unix_time / TOTP_STEP_SECS;
+    let mut mac = Hmac::<sha1::Sha1>::new_from_slice(&key)
+        .map_err(|e| SessionError::InvalidTotpSecret(e.to_string()))?;
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    Ok(format!("{:06}", binary % 1_000_000))
+}
+
+/// Seconds until the TOTP code for `unix_time` expires.
+pub fn totp_seconds_remaining(unix_time: u64) -> u64 {
+    TOTP_STEP_SECS - unix_time % TOTP_STEP_SECS
+}
+
 pub fn create_cookie(name: &str, value: &str, domain: Option<&str>) -> SerializableCookie {
     SerializableCookie {
         name: name.to_string(),
@@ -179,6 +350,22 @@ pub fn create_cookie(name: &str, value: &str, domain: Option<&str>) -> Serializa
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_credentials_file() {
+        let json = r#"{"username": "alice", "password": "hunter2", "post_login_wait": {"type": "for_url_change"}}"#;
+        let creds = CredentialsFile::parse(json, false).unwrap();
+        assert_eq!(creds.username.as_deref(), Some("alice"));
+        assert_eq!(creds.post_login_wait, Some(PostLoginWait::ForUrlChange));
+        assert!(creds.login_url.is_none());
+
+        let toml = "password = \"hunter2\"\nusername_field = \"#email\"\n";
+        let creds = CredentialsFile::parse(toml, true).unwrap();
+        assert_eq!(creds.password.as_deref(), Some("hunter2"));
+        assert_eq!(creds.username_field.as_deref(), Some("#email"));
+
+        assert!(CredentialsFile::parse(r#"{"pasword": "typo"}"#, false).is_err());
+    }
+
     #[tokio::test]
     async fn test_session_creation() {
         let manager = SessionManager::new();
@@ -202,6 +389,26 @@ mod tests {
         assert_eq!(cookies[0].name, "session");
     }
 
+    #[test]
+    fn test_generate_totp_rfc6238_vectors() {
+        // RFC 6238 appendix B, SHA1 secret "12345678901234567890", truncated to 6 digits.
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        assert_eq!(generate_totp(secret, 59).unwrap(), "287082");
+        assert_eq!(generate_totp(secret, 1111111109).unwrap(), "081804");
+        assert_eq!(generate_totp(&secret.to_lowercase(), 1234567890).unwrap(), "005924");
+        assert!(generate_totp("not base32!", 0).is_err());
+        assert_eq!(totp_seconds_remaining(59), 1);
+    }
+
+    #[test]
+    fn test_post_login_wait_parsing() {
+        assert_eq!("1500".parse(), Ok(PostLoginWait::FixedMs(1500)));
+        assert_eq!("selector:#dashboard".parse(), Ok(PostLoginWait::ForSelector("#dashboard".to_string())));
+        assert_eq!("url-change".parse(), Ok(PostLoginWait::ForUrlChange));
+        assert!("selector:".parse::<PostLoginWait>().is_err());
+        assert!("soon".parse::<PostLoginWait>().is_err());
+    }
+
     #[tokio::test]
     async fn test_session_expiry() {
         let manager = SessionManager::new();
@@ -212,4 +419,22 @@ mod tests {
         
         assert!(manager.is_expired().await);
     }
+
+    #[tokio::test]
+    async fn test_sliding_expiry() {
+        let manager = SessionManager::new();
+        manager.create_session("test-sliding".to_string()).await.unwrap();
+        manager.set_sliding_expiry(60).await.unwrap();
+        assert!(!manager.is_expired().await);
+
+        // An idle session lapses; touching it afterwards doesn't revive it
+        manager.set_expiry(chrono::Utc::now().timestamp() - 1).await.unwrap();
+        manager.touch().await.unwrap();
+        assert!(manager.is_expired().await);
+
+        manager.set_expiry(chrono::Utc::now().timestamp() + 1).await.unwrap();
+        manager.touch().await.unwrap();
+        let expires_at = manager.session_data.read().await.as_ref().unwrap().expires_at.unwrap();
+        assert!(expires_at >= chrono::Utc::now().timestamp() + 59);
+    }
 }
@@ -54,6 +54,13 @@ async fn main() -> Result<()> {
             steps: 3,
             delay_ms: 500,
         },
+        wait_for_text: None,
+        pre_nav_script: None,
+        post_nav_script: None,
+        detect_rate_limits: true,
+        warm_up: false,
+        post_nav_settle_ms: 1000,
+        post_modal_settle_ms: 500,
     };
 
     let mut recording_data = Vec::new();
@@ -82,6 +89,7 @@ async fn main() -> Result<()> {
                     metadata: serde_json::json!({
                         "page_number": pages_visited,
                     }),
+                    success: true,
                 });
 
                 // Extract links
@@ -1,3 +1,4 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -30,6 +31,7 @@ pub struct CrawlArgs {
     pub fps: u32,
     pub audio: bool,
     pub headless: bool,
+    pub new_headless: bool,
     pub daemon: bool,
     pub progress: bool,
     pub log_file: Option<PathBuf>,
@@ -45,9 +47,90 @@ pub struct CrawlArgs {
     pub scan_url: Option<String>,
     pub login_script: Option<String>,
     pub concurrency: usize,
+    pub tui: bool,
+    pub organize_by_session: bool,
+    pub geo: Option<(f64, f64, f64)>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub color_scheme: Option<ColorSchemeArg>,
+    pub metrics_addr: Option<std::net::SocketAddr>,
+    pub save_har: bool,
+    pub post_login_wait: Option<session::PostLoginWait>,
+    pub totp_secret: Option<String>,
+    pub totp_field: Option<String>,
+    pub username_env: Option<String>,
+    pub password_env: Option<String>,
+    pub credentials_file: Option<PathBuf>,
+    pub max_runtime: Option<u64>,
+    pub discord_webhook: Option<String>,
+    pub email_config: Option<PathBuf>,
+    pub viewport: Option<(u32, u32)>,
+    pub finalize_timeout: Option<u64>,
+    pub retry: Option<u32>,
+    pub screenshot_format: Option<ScreenshotFormatArg>,
+    pub screenshot_quality: u8,
+    pub smooth_scroll: Option<u64>,
+    pub pause_at_sections: bool,
+    pub emit_sitemap: Option<PathBuf>,
+    pub dedup_content: bool,
+    pub allowed_domains: Vec<String>,
+    pub fetch_backend: Option<FetchBackendArg>,
+    pub discover_assets: bool,
+    pub download_dir: Option<PathBuf>,
+    pub unique_export: bool,
+    pub exclude_failures: bool,
+    pub browser_idle_timeout: Option<u64>,
+    pub tabs: usize,
+    pub wait_for_text: Option<String>,
+    pub capture_window: bool,
+    pub pre_nav_script: Option<String>,
+    pub post_nav_script: Option<String>,
+    pub max_urls_per_template: Option<usize>,
+    pub max_param_values: Option<usize>,
+    pub stream_to: Option<String>,
+    pub insecure: bool,
+    pub element: Option<String>,
+    pub max_recording_size: Option<u64>,
+    pub record_first_pages: Option<usize>,
+    pub profile: Option<PathBuf>,
+    pub respect_rate_limits: bool,
+    pub warm_up: bool,
+    pub intro_card: Option<u32>,
+    pub headers: Vec<(String, String)>,
+    pub legacy_json_export: bool,
+    pub delay_jitter: u64,
+    pub seed: Option<u64>,
+    pub contact_sheet: Option<(u32, u32)>,
+    pub window_position: Option<(i32, i32)>,
+    pub minimized: bool,
+    pub post_nav_settle_ms: u64,
+    pub post_modal_settle_ms: u64,
+    pub health_addr: Option<std::net::SocketAddr>,
+    pub stall_timeout: u64,
+    pub url_file: Option<PathBuf>,
+    pub no_discover: bool,
+    pub frame_batch_size: Option<u64>,
+    pub session_id: Option<String>,
+    pub session_prefix: Option<String>,
+    pub verify_video: bool,
+    pub respect_canonical: bool,
+    pub audio_codec: Option<AudioCodecArg>,
+    pub audio_bitrate: Option<u32>,
+    pub http_timeout: Option<u64>,
+    pub aux_retries: Option<u32>,
+    pub pause_during_delay: bool,
+    pub chrome_args: Vec<String>,
+    pub device_scale_factor: Option<f64>,
+    pub kiosk: bool,
+    pub json_progress: bool,
+    pub export_format: Vec<ExportFormatArg>,
+    pub memory_limit_mb: Option<u64>,
+    pub screenshots_per_page: Option<PathBuf>,
+    pub max_browser_relaunches: Option<u32>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     /// Start recording with GUI (default)
     Gui,
@@ -82,9 +165,9 @@ pub enum Commands {
         #[arg(short, long)]
         audio: bool,
 
-        /// Run browser in headless mode
-        #[arg(long)]
-        headless: bool,
+        /// Run browser in headless mode; --headless=new uses Chrome's new headless implementation
+        #[arg(long, value_name = "MODE", num_args = 0..=1, require_equals = true, default_missing_value = "legacy")]
+        headless: Option<HeadlessModeArg>,
 
         /// Run as a daemon (background process)
         #[arg(long)]
@@ -145,6 +228,338 @@ pub enum Commands {
         /// Number of concurrent crawl workers for parallel link discovery
         #[arg(short = 'j', long, default_value = "1")]
         concurrency: usize,
+
+        /// Show an interactive terminal dashboard instead of the progress bar
+        #[arg(long, conflicts_with = "daemon")]
+        tui: bool,
+
+        /// Store each session's videos, frames and metadata under OUTPUT/<session_id>/
+        #[arg(long)]
+        organize_by_session: bool,
+
+        /// Override geolocation as LAT,LON[,ACCURACY] (accuracy in meters, default 100)
+        #[arg(long, value_parser = parse_geo, allow_hyphen_values = true)]
+        geo: Option<(f64, f64, f64)>,
+
+        /// Override the browser timezone (IANA id, e.g. Europe/Berlin)
+        #[arg(long)]
+        timezone: Option<String>,
+
+        /// Override the browser locale (e.g. de-DE)
+        #[arg(long)]
+        locale: Option<String>,
+
+        /// Emulate prefers-color-scheme (dark or light)
+        #[arg(long)]
+        color_scheme: Option<ColorSchemeArg>,
+
+        /// Serve Prometheus metrics on this address (e.g. 127.0.0.1:9184)
+        #[arg(long)]
+        metrics_addr: Option<std::net::SocketAddr>,
+
+        /// Capture network traffic and save it as <session_id>.har in the session directory
+        #[arg(long)]
+        save_har: bool,
+
+        /// What to wait for after submitting the login form: milliseconds, selector:<css> or url-change
+        #[arg(long, value_name = "WAIT")]
+        post_login_wait: Option<session::PostLoginWait>,
+
+        /// Base32 TOTP secret for logins that require a one-time code after the password
+        #[arg(long, env = "SITE_RECORDER_TOTP_SECRET", hide_env_values = true)]
+        totp_secret: Option<String>,
+
+        /// CSS selector for the one-time code field (defaults to common OTP inputs)
+        #[arg(long)]
+        totp_field: Option<String>,
+
+        /// Read the username from this environment variable instead of --username
+        #[arg(long, value_name = "VAR", conflicts_with = "username")]
+        username_env: Option<String>,
+
+        /// Read the password from this environment variable instead of --password
+        #[arg(long, value_name = "VAR", conflicts_with = "password")]
+        password_env: Option<String>,
+
+        /// JSON or TOML file with login credentials (LoginCredentials fields)
+        #[arg(long, value_name = "PATH")]
+        credentials_file: Option<PathBuf>,
+
+        /// Stop crawling after this long (e.g. 1800, 90s, 30m, 2h); combined with --max-pages
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+        max_runtime: Option<u64>,
+
+        /// Discord webhook URL to post crawl and recording notifications to
+        #[arg(long, value_name = "URL", env = "SITE_RECORDER_DISCORD_WEBHOOK", hide_env_values = true)]
+        discord_webhook: Option<String>,
+
+        /// JSON or TOML file with SMTP settings for warning, error and summary emails
+        #[arg(long, value_name = "PATH")]
+        email_config: Option<PathBuf>,
+
+        /// Browser viewport as WIDTHxHEIGHT (e.g., 1280x800); independent of --screen-width/--screen-height
+        #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = parse_viewport)]
+        viewport: Option<(u32, u32)>,
+
+        /// Max time to let FFmpeg finalize the video before killing it (e.g. 30, 2m)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+        finalize_timeout: Option<u64>,
+
+        /// Retry failed page loads up to N times with exponential backoff and jitter
+        #[arg(long, value_name = "N")]
+        retry: Option<u32>,
+
+        /// Image format for browser-mode frames; jpeg is much smaller than png
+        #[arg(long, value_name = "FORMAT")]
+        screenshot_format: Option<ScreenshotFormatArg>,
+
+        /// Frame quality (1-100) for --screenshot-format jpeg or webp; ignored for png
+        #[arg(long, default_value = "85", value_parser = clap::value_parser!(u8).range(1..=100))]
+        screenshot_quality: u8,
+
+        /// Scroll each page smoothly over this many milliseconds instead of in jumps
+        #[arg(long, value_name = "MS")]
+        smooth_scroll: Option<u64>,
+
+        /// With --smooth-scroll, scroll one screen at a time and pause at each
+        #[arg(long, requires = "smooth_scroll")]
+        pause_at_sections: bool,
+
+        /// Write a sitemap.xml of the visited URLs here (split into an index past 50,000 URLs)
+        #[arg(long, value_name = "PATH")]
+        emit_sitemap: Option<PathBuf>,
+
+        /// Detect pages with the same visible content as an earlier page and skip their links
+        #[arg(long)]
+        dedup_content: bool,
+
+        /// Also follow links into this domain; repeatable. A leading "." includes subdomains (e.g. .example.org)
+        #[arg(long = "allow-domain", value_name = "DOMAIN")]
+        allowed_domains: Vec<String>,
+
+        /// How pages are loaded: browser (default) or http for fast link-only crawls with -m none
+        #[arg(long, value_enum)]
+        fetch_backend: Option<FetchBackendArg>,
+
+        /// Also collect image, stylesheet and CSS url() references from each page into the export
+        #[arg(long)]
+        discover_assets: bool,
+
+        /// Save files that crawled links download into this directory (downloads are refused otherwise)
+        #[arg(long, value_name = "DIR")]
+        download_dir: Option<PathBuf>,
+
+        /// Write only the first record for each URL and action to the data export
+        #[arg(long)]
+        unique_export: bool,
+
+        /// Leave failed page loads out of the data export
+        #[arg(long)]
+        exclude_failures: bool,
+
+        /// Shut the browser down after it has been idle this long (e.g. 600, 10m); defaults to 5 minutes or 4x --delay
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+        browser_idle_timeout: Option<u64>,
+
+        /// Load this many pages at once in separate tabs of one browser (recording mode none only)
+        #[arg(long, default_value = "1")]
+        tabs: usize,
+
+        /// Wait until each page's text contains this before recording it (for late-rendering SPAs)
+        #[arg(long, value_name = "TEXT")]
+        wait_for_text: Option<String>,
+
+        /// Screen-record only the Chrome window instead of the whole display
+        #[arg(long, conflicts_with = "region")]
+        capture_window: bool,
+
+        /// Path to JavaScript run at the start of every page, before the page's own scripts
+        #[arg(long, value_name = "PATH")]
+        pre_nav_script: Option<String>,
+
+        /// Path to JavaScript run after each page loads, before scrolling (e.g. to hide sticky headers)
+        #[arg(long, value_name = "PATH")]
+        post_nav_script: Option<String>,
+
+        /// Stop queuing URLs that differ only in query values after this many per pattern (default 200, 0 for no cap)
+        #[arg(long, value_name = "N")]
+        max_urls_per_template: Option<usize>,
+
+        /// Stop queuing new values of a query parameter on one path after this many (default 50, 0 for no cap)
+        #[arg(long, value_name = "N")]
+        max_param_values: Option<usize>,
+
+        /// Stream the screen recording to stdout (-) or an existing named pipe instead of a file; logs go to stderr
+        #[arg(long, value_name = "-|FIFO")]
+        stream_to: Option<String>,
+
+        /// Ignore TLS certificate errors (self-signed or expired certs); only for trusted dev/staging sites
+        #[arg(long)]
+        insecure: bool,
+
+        /// CSS selector of the element to record (with --recording-mode element)
+        #[arg(long, value_name = "SELECTOR", required_if_eq("recording_mode", "element"))]
+        element: Option<String>,
+
+        /// Stop crawling once the video and frames on disk reach this many megabytes
+        #[arg(long, value_name = "MB")]
+        max_recording_size: Option<u64>,
+
+        /// Stop recording after this many pages and keep crawling without video
+        #[arg(long, value_name = "N")]
+        record_first_pages: Option<usize>,
+
+        /// Chrome profile directory to reuse across runs (keeps logins, cookies and cache); one instance at a time
+        #[arg(long, value_name = "DIR")]
+        profile: Option<PathBuf>,
+
+        /// Wait out HTTP 429 responses (honoring Retry-After) and retry; pass false to disable
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+        respect_rate_limits: bool,
+
+        /// Accept cookie consent and age gates the first time each host is visited
+        #[arg(long)]
+        warm_up: bool,
+
+        /// Open the video with a title card (URL, date, session ID) shown for SECS seconds (default 3)
+        #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "3")]
+        intro_card: Option<u32>,
+
+        /// Extra request header sent with every request, as "Name: Value" (repeatable)
+        #[arg(long = "header", value_name = "NAME: VALUE", value_parser = parse_header)]
+        headers: Vec<(String, String)>,
+
+        /// Write the data export as a bare JSON array instead of the versioned envelope (deprecated)
+        #[arg(long)]
+        legacy_json_export: bool,
+
+        /// Vary each delay by a random amount up to this many milliseconds either way
+        #[arg(long, value_name = "MS", default_value = "0")]
+        delay_jitter: u64,
+
+        /// Seed for random delay jitter, so a run can be reproduced
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Tile a labeled screenshot of each page into <session>_contactsheet.jpg, COLSxROWS tiles (default 4x4)
+        #[arg(long, value_name = "COLSxROWS", num_args = 0..=1, default_missing_value = "4x4", value_parser = parse_grid)]
+        contact_sheet: Option<(u32, u32)>,
+
+        /// Place the browser window at X,Y (may be off-screen, e.g. -2000,0). Use with --recording-mode browser
+        #[arg(long, value_name = "X,Y", value_parser = parse_position, allow_hyphen_values = true)]
+        window_position: Option<(i32, i32)>,
+
+        /// Keep the browser window minimized. Use with --recording-mode browser
+        #[arg(long)]
+        minimized: bool,
+
+        /// Pause after each page loads, in milliseconds; lower it to speed up crawls of fast sites
+        #[arg(long, value_name = "MS", default_value = "1000")]
+        post_nav_settle_ms: u64,
+
+        /// Pause after closing modal dialogs, in milliseconds
+        #[arg(long, value_name = "MS", default_value = "500")]
+        post_modal_settle_ms: u64,
+
+        /// Serve a JSON health check on GET /healthz at this address (e.g. 127.0.0.1:9185)
+        #[arg(long)]
+        health_addr: Option<std::net::SocketAddr>,
+
+        /// Report the crawl as stalled (HTTP 503 on /healthz) after this long without page progress
+        #[arg(long, value_name = "DURATION", default_value = "5m", value_parser = parse_duration_secs)]
+        stall_timeout: u64,
+
+        /// Also visit the URLs in this file, one per line, after the start URL
+        #[arg(long, value_name = "PATH")]
+        url_file: Option<PathBuf>,
+
+        /// Visit only the start URL, sitemap entries and --url-file URLs, in
+        /// that order, without following links; --max-pages still applies
+        #[arg(long)]
+        no_discover: bool,
+
+        /// Browser frames per subdirectory and encoded segment, joined into one video at the end [default: 5000]
+        #[arg(long, value_name = "FRAMES")]
+        frame_batch_size: Option<u64>,
+
+        /// Name the session this instead of session_<timestamp>_<suffix>
+        #[arg(long, value_name = "ID", conflicts_with = "session_prefix")]
+        session_id: Option<String>,
+
+        /// Start generated session IDs with this instead of "session", e.g. to tag runs by project
+        #[arg(long, value_name = "PREFIX")]
+        session_prefix: Option<String>,
+
+        /// Check each finished video with ffprobe and report an empty or unplayable file as a warning
+        #[arg(long)]
+        verify_video: bool,
+
+        /// Treat a page whose rel=canonical link names another crawlable URL as a duplicate of it and skip its links
+        #[arg(long)]
+        respect_canonical: bool,
+
+        /// Audio codec (default: opus for WebM, aac otherwise)
+        #[arg(long, requires = "audio")]
+        audio_codec: Option<AudioCodecArg>,
+
+        /// Audio bitrate in kbps (8-512); the encoder default otherwise
+        #[arg(long, value_name = "KBPS", requires = "audio")]
+        audio_bitrate: Option<u32>,
+
+        /// Limit on each HTTP request of the crawler, sitemap and robots.txt included [default: 30]
+        #[arg(long, value_name = "SECS")]
+        http_timeout: Option<u64>,
+
+        /// Retries of a sitemap or robots.txt fetch that timed out or got a 5xx/429 before going on without it [default: 2]
+        #[arg(long, value_name = "N")]
+        aux_retries: Option<u32>,
+
+        /// Pause the recording during the --delay wait between pages, leaving the idle gaps out of browser videos (a screen video holds its last frame instead)
+        #[arg(long)]
+        pause_during_delay: bool,
+
+        /// Extra Chrome switch passed through at launch, e.g. --chrome-arg=--lang=de (repeatable)
+        #[arg(long = "chrome-arg", value_name = "--FLAG[=VALUE]", allow_hyphen_values = true, value_parser = parse_chrome_arg)]
+        chrome_args: Vec<String>,
+
+        /// Device pixel ratio to render and capture pages at: 1 for small
+        /// files, 2 for sharp HiDPI video (four times the pixels)
+        #[arg(long, value_name = "RATIO")]
+        device_scale_factor: Option<f64>,
+
+        /// Kiosk mode: load the start URL once and keep recording it, without
+        /// crawling, until --duration passes or the run is stopped
+        #[arg(long, conflicts_with_all = ["sitemap", "url_file"])]
+        kiosk: bool,
+
+        /// How long to record in kiosk mode (e.g. 600, 90s, 30m, 2h)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs, requires = "kiosk", conflicts_with = "max_runtime")]
+        duration: Option<u64>,
+
+        /// Report progress as JSON lines on stderr instead of the progress
+        /// bar, for CI logs and other non-interactive output
+        #[arg(long = "json", conflicts_with = "tui")]
+        json_progress: bool,
+
+        /// Data export formats, comma-separated (json, csv, html); each is
+        /// written to <session>_data.<ext>
+        #[arg(long, value_delimiter = ',', default_value = "json")]
+        export_format: Vec<ExportFormatArg>,
+
+        /// Capture browser frames less often (down to 1/8 of --fps) while
+        /// SiteRecorder, FFmpeg and Chrome together use more than this much memory, instead of being killed
+        #[arg(long, value_name = "MB")]
+        memory_limit_mb: Option<u64>,
+
+        /// Also save a full-page PNG of every visited page into this directory,
+        /// named after its URL, in any recording mode
+        #[arg(long, value_name = "DIR")]
+        screenshots_per_page: Option<PathBuf>,
+
+        /// Relaunch a crashed browser and carry on this many times before
+        /// ending the crawl [default: 3]
+        #[arg(long, value_name = "N")]
+        max_browser_relaunches: Option<u32>,
     },
     
     /// Resume an interrupted session
@@ -159,6 +574,97 @@ pub enum Commands {
         /// Output directory to list sessions from
         #[arg(short, long, default_value = "./recordings")]
         output: PathBuf,
+
+        /// Order sessions by start date, recording duration or size on disk
+        #[arg(long, value_enum, default_value = "date")]
+        sort_by: SessionSortArg,
+
+        /// Print the sessions as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+
+        /// Only sessions started within this long (e.g. 7d, 12h) or since this date (e.g. 2024-01-01)
+        #[arg(long, value_name = "DURATION|DATE", value_parser = parse_since)]
+        since: Option<DateTime<Utc>>,
+    },
+
+    /// Estimate a crawl's size by counting the URLs found at each link depth, without recording
+    Scope {
+        /// Starting URL
+        url: String,
+
+        /// Follow links this many levels deep from the starting URL
+        #[arg(long, default_value = "3")]
+        max_depth: usize,
+
+        /// Stop after fetching this many pages
+        #[arg(long, default_value = "500")]
+        max_fetches: usize,
+
+        /// Ignore TLS certificate errors
+        #[arg(long)]
+        insecure: bool,
+
+        /// Print the counts as JSON instead of a histogram
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Encode the browser frames an interrupted recording left behind
+    Encode {
+        /// Session directory, or the frames directory itself
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Video to write (default: <SESSION>_recovered.mp4 next to the frames)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Frames per second the frames were captured at
+        #[arg(short, long, default_value = "30")]
+        fps: u32,
+    },
+
+    /// Save a screenshot of one page, without crawling or recording
+    Shot {
+        /// Page to capture
+        url: String,
+
+        /// Image to write; a .jpg/.jpeg or .webp extension picks that format, anything else is PNG
+        #[arg(short, long, default_value = "screenshot.png")]
+        output: PathBuf,
+
+        /// Ignore TLS certificate errors
+        #[arg(long)]
+        insecure: bool,
+    },
+
+    /// Compare two JSON exports: added and removed pages, status and title changes
+    Diff {
+        /// Export of the earlier run
+        baseline: PathBuf,
+
+        /// Export of the later run
+        current: PathBuf,
+
+        /// Print the diff as JSON instead of a summary
+        #[arg(long)]
+        json: bool,
+
+        /// Also write the diff as JSON to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Run a crawl on a cron schedule, each run in its own timestamped output directory
+    Schedule {
+        /// When to crawl, as "MIN HOUR DAY MONTH WEEKDAY" (e.g. "0 */6 * * *"), optionally with a leading seconds field
+        #[arg(value_name = "CRON", value_parser = crate::schedule::parse_schedule)]
+        schedule: cron::Schedule,
+
+        /// Arguments for each crawl, as given to the crawl command (after `--`)
+        #[arg(value_name = "CRAWL_ARGS", required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        crawl: Vec<String>,
     },
 
     /// Run the vulnerability scanner standalone (no recording)
@@ -221,12 +727,101 @@ impl Commands {
                 scan_url,
                 login_script,
                 concurrency,
+                tui,
+                organize_by_session,
+                geo,
+                timezone,
+                locale,
+                color_scheme,
+                metrics_addr,
+                save_har,
+                post_login_wait,
+                totp_secret,
+                totp_field,
+                username_env,
+                password_env,
+                credentials_file,
+                max_runtime,
+                discord_webhook,
+                email_config,
+                viewport,
+                finalize_timeout,
+                retry,
+                screenshot_format,
+                screenshot_quality,
+                smooth_scroll,
+                pause_at_sections,
+                emit_sitemap,
+                dedup_content,
+                allowed_domains,
+                fetch_backend,
+                discover_assets,
+                download_dir,
+                unique_export,
+                exclude_failures,
+                browser_idle_timeout,
+                tabs,
+                wait_for_text,
+                capture_window,
+                pre_nav_script,
+                post_nav_script,
+                max_urls_per_template,
+                max_param_values,
+                stream_to,
+                insecure,
+                element,
+                max_recording_size,
+                record_first_pages,
+                profile,
+                respect_rate_limits,
+                warm_up,
+                intro_card,
+                headers,
+                legacy_json_export,
+                delay_jitter,
+                seed,
+                contact_sheet,
+                window_position,
+                minimized,
+                post_nav_settle_ms,
+                post_modal_settle_ms,
+                health_addr,
+                stall_timeout,
+                url_file,
+                no_discover,
+                frame_batch_size,
+                session_id,
+                session_prefix,
+                verify_video,
+                respect_canonical,
+                audio_codec,
+                audio_bitrate,
+                http_timeout,
+                aux_retries,
+                pause_during_delay,
+                chrome_args,
+                device_scale_factor,
+                kiosk,
+                duration,
+                json_progress,
+                export_format,
+                memory_limit_mb,
+                screenshots_per_page,
+                max_browser_relaunches,
             } => {
                 let login_script = login_script
                     .map(|path| {
                         std::fs::read_to_string(&path)
                             .unwrap_or_else(|e| panic!("Failed to read login script {}: {}", path, e))
                     });
+                let pre_nav_script = pre_nav_script.map(|path| {
+                    std::fs::read_to_string(&path)
+                        .unwrap_or_else(|e| panic!("Failed to read pre-navigation script {}: {}", path, e))
+                });
+                let post_nav_script = post_nav_script.map(|path| {
+                    std::fs::read_to_string(&path)
+                        .unwrap_or_else(|e| panic!("Failed to read post-navigation script {}: {}", path, e))
+                });
                 CrawlArgs {
                     url,
                     max_pages,
@@ -235,7 +830,8 @@ impl Commands {
                     recording_mode,
                     fps,
                     audio,
-                    headless,
+                    new_headless: matches!(headless, Some(HeadlessModeArg::New)),
+                    headless: headless.is_some(),
                     daemon,
                     progress,
                     log_file,
@@ -251,6 +847,86 @@ impl Commands {
                     login_script,
                     concurrency,
                     region,
+                    tui,
+                    organize_by_session,
+                    geo,
+                    timezone,
+                    locale,
+                    color_scheme,
+                    metrics_addr,
+                    save_har,
+                    post_login_wait,
+                    totp_secret,
+                    totp_field,
+                    username_env,
+                    password_env,
+                    credentials_file,
+                    max_runtime: max_runtime.or(duration),
+                    discord_webhook,
+                    email_config,
+                    viewport,
+                    finalize_timeout,
+                    retry,
+                    screenshot_format,
+                    screenshot_quality,
+                    smooth_scroll,
+                    pause_at_sections,
+                    emit_sitemap,
+                    dedup_content,
+                    allowed_domains,
+                    fetch_backend,
+                    discover_assets,
+                    download_dir,
+                    unique_export,
+                    exclude_failures,
+                    browser_idle_timeout,
+                    tabs,
+                    wait_for_text,
+                    capture_window,
+                    pre_nav_script,
+                    post_nav_script,
+                    max_urls_per_template,
+                    max_param_values,
+                    stream_to,
+                    insecure,
+                    element,
+                    max_recording_size,
+                    record_first_pages,
+                    profile,
+                    respect_rate_limits,
+                    warm_up,
+                    intro_card,
+                    headers,
+                    legacy_json_export,
+                    delay_jitter,
+                    seed,
+                    contact_sheet,
+                    window_position,
+                    minimized,
+                    post_nav_settle_ms,
+                    post_modal_settle_ms,
+                    health_addr,
+                    stall_timeout,
+                    url_file,
+                    no_discover,
+                    frame_batch_size,
+                    session_id,
+                    session_prefix,
+                    verify_video,
+                    respect_canonical,
+                    audio_codec,
+                    audio_bitrate,
+                    http_timeout,
+                    aux_retries,
+                    pause_during_delay,
+                    chrome_args,
+                    device_scale_factor,
+                    kiosk,
+                    json_progress,
+                    export_format,
+                    memory_limit_mb,
+                    screenshots_per_page,
+                    max_browser_relaunches,
                 }
             }
             _ => panic!("into_crawl_args called on non-Crawl command"),
@@ -290,6 +966,131 @@ fn parse_region(s: &str) -> Result<(i32, i32, i32, i32), String> {
     Ok((x, y, w, h))
 }
 
+/// Parse a browser viewport in the form `WIDTHxHEIGHT`.
+fn parse_viewport(s: &str) -> Result<(u32, u32), String> {
+    let (w, h) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| "Viewport must be in the form WIDTHxHEIGHT".to_string())?;
+    let width = w.trim().parse::<u32>().map_err(|_| "Invalid viewport width".to_string())?;
+    let height = h.trim().parse::<u32>().map_err(|_| "Invalid viewport height".to_string())?;
+    if width == 0 || height == 0 {
+        return Err("Viewport width and height must be positive".to_string());
+    }
+    Ok((width, height))
+}
+
+/// Parse a window position in the form `X,Y`; either may be negative.
+fn parse_position(s: &str) -> Result<(i32, i32), String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| "Position must be in the form X,Y".to_string())?;
+    let x = x.trim().parse::<i32>().map_err(|_| "Invalid x position".to_string())?;
+    let y = y.trim().parse::<i32>().map_err(|_| "Invalid y position".to_string())?;
+    Ok((x, y))
+}
+
+/// Parse a request header in the form `Name: Value`.
+fn parse_header(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| "Header must be in the form \"Name: Value\"".to_string())?;
+    let (name, value) = (name.trim(), value.trim());
+    browser::validate_header(name, value)?;
+    if browser::is_forbidden_header(name) {
+        return Err(format!("{} is set by the browser and can't be overridden", name));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Parse a Chrome switch to pass through, refusing unsafe ones.
+fn parse_chrome_arg(s: &str) -> Result<String, String> {
+    let arg = s.trim();
+    browser::validate_chrome_arg(arg)?;
+    Ok(arg.to_string())
+}
+
+/// Parse a contact sheet grid in the form `COLSxROWS`.
+fn parse_grid(s: &str) -> Result<(u32, u32), String> {
+    let (columns, rows) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| "Grid must be in the form COLSxROWS".to_string())?;
+    let columns = columns.trim().parse::<u32>().map_err(|_| "Invalid column count".to_string())?;
+    let rows = rows.trim().parse::<u32>().map_err(|_| "Invalid row count".to_string())?;
+    if columns == 0 || rows == 0 || columns * rows > 100 {
+        return Err("Grid must have between 1 and 100 tiles".to_string());
+    }
+    Ok((columns, rows))
+}
+
+/// Parse a geolocation in the form `LAT,LON[,ACCURACY]`.
+fn parse_geo(s: &str) -> Result<(f64, f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return Err("Geolocation must be in the form LAT,LON[,ACCURACY]".to_string());
+    }
+    let latitude = parts[0]
+        .parse::<f64>()
+        .map_err(|_| "Invalid latitude".to_string())?;
+    let longitude = parts[1]
+        .parse::<f64>()
+        .map_err(|_| "Invalid longitude".to_string())?;
+    let accuracy = match parts.get(2) {
+        Some(a) => a.parse::<f64>().map_err(|_| "Invalid accuracy".to_string())?,
+        None => 100.0,
+    };
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err("Latitude must be within ±90 and longitude within ±180".to_string());
+    }
+    Ok((latitude, longitude, accuracy))
+}
+
+/// Parse a duration as seconds, with an optional `s`, `m` or `h` suffix.
+fn parse_duration_secs(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, multiplier) = match s.char_indices().last() {
+        Some((i, 's')) => (&s[..i], 1),
+        Some((i, 'm')) => (&s[..i], 60),
+        Some((i, 'h')) => (&s[..i], 3600),
+        _ => (s, 1),
+    };
+    let value = number
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| "Duration must be a number of seconds, optionally suffixed with s, m or h".to_string())?;
+    if value == 0 {
+        return Err("Duration must be greater than zero".to_string());
+    }
+    Ok(value * multiplier)
+}
+
+/// Parse a `--since` cutoff: a duration before now with an `m`, `h`, `d`
+/// or `w` suffix, a `YYYY-MM-DD` date (midnight UTC) or an RFC 3339 time.
+fn parse_since(s: &str) -> Result<DateTime<Utc>, String> {
+    since_cutoff(s, Utc::now())
+}
+
+fn since_cutoff(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let (number, unit_secs) = match s.char_indices().last() {
+        Some((i, 'm')) => (&s[..i], 60),
+        Some((i, 'h')) => (&s[..i], 3600),
+        Some((i, 'd')) => (&s[..i], 86_400),
+        Some((i, 'w')) => (&s[..i], 604_800),
+        _ => return Err("Expected a duration like 7d or 12h, or a date like 2024-01-01".to_string()),
+    };
+    let value = number
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| "Expected a duration like 7d or 12h, or a date like 2024-01-01".to_string())?;
+    Ok(now - chrono::Duration::seconds(value * unit_secs))
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum RecordingModeArg {
     /// Record screen only
@@ -298,12 +1099,85 @@ pub enum RecordingModeArg {
     Browser,
     /// Record both screen and screenshots
     Both,
+    /// Crawl and export data without recording (no FFmpeg needed)
+    None,
+    /// Record screenshots of a single element (see --element)
+    Element,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ScreenshotFormatArg {
+    /// Lossless PNG frames (default)
+    Png,
+    /// JPEG frames at --screenshot-quality
+    Jpeg,
+    /// WebP frames at --screenshot-quality
+    Webp,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ExportFormatArg {
+    /// Versioned JSON, read back by resume, list and diff
+    Json,
+    /// One row per record
+    Csv,
+    /// A browsable report
+    Html,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum FetchBackendArg {
+    /// Load pages in Chrome
+    Browser,
+    /// Plain HTTP requests, no JavaScript (link discovery only)
+    Http,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum SessionSortArg {
+    /// Newest first
+    Date,
+    /// Longest recording first
+    Duration,
+    /// Largest on disk first
+    Size,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum HeadlessModeArg {
+    /// Chrome's original headless mode
+    Legacy,
+    /// The headless mode built on the regular browser
+    New,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum AudioCodecArg {
+    /// AAC, for MP4, AVI and MKV
+    Aac,
+    /// Opus, for WebM, MP4 and MKV
+    Opus,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ColorSchemeArg {
+    /// Emulate prefers-color-scheme: light
+    Light,
+    /// Emulate prefers-color-scheme: dark
+    Dark,
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Parse the arguments of the crawl command, without the subcommand name.
+    pub fn parse_crawl_args(args: &[String]) -> Result<CrawlArgs, clap::Error> {
+        let argv = ["site-recorder", "crawl"].into_iter().map(String::from).chain(args.iter().cloned());
+        let cli = Self::try_parse_from(argv)?;
+        Ok(cli.command.expect("crawl subcommand").into_crawl_args())
+    }
 }
 
 #[cfg(test)]
@@ -313,7 +1187,7 @@ mod tests {
     #[test]
     fn test_cli_parsing() {
         // Test basic crawl command
-        let cli = Cli::try_parse_from(&[
+        let cli = Cli::try_parse_from([
             "site-recorder",
             "crawl",
             "https://example.com",
@@ -326,7 +1200,7 @@ mod tests {
     fn test_recording_modes() {
         let modes = vec!["screen", "browser", "both"];
         for mode in modes {
-            let cli = Cli::try_parse_from(&[
+            let cli = Cli::try_parse_from([
                 "site-recorder",
                 "crawl",
                 "https://example.com",
@@ -335,4 +1209,188 @@ mod tests {
             assert!(cli.is_ok());
         }
     }
+
+    #[test]
+    fn test_parse_geo() {
+        assert_eq!(parse_geo("52.52,13.405"), Ok((52.52, 13.405, 100.0)));
+        assert_eq!(parse_geo("-33.86, 151.2, 25"), Ok((-33.86, 151.2, 25.0)));
+        assert!(parse_geo("52.52").is_err());
+        assert!(parse_geo("91,0").is_err());
+
+        let cli = Cli::try_parse_from(["site-recorder", "crawl", "https://example.com", "--geo", "-33.86,151.2"]);
+        assert!(cli.is_ok());
+    }
+
+    #[test]
+    fn test_viewport_and_screen_size() {
+        assert_eq!(parse_viewport("1280x800"), Ok((1280, 800)));
+        assert_eq!(parse_viewport("390X844"), Ok((390, 844)));
+        assert!(parse_viewport("1280").is_err());
+        assert!(parse_viewport("0x800").is_err());
+
+        let cli = Cli::try_parse_from([
+            "site-recorder", "crawl", "https://example.com",
+            "--viewport", "1280x800", "--screen-width", "2560", "--screen-height", "1440",
+        ])
+        .unwrap();
+        let args = cli.command.unwrap().into_crawl_args();
+        assert_eq!(args.viewport, Some((1280, 800)));
+        assert_eq!((args.screen_width, args.screen_height), (2560, 1440));
+    }
+
+    #[test]
+    fn test_parse_duration_secs() {
+        assert_eq!(parse_duration_secs("1800"), Ok(1800));
+        assert_eq!(parse_duration_secs("90s"), Ok(90));
+        assert_eq!(parse_duration_secs("30m"), Ok(1800));
+        assert_eq!(parse_duration_secs("2h"), Ok(7200));
+        assert!(parse_duration_secs("0").is_err());
+        assert!(parse_duration_secs("soon").is_err());
+    }
+
+    #[test]
+    fn test_contact_sheet_grid() {
+        assert_eq!(parse_grid("5x3"), Ok((5, 3)));
+        assert!(parse_grid("0x3").is_err());
+        assert!(parse_grid("20x20").is_err());
+
+        let parse = |extra: &[&str]| {
+            let args = ["site-recorder", "crawl", "https://example.com"].iter().chain(extra).copied();
+            Cli::try_parse_from(args).unwrap().command.unwrap().into_crawl_args().contact_sheet
+        };
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&["--contact-sheet"]), Some((4, 4)));
+        assert_eq!(parse(&["--contact-sheet", "6x2"]), Some((6, 2)));
+    }
+
+    #[test]
+    fn test_window_position() {
+        assert_eq!(parse_position("-2000,0"), Ok((-2000, 0)));
+        assert_eq!(parse_position(" 10 , 20 "), Ok((10, 20)));
+        assert!(parse_position("10x20").is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(parse_header("X-Api-Key: abc:123"), Ok(("X-Api-Key".to_string(), "abc:123".to_string())));
+        assert!(parse_header("X-Api-Key").is_err());
+        assert!(parse_header("Host: example.org").is_err());
+
+        let cli = Cli::try_parse_from([
+            "site-recorder", "crawl", "https://example.com",
+            "--header", "X-Forwarded-For: 10.0.0.1", "--header", "X-Flag: beta",
+        ])
+        .unwrap();
+        assert_eq!(cli.command.unwrap().into_crawl_args().headers.len(), 2);
+    }
+
+    #[test]
+    fn test_since_cutoff() {
+        let now = DateTime::parse_from_rfc3339("2024-03-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |s: &str| since_cutoff(s, now).map(|t| t.to_rfc3339());
+        assert_eq!(at("7d"), Ok("2024-03-03T12:00:00+00:00".to_string()));
+        assert_eq!(at("12h"), Ok("2024-03-10T00:00:00+00:00".to_string()));
+        assert_eq!(at("2024-01-01"), Ok("2024-01-01T00:00:00+00:00".to_string()));
+        assert_eq!(at("2024-01-01T08:00:00+02:00"), Ok("2024-01-01T06:00:00+00:00".to_string()));
+        assert!(at("7").is_err());
+        assert!(at("last week").is_err());
+    }
+
+    #[test]
+    fn test_respect_rate_limits_defaults_on() {
+        let parse = |extra: &[&str]| {
+            let args = ["site-recorder", "crawl", "https://example.com"].iter().chain(extra).copied();
+            Cli::try_parse_from(args).unwrap().command.unwrap().into_crawl_args().respect_rate_limits
+        };
+        assert!(parse(&[]));
+        assert!(!parse(&["--respect-rate-limits", "false"]));
+    }
+
+    #[test]
+    fn test_element_mode_requires_selector() {
+        let base = ["site-recorder", "crawl", "https://example.com", "-m", "element"];
+        assert!(Cli::try_parse_from(base).is_err());
+        assert!(Cli::try_parse_from(base.iter().copied().chain(["--element", "#chart"])).is_ok());
+    }
+
+    #[test]
+    fn test_chrome_args() {
+        let cli = Cli::try_parse_from([
+            "site-recorder", "crawl", "https://example.com", "--chrome-arg", "--lang=de", "--chrome-arg=--disable-gpu",
+        ])
+        .unwrap();
+        let args = cli.command.unwrap().into_crawl_args();
+        assert_eq!(args.chrome_args, vec!["--lang=de", "--disable-gpu"]);
+
+        let cli = Cli::try_parse_from(["site-recorder", "crawl", "https://example.com", "--chrome-arg=--no-sandbox"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_headless_modes() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["site-recorder", "crawl", "https://example.com"];
+            argv.extend_from_slice(extra);
+            let args = Cli::try_parse_from(argv).unwrap().command.unwrap().into_crawl_args();
+            (args.headless, args.new_headless)
+        };
+        assert_eq!(parse(&[]), (false, false));
+        assert_eq!(parse(&["--headless"]), (true, false));
+        assert_eq!(parse(&["--headless=legacy"]), (true, false));
+        assert_eq!(parse(&["--headless=new"]), (true, true));
+        assert!(Cli::try_parse_from(["site-recorder", "crawl", "https://example.com", "--headless=old"]).is_err());
+    }
+
+    #[test]
+    fn test_tui_conflicts_with_daemon() {
+        let cli = Cli::try_parse_from(["site-recorder", "crawl", "https://example.com", "--tui"]);
+        assert!(cli.is_ok());
+
+        let cli = Cli::try_parse_from([
+            "site-recorder",
+            "crawl",
+            "https://example.com",
+            "--tui",
+            "--daemon",
+        ]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_schedule_crawl_args() {
+        let cli = Cli::try_parse_from([
+            "site-recorder", "schedule", "0 */6 * * *", "--", "https://example.com", "--max-pages", "20",
+        ])
+        .unwrap();
+        let Some(Commands::Schedule { crawl, .. }) = cli.command else {
+            panic!("expected the schedule command");
+        };
+        let args = Cli::parse_crawl_args(&crawl).unwrap();
+        assert_eq!(args.url, "https://example.com");
+        assert_eq!(args.max_pages, 20);
+
+        assert!(Cli::try_parse_from(["site-recorder", "schedule", "hourly", "--", "https://example.com"]).is_err());
+        assert!(Cli::parse_crawl_args(&["--max-pages".to_string(), "20".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_kiosk_duration() {
+        let args = Cli::parse_crawl_args(&["https://example.com/status", "--kiosk", "--duration", "10m"].map(String::from)).unwrap();
+        assert!(args.kiosk);
+        assert_eq!(args.max_runtime, Some(600));
+
+        assert!(Cli::parse_crawl_args(&["https://example.com", "--duration", "10m"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let args = Cli::parse_crawl_args(&["https://example.com".to_string()]).unwrap();
+        assert!(matches!(args.export_format[..], [ExportFormatArg::Json]));
+
+        let args = Cli::parse_crawl_args(&["https://example.com", "--export-format", "json,csv,html"].map(String::from)).unwrap();
+        assert_eq!(args.export_format.len(), 3);
+        assert!(matches!(args.export_format[2], ExportFormatArg::Html));
+
+        assert!(Cli::parse_crawl_args(&["https://example.com", "--export-format", "xls"].map(String::from)).is_err());
+    }
 }
@@ -2,12 +2,129 @@ use anyhow::Result;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use recorder::RecorderEvent;
 use tracing::{error, info, warn};
 
+use crate::events::CrawlEvent;
+use crate::health::{HealthReport, HealthStatus};
+
 pub struct DaemonManager {
     pid_file: Option<PathBuf>,
     should_stop: Arc<AtomicBool>,
+    status: StatusBoard,
+}
+
+/// Crawl progress kept from engine events, logged on `SIGUSR1` and
+/// served on `/healthz`.
+#[derive(Debug, Clone)]
+struct CrawlSnapshot {
+    started: Instant,
+    /// When a page last started, finished loading or failed, or the crawl
+    /// last reported recorder stats while waiting.
+    last_progress: Instant,
+    recording: bool,
+    current_url: Option<String>,
+    pages_visited: usize,
+    pages_failed: usize,
+    pages_discovered: usize,
+    frontier: usize,
+    finished: bool,
+}
+
+impl Default for CrawlSnapshot {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            last_progress: Instant::now(),
+            recording: false,
+            current_url: None,
+            pages_visited: 0,
+            pages_failed: 0,
+            pages_discovered: 0,
+            frontier: 0,
+            finished: false,
+        }
+    }
+}
+
+/// Shared handle on the daemon's latest crawl progress.
+#[derive(Debug, Clone, Default)]
+pub struct StatusBoard {
+    snapshot: Arc<Mutex<CrawlSnapshot>>,
+}
+
+impl StatusBoard {
+    /// Update the snapshot from a crawl event.
+    pub fn observe(&self, event: &CrawlEvent) {
+        let mut snapshot = self.snapshot.lock().unwrap();
+        match event {
+            CrawlEvent::PageStarted { url, .. } => {
+                snapshot.current_url = Some(url.clone());
+                snapshot.last_progress = Instant::now();
+            }
+            CrawlEvent::PageVisited {
+                pages_visited,
+                pages_discovered,
+                pages_remaining,
+                ..
+            } => {
+                snapshot.pages_visited = *pages_visited;
+                snapshot.pages_discovered = *pages_discovered;
+                snapshot.frontier = *pages_remaining;
+                snapshot.last_progress = Instant::now();
+            }
+            CrawlEvent::NavigationFailed { .. } => {
+                snapshot.pages_failed += 1;
+                snapshot.last_progress = Instant::now();
+            }
+            // Sent after each page and as a heartbeat during delays, rate
+            // limit waits and kiosk runs
+            CrawlEvent::RecorderStats { .. } => snapshot.last_progress = Instant::now(),
+            CrawlEvent::Recorder(RecorderEvent::Started { .. }) => snapshot.recording = true,
+            CrawlEvent::Recorder(RecorderEvent::Stopped { .. }) => snapshot.recording = false,
+            CrawlEvent::Finished { .. } => {
+                snapshot.finished = true;
+                snapshot.current_url = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// One-line summary of the crawl for the log.
+    pub fn describe(&self) -> String {
+        let snapshot = self.snapshot.lock().unwrap();
+        format!(
+            "Crawl status: {} after {}s; {} visited, {} failed, {} discovered, {} queued; current page: {}",
+            if snapshot.finished { "finished" } else { "running" },
+            snapshot.started.elapsed().as_secs(),
+            snapshot.pages_visited,
+            snapshot.pages_failed,
+            snapshot.pages_discovered,
+            snapshot.frontier,
+            snapshot.current_url.as_deref().unwrap_or("none"),
+        )
+    }
+
+    /// Health of the crawl: stalled once it has gone `stall_after` without
+    /// working on a page or reporting that it is waiting.
+    pub fn health(&self, stall_after: Duration) -> HealthReport {
+        let snapshot = self.snapshot.lock().unwrap();
+        let status = if snapshot.finished {
+            HealthStatus::Finished
+        } else if snapshot.last_progress.elapsed() > stall_after {
+            HealthStatus::Stalled
+        } else {
+            HealthStatus::Ok
+        };
+        HealthReport {
+            status,
+            uptime_secs: snapshot.started.elapsed().as_secs(),
+            pages_visited: snapshot.pages_visited,
+            recording: snapshot.recording,
+        }
+    }
 }
 
 impl DaemonManager {
@@ -15,6 +132,7 @@ impl DaemonManager {
         Self {
             pid_file,
             should_stop: Arc::new(AtomicBool::new(false)),
+            status: StatusBoard::default(),
         }
     }
 
@@ -35,15 +153,20 @@ impl DaemonManager {
         Ok(())
     }
 
-    /// Set up signal handlers for graceful shutdown
+    /// Set up signal handlers for graceful shutdown and status dumps
     fn setup_signal_handlers(&self) -> Result<()> {
         let should_stop = self.should_stop.clone();
-        setup_platform_signal_handlers(should_stop)
+        setup_platform_signal_handlers(should_stop, self.status.clone())
     }
 
-    /// Check if shutdown was requested
-    pub fn should_stop(&self) -> bool {
-        self.should_stop.load(Ordering::SeqCst)
+    /// Progress that `SIGUSR1` logs; feed it the crawl's events.
+    pub fn status_board(&self) -> StatusBoard {
+        self.status.clone()
+    }
+
+    /// Flag set once shutdown is requested, for handing to the crawl engine.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.should_stop.clone()
     }
 
     /// Clean up daemon resources
@@ -67,15 +190,19 @@ impl Drop for DaemonManager {
     }
 }
 
+/// SIGTERM and SIGINT stop the crawl gracefully; SIGUSR1 logs the current
+/// status and leaves the crawl running.
 #[cfg(unix)]
-fn setup_platform_signal_handlers(should_stop: Arc<AtomicBool>) -> Result<()> {
-    use signal_hook::consts::{SIGINT, SIGTERM};
+fn setup_platform_signal_handlers(should_stop: Arc<AtomicBool>, status: StatusBoard) -> Result<()> {
+    use signal_hook::consts::{SIGINT, SIGTERM, SIGUSR1};
     use signal_hook::iterator::Signals;
 
-    let mut signals = Signals::new(&[SIGTERM, SIGINT])?;
+    let mut signals = Signals::new([SIGTERM, SIGINT, SIGUSR1])?;
     std::thread::spawn(move || {
         for sig in signals.forever() {
-            if sig == SIGTERM || sig == SIGINT {
+            if sig == SIGUSR1 {
+                info!("{}", status.describe());
+            } else if sig == SIGTERM || sig == SIGINT {
                 info!(
                     "Received shutdown signal ({}), initiating graceful shutdown",
                     sig
@@ -89,8 +216,9 @@ fn setup_platform_signal_handlers(should_stop: Arc<AtomicBool>) -> Result<()> {
     Ok(())
 }
 
+/// Windows has no SIGUSR1, so status dumps are unavailable there.
 #[cfg(windows)]
-fn setup_platform_signal_handlers(should_stop: Arc<AtomicBool>) -> Result<()> {
+fn setup_platform_signal_handlers(should_stop: Arc<AtomicBool>, _status: StatusBoard) -> Result<()> {
     ctrlc::set_handler(move || {
         info!("Received Ctrl+C, initiating graceful shutdown");
         should_stop.store(true, Ordering::SeqCst);
@@ -195,7 +323,56 @@ mod tests {
     #[test]
     fn test_daemon_manager_creation() {
         let manager = DaemonManager::new(None);
-        assert!(!manager.should_stop());
+        assert!(!manager.stop_flag().load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_status_board_describe() {
+        let board = DaemonManager::new(None).status_board();
+        board.observe(&CrawlEvent::PageStarted {
+            url: "https://example.com/a".to_string(),
+            page_number: 3,
+        });
+        board.observe(&CrawlEvent::PageVisited {
+            url: "https://example.com/a".to_string(),
+            pages_visited: 3,
+            pages_discovered: 10,
+            pages_remaining: 7,
+        });
+        board.observe(&CrawlEvent::NavigationFailed {
+            url: "https://example.com/b".to_string(),
+            error: "timeout".to_string(),
+        });
+        let status = board.describe();
+        assert!(status.contains("running"));
+        assert!(status.contains("3 visited, 1 failed, 10 discovered, 7 queued"));
+        assert!(status.ends_with("current page: https://example.com/a"));
+    }
+
+    #[test]
+    fn test_status_board_health() {
+        let board = StatusBoard::default();
+        board.observe(&CrawlEvent::PageVisited {
+            url: "https://example.com/a".to_string(),
+            pages_visited: 1,
+            pages_discovered: 4,
+            pages_remaining: 3,
+        });
+        let health = board.health(Duration::from_secs(60));
+        assert_eq!(health.status, HealthStatus::Ok);
+        assert_eq!(health.pages_visited, 1);
+        assert!(!health.recording);
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(board.health(Duration::ZERO).status, HealthStatus::Stalled);
+
+        // A long wait between pages keeps reporting stats, which is not a stall
+        std::thread::sleep(Duration::from_millis(50));
+        board.observe(&CrawlEvent::RecorderStats {
+            duration_secs: 30,
+            frames_captured: 900,
+        });
+        assert_eq!(board.health(Duration::from_millis(40)).status, HealthStatus::Ok);
     }
 
     #[test]
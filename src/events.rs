@@ -0,0 +1,12 @@
+pub use engine::{CrawlEvent, EventSink};
+use tauri::{AppHandle, Manager};
+
+/// A sink that publishes every event to the GUI windows under
+/// [`CrawlEvent::channel`].
+pub fn tauri_sink(app: AppHandle) -> EventSink {
+    EventSink::disabled().with_handler(move |event| {
+        if let Err(e) = app.emit_all(event.channel(), event) {
+            tracing::debug!("Failed to emit {}: {}", event.channel(), e);
+        }
+    })
+}
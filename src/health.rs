@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::daemon::StatusBoard;
+use crate::http::{Response, Router};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    /// No page has loaded or failed for longer than the stall timeout.
+    Stalled,
+    Finished,
+}
+
+/// Body of `GET /healthz`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub uptime_secs: u64,
+    pub pages_visited: usize,
+    pub recording: bool,
+}
+
+impl HealthReport {
+    /// 503 for a stalled crawl so liveness probes restart it, 200 otherwise.
+    fn response_status(&self) -> &'static str {
+        match self.status {
+            HealthStatus::Stalled => "503 Service Unavailable",
+            HealthStatus::Ok | HealthStatus::Finished => "200 OK",
+        }
+    }
+}
+
+/// Answer `GET /healthz` on `router` from `board`.
+pub fn add_routes(router: &mut Router, board: StatusBoard, stall_after: Duration) {
+    router.route("/healthz", move || {
+        let report = board.health(stall_after);
+        Response {
+            status: report.response_status(),
+            content_type: "application/json",
+            body: serde_json::to_string(&report).unwrap_or_default(),
+        }
+    });
+}
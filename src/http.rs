@@ -0,0 +1,105 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+/// Answer to one `GET` request.
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+type Handler = Arc<dyn Fn() -> Response + Send + Sync>;
+
+/// The plain HTTP endpoints served on one address, e.g. `/metrics` and
+/// `/healthz`. Requests for any other path get a 404.
+#[derive(Clone, Default)]
+pub struct Router {
+    routes: Vec<(&'static str, Handler)>,
+}
+
+impl Router {
+    /// Answer `GET path` with `handler`.
+    pub fn route(&mut self, path: &'static str, handler: impl Fn() -> Response + Send + Sync + 'static) -> &mut Self {
+        self.routes.push((path, Arc::new(handler)));
+        self
+    }
+
+    /// Bind `addr` and answer requests on a background task.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?;
+        for (path, _) in &self.routes {
+            info!("Serving http://{}{}", local, path);
+        }
+
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let router = self.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = router.respond(stream).await {
+                                debug!("HTTP request to {} failed: {}", local, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept a connection on {}: {}", local, e),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn respond(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await?;
+        let response = self.response_to(&String::from_utf8_lossy(&buf[..n]));
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    /// The full HTTP response to `request`.
+    fn response_to(&self, request: &str) -> String {
+        let path = request.strip_prefix("GET ").and_then(|rest| rest.split(' ').next());
+        match self.routes.iter().find(|(route, _)| Some(*route) == path) {
+            Some((_, handler)) => {
+                let response = handler();
+                format!(
+                    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.status,
+                    response.content_type,
+                    response.body.len(),
+                    response.body
+                )
+            }
+            None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_router_response() {
+        let mut router = Router::default();
+        router.route("/healthz", || Response {
+            status: "503 Service Unavailable",
+            content_type: "application/json",
+            body: "{}".to_string(),
+        });
+
+        let response = router.response_to("GET /healthz HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.contains("Content-Length: 2\r\n"));
+        assert!(response.ends_with("\r\n\r\n{}"));
+        assert!(router.response_to("GET /healthz/x HTTP/1.1\r\n").starts_with("HTTP/1.1 404"));
+        assert!(router.response_to("POST /healthz HTTP/1.1\r\n").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+use recorder::RecorderEvent;
+use tracing::warn;
+
+use crate::events::CrawlEvent;
+use crate::http::{Response, Router};
+
+/// Prometheus counters and gauges for a crawl, served as plain text on
+/// `GET /metrics` when `--metrics-addr` is given.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pages_visited_total: IntCounter,
+    pages_failed_total: IntCounter,
+    recording_duration_seconds: IntGauge,
+    frames_captured_total: IntCounter,
+    frontier_size: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("siterecorder".to_string()), None)?;
+
+        let pages_visited_total = IntCounter::new("pages_visited_total", "Pages successfully visited")?;
+        let pages_failed_total = IntCounter::new("pages_failed_total", "Pages that failed to load")?;
+        let recording_duration_seconds =
+            IntGauge::new("recording_duration_seconds", "Duration of the current recording in seconds")?;
+        let frames_captured_total = IntCounter::new("frames_captured_total", "Browser frames captured")?;
+        let frontier_size = IntGauge::new("frontier_size", "URLs queued but not yet visited")?;
+
+        registry.register(Box::new(pages_visited_total.clone()))?;
+        registry.register(Box::new(pages_failed_total.clone()))?;
+        registry.register(Box::new(recording_duration_seconds.clone()))?;
+        registry.register(Box::new(frames_captured_total.clone()))?;
+        registry.register(Box::new(frontier_size.clone()))?;
+
+        Ok(Self {
+            registry,
+            pages_visited_total,
+            pages_failed_total,
+            recording_duration_seconds,
+            frames_captured_total,
+            frontier_size,
+        })
+    }
+
+    /// Update counters from a crawl event.
+    pub fn observe(&self, event: &CrawlEvent) {
+        match event {
+            CrawlEvent::PageVisited { pages_remaining, .. } => {
+                self.pages_visited_total.inc();
+                self.frontier_size.set(*pages_remaining as i64);
+            }
+            CrawlEvent::NavigationFailed { .. } => self.pages_failed_total.inc(),
+            CrawlEvent::Recorder(RecorderEvent::Stopped { duration_secs, .. }) => {
+                self.recording_duration_seconds.set(*duration_secs as i64);
+            }
+            CrawlEvent::RecorderStats {
+                duration_secs,
+                frames_captured,
+            } => {
+                self.recording_duration_seconds.set(*duration_secs as i64);
+                let seen = self.frames_captured_total.get();
+                if *frames_captured > seen {
+                    self.frames_captured_total.inc_by(frames_captured - seen);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            warn!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    /// Answer `GET /metrics` (and `GET /`) on `router`.
+    pub fn add_routes(&self, router: &mut Router) {
+        for path in ["/metrics", "/"] {
+            let metrics = self.clone();
+            router.route(path, move || Response {
+                status: "200 OK",
+                content_type: "text/plain; version=0.0.4",
+                body: metrics.render(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_updates_counters() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe(&CrawlEvent::PageVisited {
+            url: "https://example.com".to_string(),
+            pages_visited: 1,
+            pages_discovered: 5,
+            pages_remaining: 4,
+        });
+        metrics.observe(&CrawlEvent::NavigationFailed {
+            url: "https://example.com/broken".to_string(),
+            error: "timeout".to_string(),
+        });
+
+        let text = metrics.render();
+        assert!(text.contains("siterecorder_pages_visited_total 1"));
+        assert!(text.contains("siterecorder_pages_failed_total 1"));
+        assert!(text.contains("siterecorder_frontier_size 4"));
+
+        metrics.observe(&CrawlEvent::RecorderStats {
+            duration_secs: 12,
+            frames_captured: 300,
+        });
+        let text = metrics.render();
+        assert!(text.contains("siterecorder_recording_duration_seconds 12"));
+        assert!(text.contains("siterecorder_frames_captured_total 300"));
+    }
+}
@@ -1,63 +1,238 @@
-use indicatif::{ProgressBar, ProgressStyle};
-use std::cell::Cell;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::events::CrawlEvent;
+
+/// How crawl progress is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressRenderer {
+    /// An interactive bar, redrawn in place.
+    #[default]
+    Bar,
+    /// One JSON object per update on stderr, for CI logs and other non-TTY
+    /// output: `{"pos", "len", "message", "elapsed"}`, elapsed in seconds.
+    Json,
+}
+
+/// Plain-line progress for [`ProgressRenderer::Json`].
+struct JsonProgress {
+    pos: AtomicU64,
+    len: u64,
+    message: Mutex<String>,
+    started: Instant,
+}
+
+impl JsonProgress {
+    fn new(position: u64, len: u64) -> Self {
+        Self {
+            pos: AtomicU64::new(position),
+            len,
+            message: Mutex::new(String::new()),
+            started: Instant::now(),
+        }
+    }
+
+    fn line(&self) -> serde_json::Value {
+        let elapsed = (self.started.elapsed().as_secs_f64() * 1000.0).round() / 1000.0;
+        serde_json::json!({
+            "pos": self.pos.load(Ordering::SeqCst),
+            "len": self.len,
+            "message": *self.message.lock().unwrap_or_else(|e| e.into_inner()),
+            "elapsed": elapsed,
+        })
+    }
+
+    fn emit(&self) {
+        eprintln!("{}", self.line());
+    }
+
+    fn inc(&self) {
+        self.pos.fetch_add(1, Ordering::SeqCst);
+        self.emit();
+    }
+
+    fn set_message(&self, msg: String) {
+        *self.message.lock().unwrap_or_else(|e| e.into_inner()) = msg;
+        self.emit();
+    }
+}
 
 pub struct CrawlProgress {
     bar: Option<ProgressBar>,
-    finished: Cell<bool>,
+    json: Option<JsonProgress>,
+    workers: Vec<WorkerProgress>,
+    // Keeps the bars drawn together; dropped with the progress.
+    _multi: Option<MultiProgress>,
+    finished: AtomicBool,
+}
+
+/// Spinner line for one concurrent crawl worker, showing the URL it is
+/// fetching.
+#[derive(Clone)]
+pub struct WorkerProgress {
+    bar: ProgressBar,
+}
+
+impl WorkerProgress {
+    pub fn set_url(&self, url: &str) {
+        self.bar.set_message(url.to_string());
+        self.bar.tick();
+    }
+
+    pub fn set_idle(&self) {
+        self.bar.set_message("idle");
+    }
+}
+
+fn overall_bar(max_pages: u64) -> ProgressBar {
+    let pb = ProgressBar::new(max_pages);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} pages ({eta})")
+            .expect("Invalid progress bar template")
+            .progress_chars("#>-")
+    );
+    pb
 }
 
 impl CrawlProgress {
-    pub fn new(max_pages: u64, enabled: bool) -> Self {
-        let bar = if enabled {
-            let pb = ProgressBar::new(max_pages);
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} pages ({eta})")
-                    .expect("Invalid progress bar template")
-                    .progress_chars("#>-")
-            );
-            Some(pb)
-        } else {
-            None
+    /// A single page bar starting at `position` pages, which is non-zero
+    /// for a crawl that continues an earlier run so the ETA only counts new
+    /// pages.
+    pub fn new_resumed(position: u64, max_pages: u64, enabled: bool, renderer: ProgressRenderer) -> Self {
+        let (bar, json) = match renderer {
+            _ if !enabled => (None, None),
+            ProgressRenderer::Bar => (Some(overall_bar(max_pages).with_position(position)), None),
+            ProgressRenderer::Json => (None, Some(JsonProgress::new(position, max_pages))),
         };
-
-        Self { 
+        Self {
             bar,
-            finished: Cell::new(false),
+            json,
+            workers: Vec::new(),
+            _multi: None,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// An overall page bar, starting at `position` pages, with one line per
+    /// concurrent worker below it.
+    pub fn new_multi(workers: usize, position: u64, max_pages: u64) -> Self {
+        let multi = MultiProgress::new();
+        let bar = multi.add(overall_bar(max_pages).with_position(position));
+        let style = ProgressStyle::default_spinner()
+            .template("  {spinner:.blue} {prefix}: {wide_msg}")
+            .expect("Invalid progress bar template");
+        let workers = (0..workers)
+            .map(|i| {
+                let worker = multi.add(ProgressBar::new_spinner());
+                worker.set_style(style.clone());
+                worker.set_prefix(format!("worker {}", i + 1));
+                worker.set_message("idle");
+                WorkerProgress { bar: worker }
+            })
+            .collect();
+
+        Self {
+            bar: Some(bar),
+            json: None,
+            workers,
+            _multi: Some(multi),
+            finished: AtomicBool::new(false),
         }
     }
 
+    /// Handle for worker `index`; `None` unless created with
+    /// [`new_multi`](Self::new_multi).
+    pub fn worker(&self, index: usize) -> Option<&WorkerProgress> {
+        self.workers.get(index)
+    }
+
     pub fn inc(&self) {
         if let Some(ref pb) = self.bar {
             pb.inc(1);
         }
+        if let Some(ref json) = self.json {
+            json.inc();
+        }
     }
 
     pub fn finish(&self) {
         // If we've already finished once, don't finish again or clear the message later.
-        if self.finished.replace(true) {
+        if self.finished.swap(true, Ordering::SeqCst) {
             return;
         }
 
+        for worker in &self.workers {
+            worker.bar.finish_and_clear();
+        }
         if let Some(ref pb) = self.bar {
             pb.finish_with_message("✓ Crawl completed");
         }
+        if let Some(ref json) = self.json {
+            json.set_message("Crawl completed".to_string());
+        }
     }
 
     pub fn set_message(&self, msg: String) {
-        if let Some(ref pb) = self.bar {
+        if let Some(ref json) = self.json {
+            json.set_message(msg);
+        } else if let Some(ref pb) = self.bar {
             pb.set_message(msg);
         }
     }
+
+    /// Advance the bars from crawl events.
+    pub fn observe(&self, event: &CrawlEvent) {
+        match event {
+            CrawlEvent::PageStarted { url, .. } => self.set_message(format!("Crawling: {}", url)),
+            CrawlEvent::PageVisited { .. } => self.inc(),
+            CrawlEvent::WorkerFetching { worker, url } => {
+                if let Some(w) = self.worker(*worker) {
+                    w.set_url(url);
+                }
+            }
+            CrawlEvent::WorkerIdle { worker } => {
+                if let Some(w) = self.worker(*worker) {
+                    w.set_idle();
+                }
+            }
+            CrawlEvent::Finished { .. } => self.finish(),
+            _ => {}
+        }
+    }
 }
 
 impl Drop for CrawlProgress {
     fn drop(&mut self) {
-        // Only auto-clear the progress bar if we haven't explicitly finished it.
-        if !self.finished.get() {
+        // Only auto-clear the bars if we haven't explicitly finished them.
+        if !self.finished.load(Ordering::SeqCst) {
+            for worker in &self.workers {
+                worker.bar.finish_and_clear();
+            }
             if let Some(ref pb) = self.bar {
                 pb.finish_and_clear();
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_progress_line() {
+        let progress = JsonProgress::new(3, 50);
+        progress.set_message("Crawling: https://example.com/".to_string());
+        progress.inc();
+
+        let line = progress.line();
+        assert_eq!(line["pos"], 4);
+        assert_eq!(line["len"], 50);
+        assert_eq!(line["message"], "Crawling: https://example.com/");
+        assert!(line["elapsed"].as_f64().is_some());
+        assert!(!line.to_string().contains('\n'));
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use tracing::{error, info};
+
+use crate::cli::CrawlArgs;
+
+/// Parse a cron expression. The usual five fields (minute hour day month
+/// weekday) fire at second 0; six or seven fields start with seconds and may
+/// end with a year.
+pub fn parse_schedule(expr: &str) -> Result<Schedule, String> {
+    let expr = expr.trim();
+    let expr = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    Schedule::from_str(&expr).map_err(|e| format!("invalid cron expression {:?}: {}", expr, e))
+}
+
+/// Output directory of the run scheduled at `at`, inside `base`.
+pub fn run_output_dir(base: &Path, at: DateTime<Local>) -> PathBuf {
+    base.join(at.format("%Y%m%d_%H%M%S").to_string())
+}
+
+/// Longest sleep between checks of the stop flag while waiting for a run.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Run `crawl` at every time of `schedule`, until the process is stopped or
+/// `stop` is set.
+///
+/// Each run writes to its own timestamped directory under `args.output`. A
+/// failed run is logged and the next scheduled time awaited; times that pass
+/// while a run is still going are skipped.
+pub fn run(
+    schedule: &Schedule,
+    args: &CrawlArgs,
+    stop: &AtomicBool,
+    mut crawl: impl FnMut(CrawlArgs) -> Result<()>,
+) -> Result<()> {
+    loop {
+        let next = schedule
+            .upcoming(Local)
+            .next()
+            .context("The schedule has no future run times")?;
+        info!("Next crawl of {} at {}", args.url, next.format("%Y-%m-%d %H:%M:%S"));
+        while let Ok(wait) = (next - Local::now()).to_std() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            std::thread::sleep(wait.min(STOP_CHECK_INTERVAL));
+        }
+        if stop.load(Ordering::SeqCst) {
+            info!("Scheduled crawls stopped");
+            return Ok(());
+        }
+
+        let mut run_args = args.clone();
+        run_args.output = run_output_dir(&args.output, next);
+        info!("Scheduled crawl starting, output in {:?}", run_args.output);
+        match crawl(run_args) {
+            Ok(()) => info!("Scheduled crawl finished"),
+            Err(e) => error!("Scheduled crawl failed: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_schedule() {
+        let every_six_hours = parse_schedule("0 */6 * * *").unwrap();
+        let from = Local.with_ymd_and_hms(2024, 3, 1, 7, 30, 0).unwrap();
+        let next: Vec<_> = every_six_hours.after(&from).take(2).collect();
+        assert_eq!(next[0], Local.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(next[1], Local.with_ymd_and_hms(2024, 3, 1, 18, 0, 0).unwrap());
+
+        assert!(parse_schedule("30 0 9 * * Mon-Fri").is_ok());
+        assert!(parse_schedule("every day").is_err());
+        assert!(parse_schedule("61 * * * *").is_err());
+
+        assert_eq!(
+            run_output_dir(Path::new("out"), from),
+            PathBuf::from("out/20240301_073000")
+        );
+    }
+
+    #[test]
+    fn test_run_stops_on_flag() {
+        let args = crate::cli::Cli::parse_crawl_args(&["https://example.com".to_string()]).unwrap();
+        let stop = AtomicBool::new(true);
+        let mut runs = 0;
+        run(&parse_schedule("* * * * *").unwrap(), &args, &stop, |_| {
+            runs += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(runs, 0);
+    }
+}
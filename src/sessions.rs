@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::SessionSortArg;
+
+/// One row of `site-recorder list`. Everything but the name, start time and
+/// size comes from the session's metadata sidecar and is `None` without one.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    /// Recording start, or the directory's modification time without a sidecar.
+    pub started: Option<DateTime<Utc>>,
+    pub url: Option<String>,
+    pub duration_secs: Option<u64>,
+    pub paused_duration_secs: u64,
+    pub format: Option<String>,
+    /// Entries in the session's `<id>_data.json` export.
+    pub pages: Option<usize>,
+    /// Total size of everything in the session directory.
+    pub size_bytes: u64,
+    pub video: Option<PathBuf>,
+}
+
+impl SessionSummary {
+    /// Summarize a session directory, or `None` if `dir` isn't a directory.
+    pub fn read(dir: &Path) -> Option<Self> {
+        let fs_meta = std::fs::metadata(dir).ok()?;
+        if !fs_meta.is_dir() {
+            return None;
+        }
+        let session_id = dir.file_name()?.to_string_lossy().to_string();
+
+        let recording = std::fs::read_to_string(dir.join(recorder::METADATA_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str::<recorder::RecordingMetadata>(&json).ok());
+        let pages = std::fs::read_to_string(dir.join(format!("{}_data.json", session_id)))
+            .ok()
+            .and_then(|json| exporter::parse_json_export(&json).ok())
+            .map(|pages| pages.len());
+        let modified = fs_meta.modified().ok().map(DateTime::<Utc>::from);
+
+        Some(match recording {
+            Some(recording) => Self {
+                session_id,
+                started: Some(recording.start_time),
+                url: recording.url,
+                duration_secs: recording.duration_secs,
+                paused_duration_secs: recording.paused_duration_secs,
+                format: Some(recording.format.extension().to_string()),
+                pages,
+                size_bytes: dir_size(dir),
+                video: recording.file_path,
+            },
+            None => Self {
+                session_id,
+                started: modified,
+                url: None,
+                duration_secs: None,
+                paused_duration_secs: 0,
+                format: None,
+                pages,
+                size_bytes: dir_size(dir),
+                video: None,
+            },
+        })
+    }
+}
+
+/// Summaries of every session directory under `output`.
+pub fn collect(output: &Path) -> std::io::Result<Vec<SessionSummary>> {
+    Ok(std::fs::read_dir(output)?
+        .flatten()
+        .filter_map(|entry| SessionSummary::read(&entry.path()))
+        .collect())
+}
+
+/// Keep sessions started at or after `since`: the sidecar's start time, or
+/// the directory's modified time for sessions without one.
+pub fn retain_since(sessions: &mut Vec<SessionSummary>, since: DateTime<Utc>) {
+    sessions.retain(|s| s.started.is_some_and(|started| started >= since));
+}
+
+/// Newest, longest or largest first; sessions missing the value go last.
+pub fn sort(sessions: &mut [SessionSummary], by: &SessionSortArg) {
+    match by {
+        SessionSortArg::Date => sessions.sort_by_key(|s| std::cmp::Reverse(s.started)),
+        SessionSortArg::Duration => sessions.sort_by_key(|s| std::cmp::Reverse(s.duration_secs)),
+        SessionSortArg::Size => sessions.sort_by_key(|s| std::cmp::Reverse(s.size_bytes)),
+    }
+}
+
+/// Fixed-width table of `sessions`, one line each, with a header.
+pub fn format_table(sessions: &[SessionSummary]) -> String {
+    let name_width = sessions
+        .iter()
+        .map(|s| s.session_id.len())
+        .chain(std::iter::once("SESSION".len()))
+        .max()
+        .unwrap_or_default();
+    let mut table = format!(
+        "  {:<name_width$}  {:<19}  {:>9}  {:<6}  {:>5}  {:>9}  URL\n",
+        "SESSION", "STARTED", "DURATION", "FORMAT", "PAGES", "SIZE"
+    );
+    for s in sessions {
+        table.push_str(&format!(
+            "  {:<name_width$}  {:<19}  {:>9}  {:<6}  {:>5}  {:>9}  {}\n",
+            s.session_id,
+            s.started.map_or("-".to_string(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            s.duration_secs.map_or("-".to_string(), format_duration),
+            s.format.as_deref().unwrap_or("-"),
+            s.pages.map_or("-".to_string(), |p| p.to_string()),
+            format_size(s.size_bytes),
+            s.url.as_deref().unwrap_or("-"),
+        ));
+    }
+    table
+}
+
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_session_with_and_without_sidecar() {
+        let root = std::env::temp_dir().join(format!("site-recorder-list-{}", std::process::id()));
+        let with = root.join("abc");
+        let without = root.join("old");
+        std::fs::create_dir_all(&with).unwrap();
+        std::fs::create_dir_all(&without).unwrap();
+        std::fs::write(
+            with.join(recorder::METADATA_FILE),
+            serde_json::json!({
+                "session_id": "abc",
+                "url": "https://example.com",
+                "start_time": "2024-01-02T03:04:05Z",
+                "end_time": null,
+                "duration_secs": 125,
+                "file_path": null,
+                "format": "Webm"
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let record = exporter::RecordingData {
+            session_id: "abc".to_string(),
+            timestamp: Utc::now(),
+            url: "https://example.com".to_string(),
+            action: "navigate".to_string(),
+            metadata: serde_json::json!({}),
+            success: true,
+        };
+        exporter::Exporter::new()
+            .export(&vec![record; 3], with.join("abc_data.json"), exporter::ExportFormat::Json)
+            .unwrap();
+
+        let mut sessions = collect(&root).unwrap();
+        sort(&mut sessions, &SessionSortArg::Duration);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        let rich = &sessions[0];
+        assert_eq!(rich.session_id, "abc");
+        assert_eq!(rich.url.as_deref(), Some("https://example.com"));
+        assert_eq!(rich.format.as_deref(), Some("webm"));
+        assert_eq!(rich.pages, Some(3));
+        assert!(rich.size_bytes > 0);
+
+        let bare = &sessions[1];
+        assert_eq!(bare.session_id, "old");
+        assert!(bare.started.is_some());
+        assert_eq!((bare.duration_secs, bare.pages), (None, None));
+
+        let table = format_table(&sessions);
+        assert!(table.contains("2m 05s"));
+        assert!(table.lines().nth(2).unwrap().starts_with("  old "));
+
+        // The sidecar's start time wins over the directory's modified time
+        let since = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        retain_since(&mut sessions, since);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "old");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
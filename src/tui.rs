@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::{Frame, Terminal};
+use recorder::RecorderEvent;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::events::CrawlEvent;
+
+const LOG_CAPACITY: usize = 500;
+const TICK: Duration = Duration::from_millis(200);
+
+/// Live terminal dashboard for foreground crawls.
+///
+/// Runs on its own thread, rendering [`CrawlEvent`]s as they arrive.
+/// Pressing `q` (or Ctrl+C) sets the shared stop flag, which the crawl loop
+/// treats exactly like a shutdown signal.
+pub struct CrawlDashboard {
+    handle: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl CrawlDashboard {
+    pub fn spawn(
+        max_pages: usize,
+        events: UnboundedReceiver<CrawlEvent>,
+        stop_requested: Arc<AtomicBool>,
+    ) -> Self {
+        let handle = std::thread::spawn(move || run(max_pages, events, stop_requested));
+        Self { handle: Some(handle) }
+    }
+
+    /// Wait for the dashboard to exit and restore the terminal.
+    pub fn join(mut self) {
+        self.join_inner();
+    }
+
+    fn join_inner(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            match handle.join() {
+                Ok(Err(e)) => eprintln!("TUI error: {}", e),
+                Err(_) => eprintln!("TUI thread panicked"),
+                Ok(Ok(())) => {}
+            }
+        }
+    }
+}
+
+impl Drop for CrawlDashboard {
+    fn drop(&mut self) {
+        self.join_inner();
+    }
+}
+
+struct DashboardState {
+    max_pages: usize,
+    started: Instant,
+    current_url: String,
+    visited: usize,
+    discovered: usize,
+    remaining: usize,
+    recording: String,
+    log: VecDeque<String>,
+    finished: bool,
+    stopping: bool,
+}
+
+impl DashboardState {
+    fn new(max_pages: usize) -> Self {
+        Self {
+            max_pages,
+            started: Instant::now(),
+            current_url: String::from("-"),
+            visited: 0,
+            discovered: 0,
+            remaining: 0,
+            recording: String::from("idle"),
+            log: VecDeque::with_capacity(LOG_CAPACITY),
+            finished: false,
+            stopping: false,
+        }
+    }
+
+    fn push_log(&mut self, line: String) {
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        let stamp = chrono::Local::now().format("%H:%M:%S");
+        self.log.push_back(format!("{} {}", stamp, line));
+    }
+
+    fn apply(&mut self, event: CrawlEvent) {
+        match event {
+            CrawlEvent::PageStarted { url, page_number } => {
+                self.push_log(format!("[{}/{}] {}", page_number, self.max_pages, url));
+                self.current_url = url;
+            }
+            CrawlEvent::PageVisited {
+                pages_visited,
+                pages_discovered,
+                pages_remaining,
+                ..
+            } => {
+                self.visited = pages_visited;
+                self.discovered = pages_discovered;
+                self.remaining = pages_remaining;
+            }
+            CrawlEvent::LinksFound { count, .. } => {
+                self.push_log(format!("  found {} links", count));
+            }
+            CrawlEvent::NavigationFailed { url, error } => {
+                self.push_log(format!("  failed {}: {}", url, error));
+            }
+            CrawlEvent::Recorder(event) => match event {
+                RecorderEvent::Started { mode, file_path, .. } => {
+                    self.recording = format!("recording ({:?})", mode);
+                    self.push_log(format!("recording started: {}", file_path.display()));
+                }
+                RecorderEvent::Stopped { duration_secs, file_path, .. } => {
+                    self.recording = format!("stopped after {}s", duration_secs);
+                    self.push_log(format!("recording saved: {}", file_path.display()));
+                }
+                RecorderEvent::Paused { .. } => {
+                    self.recording = String::from("paused");
+                    self.push_log("recording paused".to_string());
+                }
+                RecorderEvent::Resumed { paused_secs, .. } => {
+                    self.recording = String::from("recording");
+                    self.push_log(format!("recording resumed after {}s", paused_secs));
+                }
+                RecorderEvent::Error { message } => {
+                    self.recording = String::from("error");
+                    self.push_log(format!("recorder error: {}", message));
+                }
+            },
+            CrawlEvent::RecorderStats { .. } | CrawlEvent::WorkerFetching { .. } | CrawlEvent::WorkerIdle { .. } => {}
+            CrawlEvent::Error { message } => {
+                self.push_log(format!("error: {}", message));
+            }
+            CrawlEvent::Finished { pages_visited } => {
+                self.push_log(format!("crawl finished: {} pages", pages_visited));
+                self.finished = true;
+            }
+        }
+    }
+}
+
+fn run(
+    max_pages: usize,
+    mut events: UnboundedReceiver<CrawlEvent>,
+    stop_requested: Arc<AtomicBool>,
+) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = event_loop(&mut terminal, max_pages, &mut events, &stop_requested);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    max_pages: usize,
+    events: &mut UnboundedReceiver<CrawlEvent>,
+    stop_requested: &AtomicBool,
+) -> io::Result<()> {
+    let mut state = DashboardState::new(max_pages);
+
+    loop {
+        loop {
+            match events.try_recv() {
+                Ok(event) => state.apply(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    state.finished = true;
+                    break;
+                }
+            }
+        }
+
+        terminal.draw(|f| draw(f, &state))?;
+
+        if state.finished {
+            return Ok(());
+        }
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (key.code == KeyCode::Char('q') || ctrl_c) && !state.stopping {
+                    stop_requested.store(true, Ordering::SeqCst);
+                    state.stopping = true;
+                    state.push_log("stop requested, finishing current page...".to_string());
+                }
+            }
+        }
+    }
+}
+
+fn draw(f: &mut Frame, state: &DashboardState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+
+    let current = Paragraph::new(state.current_url.as_str())
+        .block(Block::default().borders(Borders::ALL).title("Current URL"));
+    f.render_widget(current, rows[0]);
+
+    let elapsed = state.started.elapsed().as_secs();
+    let counters = vec![
+        Line::from(vec![
+            Span::styled("Visited: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{}/{}   ", state.visited, state.max_pages)),
+            Span::styled("Discovered: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{}   ", state.discovered)),
+            Span::styled("Remaining: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(state.remaining.to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled("Elapsed: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{:02}:{:02}:{:02}   ", elapsed / 3600, (elapsed / 60) % 60, elapsed % 60)),
+            Span::styled("Recording: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(state.recording.as_str(), Style::default().fg(Color::Red)),
+        ]),
+    ];
+    let stats = Paragraph::new(counters).block(Block::default().borders(Borders::ALL).title("Progress"));
+    f.render_widget(stats, rows[1]);
+
+    // Show the tail of the log that fits in the panel.
+    let visible = rows[2].height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = state
+        .log
+        .iter()
+        .skip(state.log.len().saturating_sub(visible))
+        .map(|l| ListItem::new(l.as_str()))
+        .collect();
+    let log = List::new(items).block(Block::default().borders(Borders::ALL).title("Log"));
+    f.render_widget(log, rows[2]);
+
+    let help = if state.stopping {
+        "Stopping..."
+    } else {
+        "q: stop crawl"
+    };
+    f.render_widget(Paragraph::new(help).style(Style::default().fg(Color::DarkGray)), rows[3]);
+}
@@ -3,13 +3,13 @@ let invoke;
 
 // DOM Elements (will be initialized in DOMContentLoaded)
 let urlInput, maxPagesInput, delayInput, outputDirInput, headlessCheckbox;
-let startBtn, stopBtn, recordingState, sessionId, currentUrl;
+let startBtn, stopBtn, pauseBtn, recordingState, sessionId, currentUrl;
 let pagesVisited, pagesDiscovered, progressBar, logContainer;
 let requiresAuthCheckbox, authFields, authUrl, username, password;
 let usernameSelector, passwordSelector, submitSelector;
 let loginScriptFile, loginScript;
 let recordingModeSelect, enableAudioCheckbox, screenWidthInput, screenHeightInput;
-let concurrencyInput;
+let concurrencyInput, organizeBySessionCheckbox, discordWebhookInput;
 let regionXInput, regionYInput, regionWInput, regionHInput;
 
 let statusInterval = null;
@@ -38,7 +38,10 @@ async function updateStatus() {
     try {
         const status = await invoke('get_status');
         
-        if (status.is_running) {
+        if (status.is_running && status.paused) {
+            recordingState.textContent = 'Paused';
+            recordingState.classList.remove('recording');
+        } else if (status.is_running) {
             recordingState.textContent = 'Recording';
             recordingState.classList.add('recording');
         } else {
@@ -95,6 +98,8 @@ async function startRecording() {
               ]
             : null,
         concurrency: parseInt(concurrencyInput.value) || 1,
+        organize_by_session: organizeBySessionCheckbox.checked,
+        discord_webhook: discordWebhookInput.value.trim() || null,
         proxy: null,
         sitemap: null,
         scan_url: null
@@ -138,6 +143,8 @@ async function startRecording() {
         // Update UI
         startBtn.disabled = true;
         stopBtn.disabled = false;
+        pauseBtn.disabled = false;
+        setPauseButton(false);
         disableInputs(true);
         
         // Start status polling
@@ -158,6 +165,8 @@ async function stopRecording() {
         // Update UI
         startBtn.disabled = false;
         stopBtn.disabled = true;
+        pauseBtn.disabled = true;
+        setPauseButton(false);
         disableInputs(false);
         
         // Stop status polling
@@ -174,6 +183,113 @@ async function stopRecording() {
     }
 }
 
+// Warn early when FFmpeg is missing instead of failing at start
+async function checkFfmpeg() {
+    try {
+        const version = await invoke('check_ffmpeg');
+        console.log(`FFmpeg ${version} available`);
+    } catch (error) {
+        addLog(`${error}`, 'error');
+        addLog('Install FFmpeg (https://ffmpeg.org/download.html) for screen recording and video export. Browser frames will still be saved.', 'warning');
+    }
+}
+
+// Pause or resume the running crawl and its recording
+let isPaused = false;
+
+function setPauseButton(paused) {
+    isPaused = paused;
+    pauseBtn.textContent = paused ? '▶️ Resume' : '⏸️ Pause';
+}
+
+async function togglePause() {
+    try {
+        if (isPaused) {
+            await invoke('resume_recording');
+            addLog('Recording resumed', 'info');
+        } else {
+            await invoke('pause_recording');
+            addLog('Pausing after the current page...', 'warning');
+        }
+        setPauseButton(!isPaused);
+        await updateStatus();
+    } catch (error) {
+        addLog(`Failed to toggle pause: ${error}`, 'error');
+    }
+}
+
+// Subscribe to live crawl events emitted by the backend
+async function setupCrawlEvents() {
+    const events = window.__TAURI__.event;
+    if (!events || !events.listen) {
+        console.warn('Tauri event API not available, relying on status polling');
+        return;
+    }
+
+    await events.listen('crawl://page', (event) => {
+        const { type, data } = event.payload;
+        if (type === 'page_started') {
+            currentUrl.textContent = data.url;
+            addLog(`Visiting page ${data.page_number}: ${data.url}`, 'info');
+        } else if (type === 'page_visited') {
+            pagesVisited.textContent = data.pages_visited;
+            pagesDiscovered.textContent = data.pages_discovered;
+            const progress = (data.pages_visited / Math.max(data.pages_discovered, 1)) * 100;
+            progressBar.style.width = `${Math.min(progress, 100)}%`;
+        }
+    });
+
+    await events.listen('crawl://links', (event) => {
+        addLog(`Found ${event.payload.data.count} links`, 'info');
+    });
+
+    await events.listen('crawl://recording', (event) => {
+        const recorderEvent = event.payload.data;
+        if (recorderEvent.type === 'started') {
+            recordingState.textContent = 'Recording';
+            recordingState.classList.add('recording');
+            addLog(`Recording started (${recorderEvent.mode})`, 'success');
+        } else if (recorderEvent.type === 'stopped') {
+            recordingState.textContent = 'Idle';
+            recordingState.classList.remove('recording');
+            addLog(`Recording saved to ${recorderEvent.file_path} (${recorderEvent.duration_secs}s)`, 'success');
+        } else if (recorderEvent.type === 'paused') {
+            recordingState.textContent = 'Paused';
+            recordingState.classList.remove('recording');
+            addLog('Recording paused', 'warning');
+        } else if (recorderEvent.type === 'resumed') {
+            recordingState.textContent = 'Recording';
+            recordingState.classList.add('recording');
+            addLog(`Recording resumed after ${recorderEvent.paused_secs}s`, 'info');
+        } else if (recorderEvent.type === 'error') {
+            addLog(`Recorder error: ${recorderEvent.message}`, 'error');
+        }
+    });
+
+    await events.listen('crawl://error', (event) => {
+        const { type, data } = event.payload;
+        if (type === 'navigation_failed') {
+            addLog(`Failed to load ${data.url}: ${data.error}`, 'warning');
+        } else {
+            addLog(`Recording failed: ${data.message}`, 'error');
+        }
+    });
+
+    await events.listen('crawl://finished', (event) => {
+        addLog(`Crawl finished: ${event.payload.data.pages_visited} pages visited`, 'success');
+        startBtn.disabled = false;
+        stopBtn.disabled = true;
+        pauseBtn.disabled = true;
+        setPauseButton(false);
+        disableInputs(false);
+        if (statusInterval) {
+            clearInterval(statusInterval);
+            statusInterval = null;
+        }
+        updateStatus();
+    });
+}
+
 // Disable/enable inputs
 function disableInputs(disabled) {
     urlInput.disabled = disabled;
@@ -181,6 +297,8 @@ function disableInputs(disabled) {
     delayInput.disabled = disabled;
     outputDirInput.disabled = disabled;
     headlessCheckbox.disabled = disabled;
+    organizeBySessionCheckbox.disabled = disabled;
+    discordWebhookInput.disabled = disabled;
 }
 
 // ==================== VULNERABILITY SCANNER ====================
@@ -823,6 +941,7 @@ document.addEventListener('DOMContentLoaded', async () => {
     headlessCheckbox = document.getElementById('headless');
     startBtn = document.getElementById('startBtn');
     stopBtn = document.getElementById('stopBtn');
+    pauseBtn = document.getElementById('pauseBtn');
     recordingState = document.getElementById('recordingState');
     sessionId = document.getElementById('sessionId');
     currentUrl = document.getElementById('currentUrl');
@@ -849,6 +968,8 @@ document.addEventListener('DOMContentLoaded', async () => {
     screenWidthInput = document.getElementById('screenWidth');
     screenHeightInput = document.getElementById('screenHeight');
     concurrencyInput = document.getElementById('concurrency');
+    organizeBySessionCheckbox = document.getElementById('organizeBySession');
+    discordWebhookInput = document.getElementById('discordWebhook');
     regionXInput = document.getElementById('regionX');
     regionYInput = document.getElementById('regionY');
     regionWInput = document.getElementById('regionW');
@@ -876,6 +997,12 @@ document.addEventListener('DOMContentLoaded', async () => {
     
     // Setup tabs
     setupTabs();
+
+    // Live crawl progress from the backend
+    await setupCrawlEvents();
+
+    // Screen recording and frame encoding need FFmpeg
+    await checkFfmpeg();
     
     // Attach event listeners after DOM is ready
     startBtn.addEventListener('click', () => {
@@ -886,6 +1013,7 @@ document.addEventListener('DOMContentLoaded', async () => {
         console.log('Stop button clicked!');
         stopRecording();
     });
+    pauseBtn.addEventListener('click', togglePause);
     
     // Directory picker button
     const selectDirBtn = document.getElementById('selectDirBtn');
@@ -935,6 +1063,10 @@ document.addEventListener('DOMContentLoaded', async () => {
                 description = 'Browser screenshots only - Lower resource usage';
                 enableAudioCheckbox.disabled = true;
                 enableAudioCheckbox.checked = false;
+            } else if (mode === 'none') {
+                description = 'No recording - Crawl and export data only, FFmpeg not required';
+                enableAudioCheckbox.disabled = true;
+                enableAudioCheckbox.checked = false;
             } else { // both
                 description = 'Both modes - Complete session coverage (recommended)';
                 enableAudioCheckbox.disabled = false;